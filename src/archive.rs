@@ -0,0 +1,731 @@
+//! Archives of every entity in a store, which can be restored into any other initialized store
+//! (even one which uses a different adapter) as a backup.
+//!
+//! Restoring an [`Archive`] creates new entities rather than reusing the `id`s in the archive, so
+//! the references between entities are remapped onto the `id`s which were created (or onto equal
+//! entities which already [exist](Archive::restore) in the store).
+
+use core::iter;
+use std::{collections::BTreeMap, mem, path::Path};
+
+use money2::Money;
+use serde::{Deserialize, Serialize};
+use sqlx::{Database, Executor, Pool, Transaction};
+use thiserror::Error;
+use tokio::fs;
+use winvoice_adapter::{
+	schema::{ContactAdapter, EmployeeAdapter, JobAdapter, LocationAdapter, OrganizationAdapter, TimesheetAdapter},
+	Deletable,
+};
+use winvoice_schema::{
+	chrono::{DateTime, Utc},
+	Contact,
+	ContactKind,
+	Employee,
+	Expense,
+	Id,
+	Job,
+	Location,
+	Organization,
+	Timesheet,
+};
+
+use crate::{
+	discounts::Discounts,
+	export::{self, Discount},
+	numbering::Register,
+	payments::{Ledger, Payment},
+	DynResult,
+};
+
+/// The version of the [`Archive`] format which this version of Winvoice writes.
+///
+/// It must be incremented whenever the format changes, so that older versions of Winvoice refuse
+/// to restore archives which they would misinterpret.
+pub const VERSION: u32 = 3;
+
+/// An [`Error`](std::error::Error) for reading or restoring an [`Archive`].
+#[derive(Clone, Debug, Eq, Error, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Error
+{
+	#[allow(missing_docs)]
+	#[error("The archive refers to {kind} #{id}, which it does not contain")]
+	Missing
+	{
+		kind: &'static str, id: Id
+	},
+
+	#[allow(missing_docs)]
+	#[error("The invoice number(s) {} are already assigned to other Jobs in the store", .0.join(", "))]
+	NumbersTaken(Vec<String>),
+
+	#[allow(missing_docs)]
+	#[error("The archive is version {0}, but this version of Winvoice can only restore up to version {VERSION}")]
+	UnsupportedVersion(u32),
+}
+
+/// Every entity in a store, in the order that they must be created.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Archive
+{
+	/// The [`VERSION`] of the format which the archive was written in.
+	pub version: u32,
+
+	/// Every `Location`, each of which comes after the `Location` it is inside of.
+	pub locations: Vec<Location>,
+
+	/// Every `Organization`.
+	pub organizations: Vec<Organization>,
+
+	/// Every `Contact`.
+	pub contacts: Vec<Contact>,
+
+	/// Every `Employee`.
+	pub employees: Vec<Employee>,
+
+	/// Every `Job`.
+	pub jobs: Vec<Job>,
+
+	/// Every `Timesheet`, without its `expenses`.
+	pub timesheets: Vec<Timesheet>,
+
+	/// Every `Expense`.
+	pub expenses: Vec<Expense>,
+
+	/// Every [`Payment`].
+	#[serde(default)]
+	pub payments: Vec<Payment>,
+
+	/// The [`Discount`] of every invoice which has one, by the `id` of its `Job`.
+	#[serde(default)]
+	pub discounts: BTreeMap<Id, Discount>,
+
+	/// The number of every invoice which has been assigned one, by the `id` of its `Job`.
+	#[serde(default)]
+	pub invoice_numbers: BTreeMap<Id, String>,
+
+	/// The last number of every [`series`](crate::numbering::Pattern::series) of invoice numbers.
+	#[serde(default)]
+	pub invoice_series: BTreeMap<String, i64>,
+
+	/// The date that every invoice which has been issued was due by when it was issued, by the `id`
+	/// of its `Job`.
+	#[serde(default)]
+	pub invoice_due_dates: BTreeMap<Id, Option<DateTime<Utc>>>,
+}
+
+/// The part of an [`Archive`] which is read before the rest, so that the [`VERSION`] can be
+/// checked before the format is relied upon.
+#[derive(Deserialize)]
+struct Header
+{
+	/// See [`Archive::version`].
+	version: u32,
+}
+
+/// The number of `Location`s which the `location` is inside of.
+fn depth(location: &Location) -> usize
+{
+	iter::successors(location.outer.as_deref(), |l| l.outer.as_deref()).count()
+}
+
+/// Get the entity which replaced the one with some `id` of some `kind`.
+fn remapped<T>(created: &BTreeMap<Id, T>, kind: &'static str, id: Id) -> Result<T, Error>
+where
+	T: Clone,
+{
+	created.get(&id).cloned().ok_or(Error::Missing { kind, id })
+}
+
+impl Archive
+{
+	/// The number of each kind of entity in the [`Archive`].
+	pub fn counts(&self) -> [(&'static str, usize); 11]
+	{
+		[
+			("Location", self.locations.len()),
+			("Organization", self.organizations.len()),
+			("Contact", self.contacts.len()),
+			("Employee", self.employees.len()),
+			("Job", self.jobs.len()),
+			("Timesheet", self.timesheets.len()),
+			("Expense", self.expenses.len()),
+			("Payment", self.payments.len()),
+			("discount", self.discounts.len()),
+			("invoice number", self.invoice_numbers.len()),
+			("invoice due date", self.invoice_due_dates.len()),
+		]
+	}
+
+	/// An [`Archive`] of some `jobs` and their `timesheets` and `payments`, along with the client of
+	/// each `Job`, the `Location`s of those clients, and the `Employee` of each `Timesheet`.
+	pub fn of_jobs(jobs: Vec<Job>, mut timesheets: Vec<Timesheet>, payments: Vec<Payment>) -> Self
+	{
+		let mut locations = BTreeMap::new();
+		let mut organizations = BTreeMap::new();
+		jobs.iter().for_each(|j| {
+			organizations.entry(j.client.id).or_insert_with(|| j.client.clone());
+			iter::successors(Some(&j.client.location), |l| l.outer.as_deref()).for_each(|l| {
+				locations.entry(l.id).or_insert_with(|| l.clone());
+			});
+		});
+
+		let employees: BTreeMap<_, _> = timesheets.iter().map(|t| (t.employee.id, t.employee.clone())).collect();
+		let expenses = timesheets.iter_mut().flat_map(|t| mem::take(&mut t.expenses)).collect();
+
+		let mut locations: Vec<_> = locations.into_values().collect();
+		locations.sort_by_key(|l| (depth(l), l.id));
+
+		Self {
+			version: VERSION,
+			locations,
+			organizations: organizations.into_values().collect(),
+			contacts: Vec::new(),
+			employees: employees.into_values().collect(),
+			jobs,
+			timesheets,
+			expenses,
+			payments,
+			discounts: BTreeMap::new(),
+			invoice_numbers: BTreeMap::new(),
+			invoice_series: BTreeMap::new(),
+			invoice_due_dates: BTreeMap::new(),
+		}
+	}
+
+	/// Read an [`Archive`] from the file at `filepath`, which is JSON if its extension is `.json`,
+	/// and YAML otherwise.
+	///
+	/// # Errors
+	///
+	/// * [`Error::UnsupportedVersion`], if the archive was written by a newer version of Winvoice.
+	/// * If the file could not be read or deserialized.
+	pub async fn read(filepath: &Path) -> DynResult<Self>
+	{
+		let contents = fs::read_to_string(filepath).await?;
+		let json = is_json(filepath);
+
+		let header: Header = match json
+		{
+			false => serde_yaml::from_str(&contents)?,
+			true => serde_json::from_str(&contents)?,
+		};
+
+		if header.version > VERSION
+		{
+			return Err(Error::UnsupportedVersion(header.version).into());
+		}
+
+		Ok(match json
+		{
+			false => serde_yaml::from_str(&contents)?,
+			true => serde_json::from_str(&contents)?,
+		})
+	}
+
+	/// Retrieve the entities in the store which the `connection` is to that an [`Archive`] may be
+	/// [restored](Archive::restore) onto: every `Location`, `Organization`, `Employee`, and `Job`,
+	/// and the `invoice_numbers` of those `Job`s.
+	pub async fn existing<EAdapter, JAdapter, LAdapter, OAdapter, Db>(connection: &Pool<Db>) -> DynResult<Self>
+	where
+		EAdapter: Deletable<Db = Db> + EmployeeAdapter,
+		JAdapter: Deletable<Db = Db> + JobAdapter,
+		LAdapter: Deletable<Db = Db> + LocationAdapter,
+		OAdapter: Deletable<Db = Db> + OrganizationAdapter,
+		Db: Database + Register,
+	{
+		let (locations, organizations, employees, jobs) = futures::try_join!(
+			LAdapter::retrieve(connection, Default::default()),
+			OAdapter::retrieve(connection, Default::default()),
+			EAdapter::retrieve(connection, Default::default()),
+			JAdapter::retrieve(connection, Default::default()),
+		)?;
+
+		let job_ids: Vec<_> = jobs.iter().map(|j| j.id).collect();
+		let invoice_numbers = Db::retrieve_numbers(&mut *connection.acquire().await?, &job_ids).await?;
+
+		Ok(Self { version: VERSION, locations, organizations, employees, jobs, invoice_numbers, ..Default::default() })
+	}
+
+	/// Create every entity in the [`Archive`] using the `transaction`, remapping the references
+	/// between them onto the entities which were created.
+	///
+	/// `Location`s, `Organization`s, and `Employee`s which are equal to one of the `existing`
+	/// entities (besides their `id`, and with their references remapped) are not created, and
+	/// references to them are remapped onto the `existing` entity instead. `Job`s which are equal
+	/// to one of the `existing` ones are skipped along with their `Timesheet`s, payments, and
+	/// invoice details, since they must have been restored already.
+	///
+	/// Returns an [`Archive`] of the entities which were created, and the `existing` `Job`s which
+	/// were skipped.
+	///
+	/// # Errors
+	///
+	/// * [`Error::Missing`], if an entity refers to another which is not in the [`Archive`].
+	/// * [`Error::NumbersTaken`], if any of the `invoice_numbers` of the `Job`s which would be created are already
+	///   assigned to one of the `existing` `Job`s. This is checked before any `Job` is created.
+	/// * If any entity could not be created.
+	pub async fn restore<CAdapter, EAdapter, JAdapter, LAdapter, OAdapter, TAdapter, Db>(
+		self,
+		transaction: &mut Transaction<'_, Db>,
+		existing: &Self,
+	) -> DynResult<(Self, Vec<Job>)>
+	where
+		CAdapter: Deletable<Db = Db> + ContactAdapter,
+		EAdapter: Deletable<Db = Db> + EmployeeAdapter,
+		JAdapter: Deletable<Db = Db> + JobAdapter,
+		LAdapter: Deletable<Db = Db> + LocationAdapter,
+		OAdapter: Deletable<Db = Db> + OrganizationAdapter,
+		TAdapter: Deletable<Db = Db> + TimesheetAdapter,
+		Db: Database + Discounts + Ledger + Register,
+		for<'connection> &'connection mut Db::Connection: Executor<'connection, Database = Db>,
+	{
+		let mut restored = Self { version: VERSION, ..Default::default() };
+
+		let mut locations = BTreeMap::<_, Location>::new();
+		let mut sorted = self.locations;
+		sorted.sort_by_key(depth);
+		for l in sorted
+		{
+			let outer = l.outer.map(|o| remapped(&locations, "Location", o.id)).transpose()?;
+			let location = match existing
+				.locations
+				.iter()
+				.find(|e| e.name == l.name && e.outer.as_ref().map(|o| o.id) == outer.as_ref().map(|o| o.id))
+			{
+				Some(e) => e.clone(),
+				None =>
+				{
+					let created = LAdapter::create(&mut **transaction, l.name, outer).await?;
+					restored.locations.push(created.clone());
+					created
+				},
+			};
+
+			locations.insert(l.id, location);
+		}
+
+		let mut organizations = BTreeMap::new();
+		for o in self.organizations
+		{
+			let location = remapped(&locations, "Location", o.location.id)?;
+			let organization =
+				match existing.organizations.iter().find(|e| e.name == o.name && e.location.id == location.id)
+				{
+					Some(e) => e.clone(),
+					None =>
+					{
+						let created = OAdapter::create(&mut **transaction, location, o.name).await?;
+						restored.organizations.push(created.clone());
+						created
+					},
+				};
+
+			organizations.insert(o.id, organization);
+		}
+
+		for c in self.contacts
+		{
+			let kind = match c.kind
+			{
+				ContactKind::Address(l) => ContactKind::Address(remapped(&locations, "Location", l.id)?),
+				k => k,
+			};
+
+			restored.contacts.push(CAdapter::create(&mut **transaction, kind, c.label).await?);
+		}
+
+		let mut employees = BTreeMap::new();
+		for e in self.employees
+		{
+			let employee = match existing.employees.iter().find(|x| Employee { id: x.id, ..e.clone() } == **x)
+			{
+				Some(x) => x.clone(),
+				None =>
+				{
+					let created = EAdapter::create(&mut **transaction, e.name, e.status, e.title).await?;
+					restored.employees.push(created.clone());
+					created
+				},
+			};
+
+			employees.insert(e.id, employee);
+		}
+
+		let mut jobs = BTreeMap::new();
+		let mut skipped = BTreeMap::new();
+		let mut to_create = Vec::with_capacity(self.jobs.len());
+		for j in self.jobs
+		{
+			let client = remapped(&organizations, "Organization", j.client.id)?;
+			match existing
+				.jobs
+				.iter()
+				.find(|x| x.client.id == client.id && Job { client: x.client.clone(), id: x.id, ..j.clone() } == **x)
+			{
+				Some(x) =>
+				{
+					jobs.insert(j.id, x.clone());
+					skipped.insert(j.id, x.clone());
+				},
+				None => to_create.push((client, j)),
+			}
+		}
+
+		let taken: Vec<_> = self
+			.invoice_numbers
+			.iter()
+			.filter(|(id, number)| !skipped.contains_key(id) && existing.invoice_numbers.values().any(|n| n == *number))
+			.map(|(_, number)| number.clone())
+			.collect();
+
+		if !taken.is_empty()
+		{
+			return Err(Error::NumbersTaken(taken).into());
+		}
+
+		for (client, j) in to_create
+		{
+			let created = JAdapter::create(
+				&mut **transaction,
+				client,
+				j.date_close,
+				j.date_open,
+				j.increment,
+				j.invoice,
+				j.notes,
+				j.objectives,
+			)
+			.await?;
+
+			jobs.insert(j.id, created.clone());
+			restored.jobs.push(created);
+		}
+
+		let mut expenses = BTreeMap::<_, Vec<(String, Money, String)>>::new();
+		self.expenses
+			.into_iter()
+			.for_each(|x| expenses.entry(x.timesheet_id).or_default().push((x.category, x.cost, x.description)));
+
+		for t in self.timesheets
+		{
+			if skipped.contains_key(&t.job.id)
+			{
+				expenses.remove(&t.id);
+				continue;
+			}
+
+			let mut created = TAdapter::create(
+				&mut *transaction,
+				remapped(&employees, "Employee", t.employee.id)?,
+				expenses.remove(&t.id).unwrap_or_default(),
+				remapped(&jobs, "Job", t.job.id)?,
+				t.time_begin,
+				t.time_end,
+				t.work_notes,
+			)
+			.await?;
+
+			restored.expenses.append(&mut created.expenses);
+			restored.timesheets.push(created);
+		}
+
+		if let Some((&id, _)) = expenses.iter().next()
+		{
+			return Err(Error::Missing { kind: "Timesheet", id }.into());
+		}
+
+		for p in self.payments.into_iter().filter(|p| !skipped.contains_key(&p.job_id))
+		{
+			let job = remapped(&jobs, "Job", p.job_id)?;
+			restored.payments.push(Db::create_payment(&mut **transaction, job.id, p.amount, p.date, p.notes).await?);
+		}
+
+		for (id, discount) in self.discounts.into_iter().filter(|(id, _)| !skipped.contains_key(id))
+		{
+			let job = remapped(&jobs, "Job", id)?;
+			Db::create_discount(&mut **transaction, job.id, discount).await?;
+			restored.discounts.insert(job.id, discount);
+		}
+
+		for (id, number) in self.invoice_numbers.into_iter().filter(|(id, _)| !skipped.contains_key(id))
+		{
+			let job = remapped(&jobs, "Job", id)?;
+			Db::create_number(&mut **transaction, job.id, &number).await?;
+			restored.invoice_numbers.insert(job.id, number);
+		}
+
+		for (id, due) in self.invoice_due_dates.into_iter().filter(|(id, _)| !skipped.contains_key(id))
+		{
+			let job = remapped(&jobs, "Job", id)?;
+			Db::create_due_date(&mut **transaction, job.id, due).await?;
+			restored.invoice_due_dates.insert(job.id, due);
+		}
+
+		Db::update_series(&mut **transaction, &self.invoice_series).await?;
+		restored.invoice_series = self.invoice_series;
+
+		Ok((restored, skipped.into_values().collect()))
+	}
+
+	/// Retrieve every entity in the store which the `connection` is to.
+	pub async fn retrieve<CAdapter, EAdapter, JAdapter, LAdapter, OAdapter, TAdapter, Db>(
+		connection: &Pool<Db>,
+	) -> DynResult<Self>
+	where
+		CAdapter: Deletable<Db = Db> + ContactAdapter,
+		EAdapter: Deletable<Db = Db> + EmployeeAdapter,
+		JAdapter: Deletable<Db = Db> + JobAdapter,
+		LAdapter: Deletable<Db = Db> + LocationAdapter,
+		OAdapter: Deletable<Db = Db> + OrganizationAdapter,
+		TAdapter: Deletable<Db = Db> + TimesheetAdapter,
+		Db: Database + Discounts + Ledger + Register,
+	{
+		let (mut locations, mut organizations, contacts, mut employees, mut jobs, mut timesheets) = futures::try_join!(
+			LAdapter::retrieve(connection, Default::default()),
+			OAdapter::retrieve(connection, Default::default()),
+			CAdapter::retrieve(connection, Default::default()),
+			EAdapter::retrieve(connection, Default::default()),
+			JAdapter::retrieve(connection, Default::default()),
+			TAdapter::retrieve(connection, Default::default()),
+		)?;
+
+		locations.sort_by_key(|l| (depth(l), l.id));
+		organizations.sort_by_key(|o| o.id);
+		employees.sort_by_key(|e| e.id);
+		jobs.sort_by_key(|j| j.id);
+		timesheets.sort_by_key(|t| t.id);
+
+		let mut expenses: Vec<_> = timesheets.iter_mut().flat_map(|t| mem::take(&mut t.expenses)).collect();
+		expenses.sort_by_key(|x| x.id);
+
+		let job_ids: Vec<_> = jobs.iter().map(|j| j.id).collect();
+		let mut acquired = connection.acquire().await?;
+		let payments = Db::retrieve_payments(&mut acquired, &job_ids).await?;
+		let discounts = Db::retrieve_discounts(&mut acquired, &job_ids).await?;
+		let invoice_numbers = Db::retrieve_numbers(&mut acquired, &job_ids).await?;
+		let invoice_series = Db::retrieve_series(&mut acquired).await?;
+		let invoice_due_dates = Db::retrieve_due_dates(&mut acquired, &job_ids).await?;
+
+		Ok(Self {
+			version: VERSION,
+			locations,
+			organizations,
+			contacts,
+			employees,
+			jobs,
+			timesheets,
+			expenses,
+			payments,
+			discounts,
+			invoice_numbers,
+			invoice_series,
+			invoice_due_dates,
+		})
+	}
+
+	/// Write the [`Archive`] to the file at `filepath`, which is JSON if its extension is `.json`,
+	/// and YAML otherwise. The file is only overwritten if it exists and `force` is specified.
+	///
+	/// # Errors
+	///
+	/// * [`Exists`](export::Exists), if the file exists and `force` is not specified.
+	/// * If the file could not be written.
+	pub async fn write(&self, filepath: &Path, force: bool) -> DynResult<()>
+	{
+		let serialized = match is_json(filepath)
+		{
+			false => serde_yaml::to_string(self)?,
+			true => serde_json::to_string_pretty(self)?,
+		};
+
+		export::write(filepath, serialized.as_bytes(), force).await
+	}
+}
+
+/// Whether the [`Archive`] at `filepath` is JSON, rather than YAML.
+fn is_json(filepath: &Path) -> bool
+{
+	filepath.extension().is_some_and(|e| e.eq_ignore_ascii_case("json"))
+}
+
+#[cfg(test)]
+mod tests
+{
+	use core::time::Duration;
+
+	use money2::Decimal;
+	use pretty_assertions::assert_eq;
+	use winvoice_schema::{
+		chrono::{TimeZone, Utc},
+		Currency,
+		Employee,
+		Expense,
+		Invoice,
+		Job,
+		Location,
+		Money,
+		Organization,
+		Timesheet,
+	};
+
+	use super::{Archive, Error, VERSION};
+	use crate::{export::Discount, payments::Payment, utils};
+
+	/// An [`Archive`] with one of each entity.
+	fn archive() -> Archive
+	{
+		let earth = Location { id: 0, name: "Earth".into(), outer: None };
+		let usa = Location { id: 1, name: "USA".into(), outer: Some(earth.clone().into()) };
+		let client = Organization { id: 0, location: usa.clone(), name: "Bar".into() };
+		let employee = Employee { id: 0, name: "Bob".into(), status: "Contractor".into(), title: "Dev".into() };
+		let job = Job {
+			client: client.clone(),
+			date_close: None,
+			date_open: Utc.ymd(2022, 1, 1).and_hms(0, 0, 0),
+			id: 3,
+			increment: Duration::from_secs(15 * 60),
+			invoice: Invoice { date: None, hourly_rate: Money::new(20_00, 2, Currency::Usd) },
+			notes: String::new(),
+			objectives: "Write code".into(),
+		};
+
+		Archive {
+			version: VERSION,
+			locations: vec![earth, usa],
+			organizations: vec![client],
+			contacts: Vec::new(),
+			employees: vec![employee.clone()],
+			jobs: vec![job.clone()],
+			timesheets: vec![Timesheet {
+				id: 7,
+				employee,
+				expenses: Vec::new(),
+				job,
+				time_begin: Utc.ymd(2022, 1, 1).and_hms(9, 0, 0),
+				time_end: None,
+				work_notes: "Fixed bugs".into(),
+			}],
+			expenses: vec![Expense {
+				id: 0,
+				category: "Food".into(),
+				cost: Money::new(5_00, 2, Currency::Usd),
+				description: "Lunch".into(),
+				timesheet_id: 7,
+			}],
+			payments: vec![Payment {
+				amount: Money::new(10_00, 2, Currency::Usd),
+				date: Utc.ymd(2022, 2, 1).and_hms(0, 0, 0),
+				id: 0,
+				job_id: 3,
+				notes: "Check #1".into(),
+			}],
+			discounts: [(3, Discount::Percent(Decimal::TEN))].into(),
+			invoice_numbers: [(3, "INV-2022-0001".into())].into(),
+			invoice_series: [("INV-2022-{seq}".into(), 1)].into(),
+			invoice_due_dates: [(3, Some(Utc.ymd(2022, 2, 15).and_hms(0, 0, 0)))].into(),
+		}
+	}
+
+	#[tokio::test]
+	async fn read_write()
+	{
+		let archive = archive();
+		let mut filepath = utils::temp_file::<Archive>("read-write");
+		for extension in ["json", "yaml"]
+		{
+			filepath.set_extension(extension);
+			archive.write(&filepath, true).await.unwrap();
+			assert!(archive.write(&filepath, false).await.is_err());
+			assert_eq!(Archive::read(&filepath).await.unwrap(), archive);
+		}
+
+		let mut timesheets = archive.timesheets.clone();
+		timesheets[0].expenses = archive.expenses.clone();
+		assert_eq!(
+			Archive {
+				discounts: archive.discounts.clone(),
+				invoice_numbers: archive.invoice_numbers.clone(),
+				invoice_series: archive.invoice_series.clone(),
+				invoice_due_dates: archive.invoice_due_dates.clone(),
+				..Archive::of_jobs(archive.jobs.clone(), timesheets, archive.payments.clone())
+			},
+			archive
+		);
+
+		std::fs::write(&filepath, format!("version: {}\nlocations: 3", VERSION + 1)).unwrap();
+		assert_eq!(
+			Archive::read(&filepath).await.unwrap_err().downcast_ref::<Error>(),
+			Some(&Error::UnsupportedVersion(VERSION + 1))
+		);
+	}
+
+	#[cfg(feature = "sqlite")]
+	#[tokio::test]
+	async fn restore()
+	{
+		use sqlx::{Pool, Sqlite};
+
+		use crate::sqlite::{
+			self,
+			schema::{SqliteContact, SqliteEmployee, SqliteJob, SqliteLocation, SqliteOrganization, SqliteTimesheet},
+		};
+
+		let database_url = utils::temp_sqlite_database::<Archive>("restore").await;
+		let connection = Pool::<Sqlite>::connect_lazy_with(sqlite::connect_options(&database_url).unwrap());
+		let archive = archive();
+
+		let mut transaction = connection.begin().await.unwrap();
+		let (restored, skipped) = archive
+			.clone()
+			.restore::<SqliteContact, SqliteEmployee, SqliteJob, SqliteLocation, SqliteOrganization, SqliteTimesheet, _>(
+				&mut transaction,
+				&Archive::default(),
+			)
+			.await
+			.unwrap();
+		transaction.commit().await.unwrap();
+
+		assert_eq!(skipped, []);
+		assert_eq!(restored.counts(), archive.counts());
+
+		let existing =
+			Archive::existing::<SqliteEmployee, SqliteJob, SqliteLocation, SqliteOrganization, _>(&connection)
+				.await
+				.unwrap();
+		assert_eq!(existing.jobs, restored.jobs);
+		assert_eq!(existing.invoice_numbers, restored.invoice_numbers);
+
+		let mut transaction = connection.begin().await.unwrap();
+		let (restored_again, skipped) = archive
+			.clone()
+			.restore::<SqliteContact, SqliteEmployee, SqliteJob, SqliteLocation, SqliteOrganization, SqliteTimesheet, _>(
+				&mut transaction,
+				&existing,
+			)
+			.await
+			.unwrap();
+		transaction.rollback().await.unwrap();
+
+		assert_eq!(skipped, restored.jobs);
+		assert_eq!(restored_again.counts().map(|(_, count)| count), [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+
+		let mut other = archive;
+		other.jobs[0].objectives = "Write more code".into();
+		other.timesheets[0].job = other.jobs[0].clone();
+
+		let mut transaction = connection.begin().await.unwrap();
+		let result = other
+			.restore::<SqliteContact, SqliteEmployee, SqliteJob, SqliteLocation, SqliteOrganization, SqliteTimesheet, _>(
+				&mut transaction,
+				&existing,
+			)
+			.await;
+		transaction.rollback().await.unwrap();
+
+		assert_eq!(
+			result.unwrap_err().downcast_ref::<Error>(),
+			Some(&Error::NumbersTaken(vec!["INV-2022-0001".into()]))
+		);
+	}
+}
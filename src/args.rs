@@ -1,21 +1,36 @@
+mod aging;
 mod command;
 mod create;
 mod delete;
+mod dump;
 mod flag_or_argument;
+mod import;
 mod init;
 mod match_args;
+mod migrate;
+mod report;
+mod restore;
 mod retrieve;
 mod run_action;
+mod start;
+mod status;
+mod stop;
 mod store_args;
+mod transfer;
 mod update;
 
+use std::{path::PathBuf, process::ExitCode};
+
+use aging::Overdue;
 use clap::Parser as Clap;
 use command::Command;
-use dialoguer::Editor;
 use run_action::RunAction;
-use winvoice_config::Config;
 
-use crate::DynResult;
+use crate::{
+	config,
+	input::{AutoAccept, Prompter, Scripted, Terminal},
+	DynResult,
+};
 
 /// Winvoice is a tool to track and generate invoices from the command line. Pass --help for more.
 ///
@@ -24,36 +39,83 @@ use crate::DynResult;
 #[derive(Clap, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct Args
 {
+	/// A YAML file containing a list of answers to give (in order) to any questions that would
+	/// otherwise be asked interactively.
+	///
+	/// Each answer is one of `confirm: <bool>`, `edit: <text|null>`, `select: [<index>, …]`,
+	/// `select_one: <index>`, or `text: <text>`. It is an error to ask a question which does not
+	/// match the next answer, or to run out of answers.
+	#[clap(conflicts_with("non-interactive"), long, value_name = "FILE")]
+	answers: Option<PathBuf>,
+
 	/// The specific Winvoice subcommand to run.
 	#[clap(subcommand)]
 	command: Command,
+
+	/// The configuration file to use instead of the default one.
+	#[clap(long, value_name = "FILE")]
+	config: Option<PathBuf>,
+
+	/// Accept the suggested answer to any question which would otherwise be asked interactively.
+	///
+	/// Yes/no questions are given their suggested answer (usually "no"), editors are closed without
+	/// changes, and every item is selected when selecting many (or the suggested item, when selecting
+	/// one). Selecting items to delete or move is an error unless they were narrowed down by --match,
+	/// --query, or --where. Use --answers to answer "yes" to a question.
+	#[clap(long)]
+	non_interactive: bool,
 }
 
 impl Args
 {
-	pub async fn run(self) -> DynResult<()>
+	/// Run the [`Command`], returning the status which `winvoice` should exit with.
+	pub async fn run(self) -> DynResult<ExitCode>
 	{
-		let config = Config::read()?;
+		if let Some(filepath) = self.config
+		{
+			config::set_path(filepath);
+		}
+
+		let config = config::read()?;
+		let prompter: Box<dyn Prompter> = match (self.answers, self.non_interactive)
+		{
+			(Some(filepath), _) => Box::new(Scripted::read(&filepath)?),
+			(None, true) => Box::new(AutoAccept),
+			(None, false) => Box::new(Terminal),
+		};
 
 		match self.command
 		{
+			Command::Aging(aging) => match aging.run(config, prompter.as_ref()).await
+			{
+				Err(e) if e.is::<Overdue>() => return Ok(Overdue::STATUS.into()),
+				result => result?,
+			},
 			Command::Config =>
 			{
-				let serialized = toml::to_string_pretty(&config)?;
-				if let Some(edited) = Editor::new().extension(".toml").edit(&serialized)?
+				let serialized = config::to_string_pretty(&config)?;
+				if let Some(edited) = prompter.edit(&serialized, ".toml")?
 				{
-					let deserialized: Config = toml::from_str(&edited)?;
-					deserialized.write()?;
+					config::write_str(&edited)?;
 				}
 			},
-			Command::Create(create) => create.run(config).await?,
-			Command::Delete(delete) => delete.run(config).await?,
+			Command::Create(create) => create.run(config, prompter.as_ref()).await?,
+			Command::Delete(delete) => delete.run(config, prompter.as_ref()).await?,
+			Command::Dump(dump) => dump.run(config, prompter.as_ref()).await?,
+			Command::Import(import) => import.run(config, prompter.as_ref()).await?,
 			Command::Init(init) => init.run(&config).await?,
-			Command::Retrieve(retrieve) => retrieve.run(config).await?,
-			Command::Update(update) => update.run(config).await?,
+			Command::Migrate(migrate) => migrate.run(&config).await?,
+			Command::Report(report) => report.run(config, prompter.as_ref()).await?,
+			Command::Restore(restore) => restore.run(config, prompter.as_ref()).await?,
+			Command::Retrieve(retrieve) => retrieve.run(config, prompter.as_ref()).await?,
+			Command::Start(start) => start.run(config, prompter.as_ref()).await?,
+			Command::Status(status) => status.run(config, prompter.as_ref()).await?,
+			Command::Stop(stop) => stop.run(config, prompter.as_ref()).await?,
+			Command::Transfer(transfer) => transfer.run(config, prompter.as_ref()).await?,
+			Command::Update(update) => update.run(config, prompter.as_ref()).await?,
 		};
 
-		Ok(())
+		Ok(ExitCode::SUCCESS)
 	}
 }
 
@@ -64,6 +126,21 @@ mod tests
 
 	use super::Args;
 
+	/// [`Args::try_parse_from`] the `args`, then [`unwrap`](Result::unwrap) the result when it
+	/// should be `ok` (or [`unwrap_err`](Result::unwrap_err) when it should not).
+	///
+	/// NOTE: this is a separate function so that each parsed [`Args`] is dropped with its own stack
+	///       frame, rather than accumulating in the frame of [`try_parse`] until it overflows.
+	fn parse(args: &[&str], ok: bool)
+	{
+		let result = Args::try_parse_from(args);
+		match ok
+		{
+			false => drop(result.unwrap_err()),
+			true => drop(result.unwrap()),
+		};
+	}
+
 	#[test]
 	fn try_parse()
 	{
@@ -78,6 +155,7 @@ mod tests
 		const JOB: &str = "job";
 		const LOCATION: &str = "location";
 		const ORGANIZATION: &str = "organization";
+		const PAYMENT: &str = "payment";
 		const TIMESHEET: &str = "timesheet";
 
 		/// Attempts to unwrap `parse_from` (since it gives better debug information than
@@ -159,6 +237,12 @@ mod tests
 				unwrap!(create ORGANIZATION $($arg) * "--location" "path" $(, $err)?);
 			};
 
+			(create payment $($arg:expr) * $(, $err:ident)?) =>
+			{
+				unwrap!(create PAYMENT $($arg) * $(, $err)?);
+				unwrap!(create PAYMENT $($arg) * "--date" DATE "--job" "path" "--notes" "note" $(, $err)?);
+			};
+
 			(create timesheet -j $($arg:expr) * $(, $err:ident)?) =>
 			{
 				unwrap!(create TIMESHEET $($arg) * $(, $err)?);
@@ -186,16 +270,40 @@ mod tests
 				unwrap!(update TIMESHEET $($($date_arg DATE) +)? $($arg) * $(, $err)?);
 			};
 
+			(aging    $($arg:expr) * $(, $err:ident)?) => { unwrap!("aging"    -ms $($arg) * $(, $err)?) };
 			(create   $($arg:expr) * $(, $err:ident)?) => { unwrap!("create"    -s $($arg) * $(, $err)?) };
 			(delete   $($arg:expr) * $(, $err:ident)?) => { unwrap!("delete"   -ms $($arg) * $(, $err)?) };
+			(dump     $($arg:expr) * $(, $err:ident)?) => { unwrap!("dump"      -s $($arg) * $(, $err)?) };
+			(import   $($arg:expr) * $(, $err:ident)?) => { unwrap!("import"    -s $($arg) * $(, $err)?) };
 			(init     $($arg:expr) * $(, $err:ident)?) => { unwrap!("init"      -s $($arg) * $(, $err)?) };
+			(migrate  $($arg:expr) * $(, $err:ident)?) => { unwrap!("migrate"   -s $($arg) * $(, $err)?) };
+			(report   $($arg:expr) * $(, $err:ident)?) => { unwrap!("report"   -ms $($arg) * $(, $err)?) };
+			(restore  $($arg:expr) * $(, $err:ident)?) => { unwrap!("restore"   -s $($arg) * $(, $err)?) };
 			(retrieve $($arg:expr) * $(, $err:ident)?) => { unwrap!("retrieve" -ms $($arg) * $(, $err)?) };
+			(start    $($arg:expr) * $(, $err:ident)?) => { unwrap!("start"    -ms $($arg) * $(, $err)?) };
+			(status   $($arg:expr) * $(, $err:ident)?) => { unwrap!("status"    -s $($arg) * $(, $err)?) };
+			(stop     $($arg:expr) * $(, $err:ident)?) => { unwrap!("stop"      -s $($arg) * $(, $err)?) };
+			(transfer $($arg:expr) * $(, $err:ident)?) => { unwrap!("transfer" -m $($arg) * $(, $err)?) };
 			(update   $($arg:expr) * $(, $err:ident)?) => { unwrap!("update"   -ms $($arg) * $(, $err)?) };
 
 			($cmd:literal -ms $($arg:expr) * $(, $err:ident)?) =>
 			{
-				unwrap!($cmd -s                  $($arg) * $(, $err)?);
-				unwrap!($cmd -s "--match" "path" $($arg) * $(, $err)?);
+				unwrap!($cmd -s                                      $($arg) * $(, $err)?);
+				unwrap!($cmd -s "--match" "path"                     $($arg) * $(, $err)?);
+				unwrap!($cmd -s "--match" "path" "--where" "id = 3"  $($arg) *, Err);
+				unwrap!($cmd -s                  "--where" "id = 3"  $($arg) * $(, $err)?);
+				unwrap!($cmd -s "--query" "name"                     $($arg) * $(, $err)?);
+				unwrap!($cmd -s "--query" "name" "--where" "id = 3"  $($arg) *, Err);
+				unwrap!($cmd -s "--query" "name" "--match" "path"    $($arg) *, Err);
+			};
+
+			($cmd:literal -m $($arg:expr) * $(, $err:ident)?) =>
+			{
+				unwrap!($cmd                                       $($arg) * $(, $err)?);
+				unwrap!($cmd "--match" "path"                      $($arg) * $(, $err)?);
+				unwrap!($cmd "--match" "path" "--where" "id = 3"   $($arg) *, Err);
+				unwrap!($cmd                  "--where" "id = 3"   $($arg) * $(, $err)?);
+				unwrap!($cmd "--query" "name"                      $($arg) * $(, $err)?);
 			};
 
 			($cmd:literal -s $($arg:expr) * $(, $err:ident)?) =>
@@ -204,13 +312,21 @@ mod tests
 				unwrap!($cmd "--store" "some_store" $($arg) * $(, $err)?);
 			};
 
-			($($arg:expr) +)            => { unwrap!($($arg) +, unwrap) };
-			($($arg:expr) +, Err)       => { unwrap!($($arg) +, unwrap_err) };
-			($($arg:expr) +, $fn:ident) => { Args::try_parse_from(["winvoice", $($arg),+]).$fn() };
+			($($arg:expr) +)      => { parse(&["winvoice", $($arg),+], true) };
+			($($arg:expr) +, Err) => { parse(&["winvoice", $($arg),+], false) };
 		}
 
+		// # winvoice aging
+		unwrap!(aging);
+		unwrap!(aging "--format" "json" "--rates" "path/to/eurofxref.csv");
+
 		// # winvoice config
 		unwrap!("config");
+		unwrap!("--non-interactive" "config");
+		unwrap!("--yes" "config", Err);
+		unwrap!("--answers" "path" "config");
+		unwrap!("--answers" "path" "--non-interactive" "config", Err);
+		unwrap!("--config" "path" "config");
 
 		// # winvoice create
 		unwrap!(create, Err);
@@ -273,6 +389,11 @@ mod tests
 		unwrap!(create organization, Err);
 		unwrap!(create organization "--name" "first last");
 
+		// # winvoice create payment
+		unwrap!(create payment, Err);
+		unwrap!(create payment "--amount" "20.00 USD");
+		unwrap!(create payment "--amount" "20.00 USD" "--date" "yesterday", Err);
+
 		// # winvoice create timesheet
 		unwrap!(create timesheet);
 
@@ -284,14 +405,64 @@ mod tests
 		unwrap!(delete JOB);
 		unwrap!(delete LOCATION);
 		unwrap!(delete ORGANIZATION);
+		unwrap!(delete PAYMENT);
 		unwrap!(delete TIMESHEET);
 
+		// # winvoice dump
+		unwrap!(dump, Err);
+		unwrap!(dump "path/to/archive.yaml");
+		unwrap!(dump "path/to/archive.json" "--force");
+		unwrap!(dump "path/to/archive.yaml" "--match" "path", Err);
+
+		// # winvoice import
+		unwrap!(import, Err);
+
+		// # winvoice import timesheet
+		unwrap!(import TIMESHEET, Err);
+		unwrap!(import TIMESHEET "--csv" "path/to/timesheets.csv");
+		unwrap!(import TIMESHEET "--match" "path" "--csv" "path/to/timesheets.csv", Err);
+		unwrap!(import TIMESHEET "--timewarrior" "path/to/timew.json");
+		unwrap!(import TIMESHEET "--timewarrior" "path/to/timew.json" "--mapping" "path/to/mappings.yaml");
+		unwrap!(import TIMESHEET "--toggl" "path/to/toggl.csv");
+		unwrap!(import TIMESHEET "--toggl" "path/to/toggl.csv" "--mapping" "path/to/mappings.yaml");
+		unwrap!(import TIMESHEET "--toggl" "path/to/toggl.csv" "--timewarrior" "path/to/timew.json", Err);
+		unwrap!(import TIMESHEET "--csv" "path/to/timesheets.csv" "--toggl" "path/to/toggl.csv", Err);
+		unwrap!(import TIMESHEET                                  "--mapping" "path/to/mappings.yaml", Err);
+
 		// # winvoice init
 		unwrap!(init);
 
+		// # winvoice migrate
+		unwrap!(migrate);
+		unwrap!(migrate "--dry-run");
+		unwrap!(migrate "--dry-run" "path/to/file", Err);
+
+		// # winvoice report
+		unwrap!(report);
+		unwrap!(report "--by" "client,job" "--period" "month" "--format" "json");
+		unwrap!(report "--by" "employee" "--currency" "USD" "--rates" "path/to/eurofxref.csv");
+		unwrap!(report "--by" "invoice", Err);
+		unwrap!(report "--period" "quarter", Err);
+
+		// # winvoice restore
+		unwrap!(restore, Err);
+		unwrap!(restore "path/to/archive.yaml");
+		unwrap!(restore "path/to/archive.yaml" "--force", Err);
+
 		// # winvoice retrieve
 		unwrap!(retrieve, Err);
 
+		// # winvoice retrieve --format
+		unwrap!(retrieve "--format" "csv" CONTACT);
+		unwrap!(retrieve "--format" "json" EMPLOYEE);
+		unwrap!(retrieve "--format" "table" JOB);
+		unwrap!(retrieve "--format" "yaml" TIMESHEET);
+		unwrap!(retrieve "--format" "xml" LOCATION, Err);
+
+		// # winvoice retrieve --save-query
+		unwrap!(retrieve "--save-query" "name" CONTACT);
+		unwrap!(retrieve JOB "--save-query" "name");
+
 		// # winvoice retrieve contact
 		unwrap!(retrieve CONTACT);
 
@@ -307,6 +478,21 @@ mod tests
 		// # winvoice retrieve job
 		unwrap!(retrieve JOB);
 		unwrap!(retrieve JOB "--export" "markdown");
+		unwrap!(retrieve JOB "--export" "html");
+		unwrap!(retrieve JOB "--export" "pdf");
+		unwrap!(retrieve JOB "--export" "ubl");
+		unwrap!(retrieve JOB "--export" "docx", Err);
+		unwrap!(retrieve JOB "--export" "template" "--template" "path/to/invoice.html.hbs");
+		unwrap!(retrieve JOB "--template" "path/to/invoice.html.hbs", Err);
+		unwrap!(retrieve JOB "--export" "markdown" "--rates" "path/to/eurofxref.csv" "--unconverted");
+		unwrap!(retrieve JOB "--unconverted", Err);
+		unwrap!(retrieve JOB "--export" "pdf" "--combine");
+		unwrap!(retrieve JOB "--combine", Err);
+		unwrap!(retrieve JOB "--export" "html" "--output" "-");
+		unwrap!(retrieve JOB "--export" "html" "--output" "-" "--output-dir" "path/to/dir", Err);
+		unwrap!(retrieve JOB "--export" "html" "--filename" "{date}--{client}" "--force");
+		unwrap!(retrieve JOB "--export" "html" "--filename" "{job}", Err);
+		unwrap!(retrieve JOB "--force", Err);
 		unwrap!(retrieve JOB "--export" "markdown" "--currency" "USD");
 		unwrap!(retrieve JOB "--export" "markdown" "--currency" "USD" "--output-dir" "path/to/dir");
 		unwrap!(retrieve JOB "--export" "markdown"                    "--output-dir" "path/to/dir");
@@ -323,8 +509,42 @@ mod tests
 		unwrap!(retrieve ORGANIZATION "--employer" "--set-employer", Err);
 		unwrap!(retrieve ORGANIZATION              "--set-employer");
 
+		// # winvoice retrieve payment
+		unwrap!(retrieve PAYMENT);
+
 		// # winvoice retrieve timesheet
 		unwrap!(retrieve TIMESHEET);
+		unwrap!(retrieve TIMESHEET "--export" "csv");
+		unwrap!(retrieve TIMESHEET "--export" "csv" "--expenses" "--output-dir" "path/to/dir");
+		unwrap!(retrieve TIMESHEET "--export" "csv" "--force");
+		unwrap!(retrieve TIMESHEET "--force", Err);
+		unwrap!(retrieve TIMESHEET "--export" "pdf", Err);
+		unwrap!(retrieve TIMESHEET "--expenses", Err);
+
+		// # winvoice start
+		unwrap!(start);
+		unwrap!(start "--time-begin" DATE);
+		unwrap!(start "--work-notes" "note");
+		unwrap!(start "--time-begin" DATE "-n" "note");
+		unwrap!(start "--time-begin" "tomorrow", Err);
+
+		// # winvoice status
+		unwrap!(status);
+
+		// # winvoice stop
+		unwrap!(stop);
+		unwrap!(stop "--time-end" DATE);
+		unwrap!(stop "--work-notes" "note");
+		unwrap!(stop "--time-end" DATE "-n" "note");
+		unwrap!(stop "--match" "path", Err);
+
+		// # winvoice transfer
+		unwrap!(transfer, Err);
+		unwrap!(transfer "--from" "default" "--to" "archive");
+		unwrap!(transfer "--from" "default" "--to" "archive" "--move");
+		unwrap!(transfer "--from" "default", Err);
+		unwrap!(transfer                    "--to" "archive", Err);
+		unwrap!(transfer "--from" "default" "--to" "archive" "--store" "default", Err);
 
 		// # winvoice update
 		unwrap!(update, Err);
@@ -346,17 +566,23 @@ mod tests
 		unwrap!(update job {-d "--close"}                                            "--reopen", Err);
 		unwrap!(update job {-d "--close" "--invoice-issued"});
 		unwrap!(update job {-d "--close" "--invoice-issued"}                         "--reopen", Err);
-		unwrap!(update job {-d "--close" "--invoice-issued" "--invoice-paid"});
+		unwrap!(update job {-d "--close" "--invoice-issued" "--invoice-paid"}        "--amount" "5.00 USD");
 		unwrap!(update job {-d "--close" "--invoice-issued" "--invoice-paid"}        "--reopen", Err);
 		// unwrap!(update job {-d "--close"                    "--invoice-paid"}, Err); // FIX: this
 		unwrap!(update job {-d "--close"                    "--invoice-paid"}        "--reopen", Err);
 		unwrap!(update job {-d           "--invoice-issued"});
 		unwrap!(update job {-d           "--invoice-issued"}                         "--reopen", Err);
-		unwrap!(update job {-d           "--invoice-issued" "--invoice-paid"});
+		unwrap!(update job {-d           "--invoice-issued" "--invoice-paid"}        "--amount" "5.00 USD");
 		unwrap!(update job {-d           "--invoice-issued" "--invoice-paid"}        "--reopen", Err);
-		unwrap!(update job {-d                              "--invoice-paid"});
+		unwrap!(update job {-d                              "--invoice-paid"}        "--amount" "5.00 USD");
+		unwrap!(update job {-d                              "--invoice-paid"}        "--amount" "five", Err);
+		unwrap!(update job {-d                              "--invoice-paid"}, Err);
 		unwrap!(update job {-d                              "--invoice-paid"}        "--reopen", Err);
+		unwrap!(update job                                                           "--amount" "5.00 USD", Err);
 		unwrap!(update job                                                           "--reopen");
+		unwrap!(update job                                                           "--discount" "10%");
+		unwrap!(update job                                                           "--discount" "50.00 USD");
+		unwrap!(update job                                                           "--discount" "ten", Err);
 
 		// # winvoice update location
 		unwrap!(update LOCATION);
@@ -0,0 +1,258 @@
+mod as_ref;
+mod run_action;
+
+use std::{collections::BTreeMap, path::PathBuf};
+
+use clap::Args as Clap;
+use money2::{Currency, Decimal};
+use serde::Serialize;
+use thiserror::Error;
+use winvoice_schema::{
+	chrono::{DateTime, Local, Utc},
+	Job,
+	Money,
+};
+
+use super::{match_args::MatchArgs, store_args::StoreArgs};
+use crate::fmt::{self, OutputFormat};
+
+/// The number of days since an invoice was issued which each [`Row`] bucket ends at. Invoices in
+/// any bucket other than the first are overdue.
+const BUCKETS: [i64; 3] = [30, 60, 90];
+
+/// Report the `Job`s whose invoices have been issued but not paid, grouped by how many days ago
+/// they were issued (0–30, 31–60, 61–90, and 90+) and totaled for each client.
+///
+/// Discounts, taxes, and payments towards each invoice are included in the amount owed. Amounts
+/// are converted to the `default_currency` of the `[invoices]` section of the Winvoice
+/// configuration file. Exits with status 2 if any invoice is overdue (i.e. it is past the date it
+/// is due by according to the payment terms, or was issued more than 30 days ago when there are
+/// none), so that this can be run periodically.
+#[derive(Clap, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Aging
+{
+	/// Print the report in the specified format.
+	#[clap(default_value = "table", long, short, value_enum, value_name = "FORMAT")]
+	format: OutputFormat,
+
+	/// Specifies the `Job`s to report on. When not specified, every `Job` is reported on.
+	#[clap(flatten)]
+	match_args: MatchArgs,
+
+	/// A file of exchange rates to use instead of fetching them. See `winvoice retrieve job
+	/// --rates`.
+	#[clap(long, short, value_name = "FILE", value_parser)]
+	rates: Option<PathBuf>,
+
+	/// Specifies the [`Store`](crate::config::Store) containing the `Job`s.
+	#[clap(flatten)]
+	store_args: StoreArgs,
+}
+
+/// The result of [`Aging`] when some invoices are overdue.
+///
+/// It is not reported as an error; `winvoice` only exits with [`Overdue::STATUS`].
+#[derive(Clone, Copy, Debug, Eq, Error, Hash, Ord, PartialEq, PartialOrd)]
+#[error("{0} invoice(s) are overdue, and have not been paid")]
+pub(super) struct Overdue(usize);
+
+impl Overdue
+{
+	/// The status which `winvoice` exits with when some invoices are [`Overdue`], to distinguish
+	/// it from errors.
+	pub(super) const STATUS: u8 = 2;
+}
+
+/// An unpaid `Job`, or the total of every unpaid `Job` for a client.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+struct Row
+{
+	/// The `name` of the `client`.
+	client: String,
+
+	/// The `Job`, or [`None`] if this is the total for the `client`.
+	job: Option<String>,
+
+	/// The date that the invoice was issued, or [`None`] if this is the total for the `client`.
+	issued: Option<String>,
+
+	/// The date that the invoice is due by, or [`None`] if there are no payment terms or this is
+	/// the total for the `client`.
+	due: Option<String>,
+
+	/// The number of days since the invoice was issued, or [`None`] if this is the total for the
+	/// `client`.
+	days: Option<i64>,
+
+	/// The amount owed for invoices issued 0–30 days ago.
+	#[serde(rename = "0-30")]
+	current: Decimal,
+
+	/// The amount owed for invoices issued 31–60 days ago.
+	#[serde(rename = "31-60")]
+	over_30: Decimal,
+
+	/// The amount owed for invoices issued 61–90 days ago.
+	#[serde(rename = "61-90")]
+	over_60: Decimal,
+
+	/// The amount owed for invoices issued more than 90 days ago.
+	#[serde(rename = "90+")]
+	over_90: Decimal,
+
+	/// The currency of the amounts.
+	currency: Currency,
+}
+
+impl Row
+{
+	/// The amounts of each bucket of this [`Row`].
+	const fn buckets_mut(&mut self) -> [&mut Decimal; 4]
+	{
+		[&mut self.current, &mut self.over_30, &mut self.over_60, &mut self.over_90]
+	}
+}
+
+impl Aging
+{
+	/// Create a [`Row`] for each of the `owed` jobs (along with the date each is due by), followed
+	/// by the total for its client, as of `now`. Returns the rows, and how many jobs are
+	/// [`Overdue`].
+	///
+	/// The amounts owed must all be in the `currency`. Clients are ordered by name, and their jobs
+	/// from the oldest invoice to the newest.
+	fn rows(owed: &[(Job, Option<DateTime<Utc>>, Money)], currency: Currency, now: DateTime<Utc>) -> (Vec<Row>, usize)
+	{
+		let date = |d: DateTime<Utc>| d.with_timezone(&Local).format("%Y-%m-%d").to_string();
+		let mut overdue = 0;
+		let clients = owed.iter().fold(BTreeMap::<_, Vec<Row>>::new(), |mut clients, (job, due, amount)| {
+			let issued = job.invoice.date.map_or(now, |d| d.issued);
+			let days = (now - issued).num_days();
+			let bucket = BUCKETS.iter().position(|b| days <= *b).unwrap_or(BUCKETS.len());
+			if due.map_or(bucket > 0, |d| d < now)
+			{
+				overdue += 1;
+			}
+
+			let mut row = Row {
+				client: job.client.name.clone(),
+				job: Some(format!("{} {}", fmt::id_num(job.id), job.objectives.lines().next().unwrap_or_default())),
+				issued: Some(date(issued)),
+				due: due.map(date),
+				days: Some(days),
+				current: Decimal::ZERO,
+				over_30: Decimal::ZERO,
+				over_60: Decimal::ZERO,
+				over_90: Decimal::ZERO,
+				currency,
+			};
+
+			*row.buckets_mut()[bucket] = amount.amount;
+			clients.entry((job.client.name.clone(), job.client.id)).or_default().push(row);
+			clients
+		});
+
+		let rows = clients
+			.into_iter()
+			.flat_map(|((client, _), mut rows)| {
+				rows.sort_by(|lhs, rhs| lhs.days.cmp(&rhs.days).reverse());
+				let total = rows.iter().fold(
+					Row {
+						client,
+						job: None,
+						issued: None,
+						due: None,
+						days: None,
+						current: Decimal::ZERO,
+						over_30: Decimal::ZERO,
+						over_60: Decimal::ZERO,
+						over_90: Decimal::ZERO,
+						currency,
+					},
+					|mut total, r| {
+						total.current += r.current;
+						total.over_30 += r.over_30;
+						total.over_60 += r.over_60;
+						total.over_90 += r.over_90;
+						total
+					},
+				);
+
+				rows.push(total);
+				rows
+			})
+			.collect();
+
+		(rows, overdue)
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use core::time::Duration;
+
+	use money2::Decimal;
+	use pretty_assertions::assert_eq;
+	use winvoice_schema::{
+		chrono::{self, TimeZone, Utc},
+		Currency,
+		Invoice,
+		InvoiceDate,
+		Job,
+		Location,
+		Money,
+		Organization,
+	};
+
+	use super::Aging;
+
+	#[test]
+	fn rows()
+	{
+		let now = Utc.ymd(2022, 6, 1).and_hms(12, 0, 0);
+		let location = Location { id: 0, name: "Earth".into(), outer: None };
+		let job = |id, client: &Organization, issued_days_ago| Job {
+			client: client.clone(),
+			date_close: None,
+			date_open: Utc.ymd(2022, 1, 1).and_hms(0, 0, 0),
+			id,
+			increment: Duration::from_secs(15 * 60),
+			invoice: Invoice {
+				date: Some(InvoiceDate { issued: now - chrono::Duration::days(issued_days_ago), paid: None }),
+				hourly_rate: Money::new(20_00, 2, Currency::Usd),
+			},
+			notes: String::new(),
+			objectives: "Write code".into(),
+		};
+
+		let acme = Organization { id: 1, location: location.clone(), name: "Acme".into() };
+		let zeta = Organization { id: 0, location, name: "Zeta".into() };
+		let days = chrono::Duration::days;
+		let owed = [
+			(job(1, &zeta, 5), Some(now - days(2)), Money::new(10_00, 2, Currency::Usd)),
+			(job(2, &acme, 45), Some(now + days(15)), Money::new(20_00, 2, Currency::Usd)),
+			(job(3, &acme, 120), None, Money::new(30_00, 2, Currency::Usd)),
+			(job(4, &acme, 30), None, Money::new(40_00, 2, Currency::Usd)),
+		];
+
+		let (rows, overdue) = Aging::rows(&owed, Currency::Usd, now);
+		assert_eq!(overdue, 2, "№1 is past due, and №3 has no payment terms but was issued over 30 days ago");
+		assert_eq!(rows.iter().map(|r| (r.client.as_str(), r.job.as_deref(), r.days)).collect::<Vec<_>>(), [
+			("Acme", Some("№3 Write code"), Some(120)),
+			("Acme", Some("№2 Write code"), Some(45)),
+			("Acme", Some("№4 Write code"), Some(30)),
+			("Acme", None, None),
+			("Zeta", Some("№1 Write code"), Some(5)),
+			("Zeta", None, None),
+		]);
+
+		assert_eq!(rows[3].current, Decimal::new(40_00, 2));
+		assert_eq!(rows[3].over_30, Decimal::new(20_00, 2));
+		assert_eq!(rows[3].over_60, Decimal::ZERO);
+		assert_eq!(rows[3].over_90, Decimal::new(30_00, 2));
+		assert_eq!(rows[5].current, Decimal::new(10_00, 2));
+		assert_eq!(rows[4].due.as_deref(), Some("2022-05-30"));
+		assert_eq!(rows[2].due, None);
+	}
+}
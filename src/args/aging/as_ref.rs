@@ -0,0 +1,10 @@
+use super::Aging;
+use crate::args::store_args::StoreArgs;
+
+impl AsRef<StoreArgs> for Aging
+{
+	fn as_ref(&self) -> &StoreArgs
+	{
+		&self.store_args
+	}
+}
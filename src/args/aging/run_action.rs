@@ -0,0 +1,127 @@
+use futures::future;
+use money2::Exchange;
+use sqlx::{Database, Executor, Pool, Transaction};
+use winvoice_adapter::{
+	schema::{
+		ContactAdapter,
+		EmployeeAdapter,
+		ExpensesAdapter,
+		JobAdapter,
+		LocationAdapter,
+		OrganizationAdapter,
+		TimesheetAdapter,
+	},
+	Deletable,
+};
+use winvoice_match::{Match, MatchJob, MatchTimesheet};
+use winvoice_schema::chrono::Utc;
+
+use super::{Aging, Overdue};
+use crate::{
+	args::RunAction,
+	config::{Config, Extensions},
+	discounts::Discounts,
+	exchange,
+	export::Discount,
+	input::Prompter,
+	numbering::Register,
+	payments::{self, Ledger},
+	DynResult,
+};
+
+#[async_trait::async_trait(?Send)]
+impl RunAction for Aging
+{
+	async fn action<CAdapter, EAdapter, JAdapter, LAdapter, OAdapter, TAdapter, XAdapter, Db>(
+		self,
+		connection: Pool<Db>,
+		config: Config,
+		_prompter: &dyn Prompter,
+	) -> DynResult<()>
+	where
+		CAdapter: Deletable<Db = Db> + ContactAdapter,
+		EAdapter: Deletable<Db = Db> + EmployeeAdapter,
+		JAdapter: Deletable<Db = Db> + JobAdapter,
+		LAdapter: Deletable<Db = Db> + LocationAdapter,
+		OAdapter: Deletable<Db = Db> + OrganizationAdapter,
+		TAdapter: Deletable<Db = Db> + TimesheetAdapter,
+		XAdapter: Deletable<Db = Db> + ExpensesAdapter,
+		Db: Database + Discounts + Ledger + Register,
+		for<'connection> &'connection mut Db::Connection: Executor<'connection, Database = Db>,
+		for<'connection> &'connection mut Transaction<'connection, Db>: Executor<'connection, Database = Db>,
+	{
+		let currency = config.invoices.default_currency;
+		let invoices = Extensions::read()?.invoices;
+		let match_condition: Option<MatchJob> = self.match_args.try_into()?;
+
+		let mut unpaid = JAdapter::retrieve(&connection, match_condition.unwrap_or_default()).await?;
+		unpaid.retain(|j| j.invoice.date.is_some_and(|d| d.paid.is_none()));
+
+		let job_ids: Vec<_> = unpaid.iter().map(|j| j.id).collect();
+		let mut acquired = connection.acquire().await?;
+		let discounts = Db::retrieve_discounts(&mut acquired, &job_ids).await?;
+		let payments = Db::retrieve_payments(&mut acquired, &job_ids).await?;
+		let due_dates = Db::retrieve_due_dates(&mut acquired, &job_ids).await?;
+		drop(acquired);
+
+		let store = self.store_args.name();
+
+		// only obtain exchange rates if some amount is in a currency other than the one being reported in.
+		let mut foreign = unpaid.iter().any(|j| {
+			j.invoice.hourly_rate.currency != currency ||
+				matches!(discounts.get(&j.id), Some(Discount::Fixed(m)) if m.currency != currency)
+		});
+
+		if !(foreign || unpaid.is_empty())
+		{
+			let timesheets = TAdapter::retrieve(&connection, MatchTimesheet {
+				job: MatchJob { id: Match::Or(job_ids.iter().map(|id| (*id).into()).collect()), ..Default::default() },
+				..Default::default()
+			})
+			.await?;
+
+			foreign = timesheets.iter().flat_map(|t| t.expenses.iter()).any(|x| x.cost.currency != currency);
+		}
+
+		let exchange_rates = match foreign
+		{
+			false => None,
+			true => Some(exchange::rates(self.rates.as_deref(), &invoices.rates).await?),
+		};
+
+		let owed = future::try_join_all(unpaid.into_iter().map(|j| {
+			let connection = &connection;
+			let exchange_rates = exchange_rates.as_ref();
+			let discounts = &discounts;
+			let due_dates = &due_dates;
+			let invoices = &invoices;
+			let payments = &payments;
+
+			async move {
+				let balance = payments::balance::<TAdapter, _>(
+					connection,
+					payments,
+					discounts,
+					invoices,
+					store,
+					&j,
+					exchange_rates,
+				)
+				.await?;
+
+				let due = invoices.due_by(store, &j, due_dates);
+				DynResult::Ok((j, due, exchange_rates.map_or(balance, |r| balance.exchange(currency, r))))
+			}
+		}))
+		.await?;
+
+		let (rows, overdue) = Self::rows(&owed, currency, Utc::now());
+		print!("{}", self.format.render(&rows)?);
+
+		match overdue
+		{
+			0 => Ok(()),
+			n => Err(Overdue(n).into()),
+		}
+	}
+}
@@ -1,11 +1,30 @@
 use clap::Subcommand as Clap;
 
-use super::{create::Create, delete::Delete, init::Init, retrieve::Retrieve, update::Update};
+use super::{
+	aging::Aging,
+	create::Create,
+	delete::Delete,
+	dump::Dump,
+	import::Import,
+	init::Init,
+	migrate::Migrate,
+	report::Report,
+	restore::Restore,
+	retrieve::Retrieve,
+	start::Start,
+	status::Status,
+	stop::Stop,
+	transfer::Transfer,
+	update::Update,
+};
 
 /// The specific command that Winvoice should run.
 #[derive(Clap, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub enum Command
 {
+	#[allow(missing_docs)]
+	Aging(Aging),
+
 	/// Edit the Winvoice configuration file in your default editor.
 	///
 	/// Setting your default editor depends on platform. On Unix-based systems, try setting
@@ -18,12 +37,39 @@ pub enum Command
 	#[allow(missing_docs)]
 	Delete(Delete),
 
+	#[allow(missing_docs)]
+	Dump(Dump),
+
+	#[allow(missing_docs)]
+	Import(Import),
+
 	#[allow(missing_docs)]
 	Init(Init),
 
+	#[allow(missing_docs)]
+	Migrate(Migrate),
+
+	#[allow(missing_docs)]
+	Report(Report),
+
+	#[allow(missing_docs)]
+	Restore(Restore),
+
 	#[allow(missing_docs)]
 	Retrieve(Retrieve),
 
+	#[allow(missing_docs)]
+	Start(Start),
+
+	#[allow(missing_docs)]
+	Status(Status),
+
+	#[allow(missing_docs)]
+	Stop(Stop),
+
+	#[allow(missing_docs)]
+	Transfer(Transfer),
+
 	#[allow(missing_docs)]
 	Update(Update),
 }
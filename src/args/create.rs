@@ -20,7 +20,7 @@ pub struct Create
 	#[clap(subcommand)]
 	command: CreateCommand,
 
-	/// Specifies the [`Store`](winvoice_config::Store) to insert [`Create`]d data into.
+	/// Specifies the [`Store`](crate::config::Store) to insert [`Create`]d data into.
 	#[clap(flatten)]
 	store_args: StoreArgs,
 }
@@ -227,6 +227,33 @@ pub enum CreateCommand
 		name: String,
 	},
 
+	/// Record a payment towards the invoice of a `Job` in the store (-s) specified.
+	///
+	/// Once the payments towards an issued invoice add up to its total, the invoice is marked as
+	/// paid.
+	Payment
+	{
+		/// How much was paid, in the same currency as the `hourly_rate` of the `Job` e.g. "50.00
+		/// USD"
+		#[clap(long, short = '$')]
+		amount: Money,
+
+		/// The date and time that the payment was made. Defaults to the current date and time.
+		///
+		/// e.g. December 12th, 2022 at 1:30:00pm is "2022-12-31T13:30:00"
+		#[clap(long, short)]
+		date: Option<NaiveDateTime>,
+
+		/// A path to a YAML file that contains a valid match condition/query/search for a
+		/// Winvoice Job.
+		#[clap(long, short, value_name = "FILE", value_parser)]
+		job: Option<PathBuf>,
+
+		/// Notes about the payment e.g. "Check #1234"
+		#[clap(long, short)]
+		notes: Option<String>,
+	},
+
 	/// Create a `Timesheet` in the store (-s) specified.
 	///
 	/// See the documentation for more information about `Timesheet`s.
@@ -11,13 +11,17 @@ use winvoice_adapter::{
 	},
 	Deletable,
 };
-use winvoice_config::Config;
 use winvoice_schema::{chrono::Utc, ContactKind, Invoice, InvoiceDate};
 
 use super::{Create, CreateCommand};
 use crate::{
 	args::{match_args::MatchArgs, update::Update, RunAction},
-	input,
+	config::{Config, Extensions},
+	discounts::Discounts,
+	fmt,
+	input::{self, Prompter},
+	numbering::{self, Register},
+	payments::{self, CurrencyMismatch, Ledger},
 	utils,
 	DynResult,
 };
@@ -29,6 +33,7 @@ impl RunAction for Create
 		self,
 		connection: Pool<Db>,
 		config: Config,
+		prompter: &dyn Prompter,
 	) -> DynResult<()>
 	where
 		CAdapter: Deletable<Db = Db> + ContactAdapter,
@@ -38,7 +43,7 @@ impl RunAction for Create
 		OAdapter: Deletable<Db = Db> + OrganizationAdapter,
 		TAdapter: Deletable<Db = Db> + TimesheetAdapter,
 		XAdapter: Deletable<Db = Db> + ExpensesAdapter,
-		Db: Database,
+		Db: Database + Discounts + Ledger + Register,
 		for<'connection> &'connection mut Db::Connection: Executor<'connection, Database = Db>,
 		for<'connection> &'connection mut Transaction<'connection, Db>: Executor<'connection, Database = Db>,
 	{
@@ -52,6 +57,7 @@ impl RunAction for Create
 					{
 						let match_condition = MatchArgs::from(address.argument()).try_into()?;
 						input::select_one_retrieved::<LAdapter, _, _>(
+							prompter,
 							&connection,
 							match_condition,
 							"Query the Location of this address",
@@ -79,6 +85,7 @@ impl RunAction for Create
 			{
 				let match_timesheet = MatchArgs::from(timesheet).try_into()?;
 				let selected = input::select_one_retrieved::<TAdapter, _, _>(
+					prompter,
 					&connection,
 					match_timesheet,
 					"Query the Timesheet this Expense is for",
@@ -113,14 +120,24 @@ impl RunAction for Create
 				};
 
 				let selected = input::select_one_retrieved::<OAdapter, _, _>(
+					prompter,
 					&connection,
 					match_client,
 					"Query the client Organization for this Job",
 				)
 				.await?;
 
+				let invoices = match date_invoice_issued.is_some()
+				{
+					false => None,
+					true => Some(Extensions::read()?.invoices),
+				};
+
+				// {{{
+				let mut transaction = connection.begin().await?;
+
 				let created = JAdapter::create(
-					&connection,
+					&mut *transaction,
 					selected,
 					date_close.map(utils::naive_local_datetime_to_utc),
 					date_open.map_or_else(Utc::now, utils::naive_local_datetime_to_utc),
@@ -138,6 +155,14 @@ impl RunAction for Create
 				.await?;
 
 				Self::report_created(&created);
+				if let Some(i) = invoices
+				{
+					numbering::assign::<Db, _>(&mut transaction, i.numbering.as_ref(), [&created]).await?;
+					numbering::schedule::<Db, _>(&mut transaction, &i, self.store_args.name(), [&created]).await?;
+				}
+
+				transaction.commit().await?;
+				// }}}
 			},
 
 			CreateCommand::Location { inside, outside, names } =>
@@ -155,6 +180,7 @@ impl RunAction for Create
 					{
 						let match_condition = MatchArgs::from(inside.argument()).try_into()?;
 						input::select_one_retrieved::<LAdapter, _, _>(
+							prompter,
 							&connection,
 							match_condition,
 							format!("Query the Location outside of {final_name}"),
@@ -184,6 +210,7 @@ impl RunAction for Create
 				{
 					let match_condition = MatchArgs::from(outside.argument()).try_into()?;
 					let mut inside_locations = input::select_retrieved::<LAdapter, _, _>(
+						prompter,
 						&connection,
 						match_condition,
 						format!("Query Locations that are inside {created}"),
@@ -220,6 +247,7 @@ impl RunAction for Create
 			{
 				let match_location = MatchArgs::from(location).try_into()?;
 				let selected = input::select_one_retrieved::<LAdapter, _, _>(
+					prompter,
 					&connection,
 					match_location,
 					"Query the Location of this Organization",
@@ -230,6 +258,58 @@ impl RunAction for Create
 				Self::report_created(&created);
 			},
 
+			CreateCommand::Payment { amount, date, job, notes } =>
+			{
+				let match_job = MatchArgs::from(job).try_into()?;
+				let mut selected = input::select_one_retrieved::<JAdapter, _, _>(
+					prompter,
+					&connection,
+					match_job,
+					"Query the Job this Payment is for",
+				)
+				.await?;
+
+				let expected = selected.invoice.hourly_rate.currency;
+				if amount.currency != expected
+				{
+					return Err(CurrencyMismatch { expected, found: amount.currency }.into());
+				}
+
+				let date = date.map_or_else(Utc::now, utils::naive_local_datetime_to_utc);
+				let invoices = Extensions::read()?.invoices;
+
+				// {{{
+				let mut transaction = connection.begin().await?;
+
+				let created =
+					Db::create_payment(&mut transaction, selected.id, amount, date, notes.unwrap_or_default()).await?;
+				Self::report_created(&created);
+
+				let discounts = Db::retrieve_discounts(&mut transaction, &[selected.id]).await?;
+				let payments = Db::retrieve_payments(&mut transaction, &[selected.id]).await?;
+				let store = self.store_args.name();
+				let balance = payments::balance::<TAdapter, _>(
+					&connection,
+					&payments,
+					&discounts,
+					&invoices,
+					store,
+					&selected,
+					None,
+				)
+				.await?;
+				if payments::reconcile(&mut selected, balance, date)
+				{
+					JAdapter::update(&mut transaction, [&selected].into_iter().inspect(|j| Update::report_updated(*j)))
+						.await?;
+				}
+
+				transaction.commit().await?;
+				// }}}
+
+				println!("The remaining balance of Job {} is {balance}", fmt::id_num(selected.id));
+			},
+
 			CreateCommand::Timesheet { default_employee, employee, job, time_begin, time_end, work_notes } =>
 			{
 				let match_employee = match default_employee
@@ -239,6 +319,7 @@ impl RunAction for Create
 				};
 
 				let employee = input::select_one_retrieved::<EAdapter, _, _>(
+					prompter,
 					&connection,
 					match_employee,
 					"Query the Employee who is responsible for the work",
@@ -247,16 +328,17 @@ impl RunAction for Create
 
 				let match_job = MatchArgs::from(job).try_into()?;
 				let job = input::select_one_retrieved::<JAdapter, _, _>(
+					prompter,
 					&connection,
 					match_job,
 					"Query the Job being worked on",
 				)
 				.await?;
 
-				let expenses = match cfg!(test) || time_end.is_none()
+				let expenses = match time_end
 				{
-					false => input::expense::menu()?,
-					true => Vec::new(),
+					Some(_) => input::expense::menu(prompter)?,
+					None => Vec::new(),
 				};
 
 				// {{{
@@ -284,44 +366,26 @@ impl RunAction for Create
 	}
 }
 
-#[cfg(all(feature = "postgres", test))]
+#[cfg(all(any(feature = "postgres", feature = "sqlite"), test))]
 mod tests
 {
 	use money2::{Exchange, ExchangeRates};
 	use pretty_assertions::assert_eq;
-	use sqlx::{
-		postgres::{PgPool, Postgres},
-		QueryBuilder,
-		Result,
-		Row,
-	};
+	use sqlx::{Database, Error, Executor, Pool, Result, Transaction};
 	use winvoice_adapter::{
-		fmt::{sql, QueryBuilderExt, TableToSql},
 		schema::{
-			columns::{
-				EmployeeColumns,
-				ExpenseColumns,
-				JobColumns,
-				LocationColumns,
-				OrganizationColumns,
-				TimesheetColumns,
-			},
+			ContactAdapter,
+			EmployeeAdapter,
+			ExpensesAdapter,
+			JobAdapter,
 			LocationAdapter,
+			OrganizationAdapter,
+			TimesheetAdapter,
 		},
 		Deletable,
 		Retrievable,
 	};
-	use winvoice_adapter_postgres::schema::{
-		PgContact,
-		PgEmployee,
-		PgExpenses,
-		PgJob,
-		PgLocation,
-		PgOrganization,
-		PgTimesheet,
-	};
-	use winvoice_config::Config;
-	use winvoice_match::{MatchEmployee, MatchJob, MatchLocation, MatchOrganization, MatchTimesheet};
+	use winvoice_match::{MatchContact, MatchEmployee, MatchJob, MatchLocation, MatchOrganization, MatchTimesheet};
 	use winvoice_schema::{
 		chrono::{DateTime, Duration, Local, NaiveDate, Utc},
 		Currency,
@@ -332,50 +396,51 @@ mod tests
 	};
 
 	use super::{Create, CreateCommand, RunAction};
-	use crate::{args::flag_or_argument::FlagOrArgument, utils};
+	use crate::{args::flag_or_argument::FlagOrArgument, config::Config, input::AutoAccept, utils};
 
 	/// WARN: use `cargo test -- --test-threads=1`.
-	#[tokio::test]
-	async fn run_action()
+	async fn run_action<CAdapter, EAdapter, JAdapter, LAdapter, OAdapter, TAdapter, XAdapter, Db>(
+		adapter: &str,
+		database_url: &str,
+	) where
+		CAdapter: Deletable<Db = Db> + ContactAdapter,
+		EAdapter: Deletable<Db = Db> + EmployeeAdapter,
+		JAdapter: Deletable<Db = Db> + JobAdapter,
+		LAdapter: Deletable<Db = Db> + LocationAdapter,
+		OAdapter: Deletable<Db = Db> + OrganizationAdapter,
+		TAdapter: Deletable<Db = Db> + TimesheetAdapter,
+		XAdapter: Deletable<Db = Db> + ExpensesAdapter,
+		Db: Database,
+		for<'connection> &'connection mut Db::Connection: Executor<'connection, Database = Db>,
+		for<'connection> &'connection mut Transaction<'connection, Db>: Executor<'connection, Database = Db>,
 	{
-		async fn contact(connection: &PgPool, label: &str) -> Result<<PgContact as Retrievable>::Entity>
+		async fn contact<CAdapter, Db>(connection: &Pool<Db>, label: &str) -> Result<<CAdapter as Retrievable>::Entity>
+		where
+			CAdapter: Deletable<Db = Db> + ContactAdapter,
+			Db: Database,
 		{
-			PgContact::retrieve(connection, <PgContact as Retrievable>::Match::from(label.to_owned()))
-				.await
-				.map(|mut v| v.remove(0))
+			CAdapter::retrieve(connection, MatchContact::from(label.to_owned())).await.map(|mut v| v.remove(0))
 		}
 
 		/// Retrieve the most-recently-created row in the database as its structural counterpart.
-		async fn latest_entity<R, T>(connection: &PgPool) -> Result<R::Entity>
+		async fn latest_entity<R, Db>(connection: &Pool<Db>, id: fn(&R::Entity) -> Id) -> Result<R::Entity>
 		where
-			R: Retrievable<Db = Postgres>,
-			R::Match: From<Id>,
-			T: TableToSql,
+			Db: Database,
+			R: Retrievable<Db = Db>,
+			R::Match: Default,
 		{
-			let id = QueryBuilder::new(sql::SELECT)
-				.push('*')
-				.push_default_from::<T>()
-				.push(sql::ORDER_BY)
-				.push("id")
-				.push(sql::DESCENDING)
-				.push(sql::LIMIT)
-				.push(1)
-				.prepare()
-				.fetch_one(connection)
+			R::retrieve(connection, R::Match::default())
 				.await
-				.and_then(|row| row.try_get::<Id, _>("id"))?;
-
-			R::retrieve(connection, R::Match::from(id)).await.map(|mut v| v.remove(0))
+				.and_then(|v| v.into_iter().max_by_key(id).ok_or(Error::RowNotFound))
 		}
 
 		/// Run a [`Create`] `command`.
 		async fn run(config: Config, command: CreateCommand)
 		{
-			Create { command, store_args: "default".into() }.run(config).await.unwrap()
+			Create { command, store_args: "default".into() }.run(config, &AutoAccept).await.unwrap()
 		}
 
-		let database_url = utils::database_url().unwrap();
-		let connection_fut = PgPool::connect(&database_url);
+		let connection_fut = Pool::<Db>::connect(database_url);
 		let exchange_rates_fut = ExchangeRates::new();
 		let mut config: Config = toml::from_str(&format!(
 			"[jobs]
@@ -389,7 +454,7 @@ mod tests
 			[organizations]
 
 			[stores.default]
-			adapter = 'postgres'
+			adapter = '{adapter}'
 			url = '{database_url}'",
 		))
 		.unwrap();
@@ -406,7 +471,7 @@ mod tests
 
 		let connection = connection_fut.await.unwrap();
 
-		let most_recent = latest_entity::<PgEmployee, EmployeeColumns<&str>>(&connection).await.unwrap();
+		let most_recent = latest_entity::<EAdapter, _>(&connection, |e| e.id).await.unwrap();
 
 		assert_eq!(most_recent.name, name);
 		assert_eq!(most_recent.status, status);
@@ -428,7 +493,7 @@ mod tests
 		})
 		.await;
 
-		let most_recent = latest_entity::<PgLocation, LocationColumns<&str>>(&connection).await.unwrap();
+		let most_recent = latest_entity::<LAdapter, _>(&connection, |l| l.id).await.unwrap();
 
 		assert_eq!(most_recent.name, arizona);
 		assert_eq!(most_recent.outer.unwrap().name, usa);
@@ -436,8 +501,8 @@ mod tests
 
 		// {{{
 		let (desert_view, earth) = futures::try_join!(
-			PgLocation::create(&connection, "Desert View".into(), None),
-			PgLocation::create(&connection, "Earth".into(), None),
+			LAdapter::create(&connection, "Desert View".into(), None),
+			LAdapter::create(&connection, "Earth".into(), None),
 		)
 		.unwrap();
 
@@ -455,8 +520,7 @@ mod tests
 		})
 		.await;
 
-		let db_desert_view =
-			PgLocation::retrieve(&connection, match_desert_view).await.map(|mut v| v.remove(0)).unwrap();
+		let db_desert_view = LAdapter::retrieve(&connection, match_desert_view).await.map(|mut v| v.remove(0)).unwrap();
 
 		assert_eq!(db_desert_view.id, desert_view.id);
 		assert_eq!(db_desert_view.name, desert_view.name);
@@ -486,7 +550,7 @@ mod tests
 		})
 		.await;
 
-		let most_recent = contact(&connection, label).await.unwrap();
+		let most_recent = contact::<CAdapter, _>(&connection, label).await.unwrap();
 
 		assert_eq!(most_recent.label, label);
 		assert_eq!(most_recent.kind.other(), Some(info));
@@ -504,7 +568,7 @@ mod tests
 		})
 		.await;
 
-		let most_recent2 = contact(&connection, label).await.unwrap();
+		let most_recent2 = contact::<CAdapter, _>(&connection, label).await.unwrap();
 
 		assert_eq!(most_recent2.label, label);
 		assert_eq!(most_recent2.kind.address().map(|a| a.id), Some(location_id));
@@ -523,7 +587,7 @@ mod tests
 		})
 		.await;
 
-		let most_recent3 = contact(&connection, label).await.unwrap();
+		let most_recent3 = contact::<CAdapter, _>(&connection, label).await.unwrap();
 
 		assert_eq!(most_recent3.label, label);
 		assert_eq!(most_recent3.kind.email(), Some(info));
@@ -542,14 +606,14 @@ mod tests
 		})
 		.await;
 
-		let most_recent4 = contact(&connection, label).await.unwrap();
+		let most_recent4 = contact::<CAdapter, _>(&connection, label).await.unwrap();
 
 		assert_eq!(most_recent4.label, label);
 		assert_eq!(most_recent4.kind.phone(), Some(info));
 		// }}}
 
 		// created contacts must be cleaned up
-		PgContact::delete(&connection, [most_recent, most_recent2, most_recent3, most_recent4].iter()).await.unwrap();
+		CAdapter::delete(&connection, [most_recent, most_recent2, most_recent3, most_recent4].iter()).await.unwrap();
 
 		/* ########## `winvoice create organization` ########## */
 
@@ -558,7 +622,7 @@ mod tests
 
 		run(config.clone(), CreateCommand::Organization { location: Some(filepath.clone()), name: name.into() }).await;
 
-		let most_recent = latest_entity::<PgOrganization, OrganizationColumns<&str>>(&connection).await.unwrap();
+		let most_recent = latest_entity::<OAdapter, _>(&connection, |o| o.id).await.unwrap();
 
 		assert_eq!(most_recent.location.id, location_id);
 		assert_eq!(most_recent.name, name);
@@ -587,7 +651,7 @@ mod tests
 		})
 		.await;
 
-		let most_recent = latest_entity::<PgJob, JobColumns<&str>>(&connection).await.unwrap();
+		let most_recent = latest_entity::<JAdapter, _>(&connection, |j| j.id).await.unwrap();
 		let exchange_rates = exchange_rates_fut.await.unwrap();
 
 		assert_eq!(most_recent.client.id, config.organizations.employer_id.unwrap());
@@ -630,7 +694,7 @@ mod tests
 		})
 		.await;
 
-		let most_recent = latest_entity::<PgJob, JobColumns<&str>>(&connection).await.unwrap();
+		let most_recent = latest_entity::<JAdapter, _>(&connection, |j| j.id).await.unwrap();
 
 		assert_eq!(most_recent.client.id, config.organizations.employer_id.unwrap());
 		assert_eq!(most_recent.date_open, utils::naive_local_datetime_to_utc(date_open));
@@ -659,7 +723,7 @@ mod tests
 		})
 		.await;
 
-		let most_recent = latest_entity::<PgTimesheet, TimesheetColumns<&str>>(&connection).await.unwrap();
+		let most_recent = latest_entity::<TAdapter, _>(&connection, |t| t.id).await.unwrap();
 
 		assert_eq!(most_recent.employee.id, config.employees.id.unwrap());
 		assert!(most_recent.expenses.is_empty());
@@ -691,7 +755,7 @@ mod tests
 		})
 		.await;
 
-		let most_recent = latest_entity::<PgTimesheet, TimesheetColumns<&str>>(&connection).await.unwrap();
+		let most_recent = latest_entity::<TAdapter, _>(&connection, |t| t.id).await.unwrap();
 
 		assert_eq!(most_recent.employee.id, config.employees.id.unwrap());
 		assert!(most_recent.expenses.is_empty());
@@ -730,7 +794,7 @@ mod tests
 		})
 		.await;
 
-		let most_recent = latest_entity::<PgExpenses, ExpenseColumns<&str>>(&connection).await.unwrap();
+		let most_recent = latest_entity::<XAdapter, _>(&connection, |x| x.id).await.unwrap();
 
 		assert_eq!(most_recent.category, category);
 		assert_eq!(most_recent.cost.exchange(Currency::Usd, &exchange_rates), cost);
@@ -738,4 +802,54 @@ mod tests
 		assert_eq!(most_recent.timesheet_id, timesheet_id);
 		// }}}
 	}
+
+	#[cfg(feature = "postgres")]
+	#[tokio::test]
+	async fn postgres()
+	{
+		use winvoice_adapter_postgres::schema::{
+			PgContact,
+			PgEmployee,
+			PgExpenses,
+			PgJob,
+			PgLocation,
+			PgOrganization,
+			PgTimesheet,
+		};
+
+		let database_url = utils::postgres_database().await;
+		run_action::<PgContact, PgEmployee, PgJob, PgLocation, PgOrganization, PgTimesheet, PgExpenses, _>(
+			"postgres",
+			&database_url,
+		)
+		.await;
+	}
+
+	#[cfg(feature = "sqlite")]
+	#[tokio::test]
+	async fn sqlite()
+	{
+		use crate::sqlite::schema::{
+			SqliteContact,
+			SqliteEmployee,
+			SqliteExpenses,
+			SqliteJob,
+			SqliteLocation,
+			SqliteOrganization,
+			SqliteTimesheet,
+		};
+
+		let database_url = utils::temp_sqlite_database::<Create>("run-action").await;
+		run_action::<
+			SqliteContact,
+			SqliteEmployee,
+			SqliteJob,
+			SqliteLocation,
+			SqliteOrganization,
+			SqliteTimesheet,
+			SqliteExpenses,
+			_,
+		>("sqlite", &database_url)
+		.await;
+	}
 }
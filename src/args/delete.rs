@@ -24,7 +24,7 @@ pub struct Delete
 	#[clap(flatten)]
 	match_args: MatchArgs,
 
-	/// Specifies the [`Store`](winvoice_config::Store) to [`Delete`] from.
+	/// Specifies the [`Store`](crate::config::Store) to [`Delete`] from.
 	#[clap(flatten)]
 	store_args: StoreArgs,
 }
@@ -22,6 +22,12 @@ pub enum DeleteCommand
 	/// Delete `Organization`s in the store (-s) specified.
 	Organization,
 
+	/// Delete the payments made towards `Job`s in the store (-s) specified.
+	///
+	/// The --match argument specifies the `Job`s to delete payments for. Invoices which are no
+	/// longer paid in full after deleting are marked as unpaid.
+	Payment,
+
 	/// Delete `Timesheet`s in the store (-s) specified.
 	Timesheet,
 }
@@ -2,7 +2,7 @@ use core::fmt::Display;
 use std::error::Error;
 
 use serde::{de::DeserializeOwned, Serialize};
-use sqlx::{Database, Executor, Pool};
+use sqlx::{Database, Executor, Pool, Transaction};
 use winvoice_adapter::{
 	schema::{
 		ContactAdapter,
@@ -16,10 +16,19 @@ use winvoice_adapter::{
 	Deletable,
 	Retrievable,
 };
-use winvoice_config::Config;
+use winvoice_schema::chrono::Utc;
 
 use super::{Delete, DeleteCommand};
-use crate::{args::RunAction, fmt, input, utils::Identifiable, DynResult};
+use crate::{
+	args::{update::Update, RunAction},
+	config::{Config, Extensions},
+	discounts::Discounts,
+	fmt,
+	input::{self, Prompter},
+	payments::{self, Ledger},
+	utils::Identifiable,
+	DynResult,
+};
 
 #[async_trait::async_trait(?Send)]
 impl RunAction for Delete
@@ -28,6 +37,7 @@ impl RunAction for Delete
 		self,
 		connection: Pool<Db>,
 		_config: Config,
+		prompter: &dyn Prompter,
 	) -> DynResult<()>
 	where
 		CAdapter: Deletable<Db = Db> + ContactAdapter,
@@ -37,12 +47,17 @@ impl RunAction for Delete
 		OAdapter: Deletable<Db = Db> + OrganizationAdapter,
 		TAdapter: Deletable<Db = Db> + TimesheetAdapter,
 		XAdapter: Deletable<Db = Db> + ExpensesAdapter,
-		Db: Database,
+		Db: Database + Discounts + Ledger,
 		for<'connection> &'connection mut Db::Connection: Executor<'connection, Database = Db>,
+		for<'connection> &'connection mut Transaction<'connection, Db>: Executor<'connection, Database = Db>,
 	{
 		/// A generic deletion function which works for any of the provided adapters in the outer
 		/// function, as they all implement `DelRetrievable` at the minimum.
-		async fn del<DelRetrievable, Db, Match>(connection: &Pool<Db>, match_condition: Match) -> DynResult<()>
+		async fn del<DelRetrievable, Db, Match>(
+			prompter: &dyn Prompter,
+			connection: &Pool<Db>,
+			match_condition: Match,
+		) -> DynResult<()>
 		where
 			Db: Database,
 			Match: TryInto<Option<DelRetrievable::Match>>,
@@ -50,23 +65,22 @@ impl RunAction for Delete
 			DelRetrievable: Deletable<Db = Db>,
 			<DelRetrievable as Deletable>::Entity: Clone + Display + Identifiable + Sync,
 			DelRetrievable: Retrievable<Db = Db, Entity = <DelRetrievable as Deletable>::Entity>,
-			DelRetrievable::Match: Default + DeserializeOwned + Serialize,
+			DelRetrievable::Match: Clone + Default + DeserializeOwned + Serialize,
 			for<'connection> &'connection mut Db::Connection: Executor<'connection, Database = Db>,
 		{
-			let match_condition = match_condition.try_into()?;
+			let match_condition: Option<_> = match_condition.try_into()?;
+			let narrowed = match_condition.is_some();
 			let type_name = fmt::type_name::<<DelRetrievable as Deletable>::Entity>();
 			let retrieved = input::select_retrieved::<DelRetrievable, _, _>(
+				prompter,
 				connection,
 				match_condition,
 				format!("Query the {type_name} to delete"),
 			)
 			.await?;
 
-			let selected = match cfg!(test)
-			{
-				false => input::select(retrieved, format!("Select the {type_name} to delete"))?,
-				true => retrieved,
-			};
+			let selected =
+				input::select_destructive(prompter, retrieved, narrowed, format!("Select the {type_name} to delete"))?;
 
 			DelRetrievable::delete(connection, selected.iter().inspect(|s| Delete::report_deleted(*s))).await?;
 			Ok(())
@@ -74,18 +88,70 @@ impl RunAction for Delete
 
 		match self.command
 		{
-			DeleteCommand::Contact => del::<CAdapter, _, _>(&connection, self.match_args).await,
-			DeleteCommand::Employee => del::<EAdapter, _, _>(&connection, self.match_args).await,
-			DeleteCommand::Expense => del::<XAdapter, _, _>(&connection, self.match_args).await,
-			DeleteCommand::Job => del::<JAdapter, _, _>(&connection, self.match_args).await,
-			DeleteCommand::Location => del::<LAdapter, _, _>(&connection, self.match_args).await,
-			DeleteCommand::Organization => del::<OAdapter, _, _>(&connection, self.match_args).await,
-			DeleteCommand::Timesheet => del::<TAdapter, _, _>(&connection, self.match_args).await,
+			DeleteCommand::Contact => del::<CAdapter, _, _>(prompter, &connection, self.match_args).await,
+			DeleteCommand::Employee => del::<EAdapter, _, _>(prompter, &connection, self.match_args).await,
+			DeleteCommand::Expense => del::<XAdapter, _, _>(prompter, &connection, self.match_args).await,
+			DeleteCommand::Job => del::<JAdapter, _, _>(prompter, &connection, self.match_args).await,
+			DeleteCommand::Location => del::<LAdapter, _, _>(prompter, &connection, self.match_args).await,
+			DeleteCommand::Organization => del::<OAdapter, _, _>(prompter, &connection, self.match_args).await,
+			DeleteCommand::Payment =>
+			{
+				let match_condition: Option<_> = self.match_args.try_into()?;
+				let narrowed = match_condition.is_some();
+				let jobs = input::select_retrieved::<JAdapter, _, _>(
+					prompter,
+					&connection,
+					match_condition,
+					"Query the Jobs whose Payments to delete",
+				)
+				.await?;
+
+				let job_ids: Vec<_> = jobs.iter().map(|j| j.id).collect();
+				let retrieved = Db::retrieve_payments(&mut *connection.acquire().await?, &job_ids).await?;
+				let selected =
+					input::select_destructive(prompter, retrieved, narrowed, "Select the Payments to delete")?;
+
+				let invoices = Extensions::read()?.invoices;
+
+				// {{{
+				let mut transaction = connection.begin().await?;
+
+				Db::delete_payments(&mut transaction, &selected).await?;
+				selected.iter().for_each(Self::report_deleted);
+
+				let discounts = Db::retrieve_discounts(&mut transaction, &job_ids).await?;
+				let payments = Db::retrieve_payments(&mut transaction, &job_ids).await?;
+				let store = self.store_args.name();
+				let mut updated = Vec::new();
+				for mut job in jobs.into_iter().filter(|j| selected.iter().any(|p| p.job_id == j.id))
+				{
+					let balance = payments::balance::<TAdapter, _>(
+						&connection,
+						&payments,
+						&discounts,
+						&invoices,
+						store,
+						&job,
+						None,
+					)
+					.await?;
+					if payments::reconcile(&mut job, balance, Utc::now())
+					{
+						updated.push(job);
+					}
+				}
+
+				JAdapter::update(&mut transaction, updated.iter().inspect(|j| Update::report_updated(*j))).await?;
+				transaction.commit().await.map_err(Into::into)
+				// }}}
+			},
+
+			DeleteCommand::Timesheet => del::<TAdapter, _, _>(prompter, &connection, self.match_args).await,
 		}
 	}
 }
 
-#[cfg(all(feature = "postgres", test))]
+#[cfg(all(any(feature = "postgres", feature = "sqlite"), test))]
 mod tests
 {
 	use core::{fmt::Debug, time::Duration};
@@ -93,7 +159,8 @@ mod tests
 
 	use pretty_assertions::assert_eq;
 	use serde::Serialize;
-	use sqlx::{PgPool, Postgres};
+	use serde_yaml as yaml;
+	use sqlx::{Database, Executor, Pool, Transaction};
 	use winvoice_adapter::{
 		schema::{
 			ContactAdapter,
@@ -104,47 +171,60 @@ mod tests
 			OrganizationAdapter,
 			TimesheetAdapter,
 		},
+		Deletable,
 		Retrievable,
 	};
-	use winvoice_adapter_postgres::schema::{
-		PgContact,
-		PgEmployee,
-		PgExpenses,
-		PgJob,
-		PgLocation,
-		PgOrganization,
-		PgTimesheet,
-	};
-	use winvoice_config::Config;
+	use winvoice_match::MatchLocation;
 	use winvoice_schema::{chrono::Utc, ContactKind::Other, Currency, Invoice, Money};
 
 	use super::{Delete, DeleteCommand, RunAction};
-	use crate::utils;
+	use crate::{
+		config::Config,
+		input::{Answer, AutoAccept, Scripted},
+		utils,
+	};
 
 	/// WARN: must use `cargo test -- --test-threads=1`.
-	#[tokio::test]
-	async fn run_action()
+	async fn run_action<CAdapter, EAdapter, JAdapter, LAdapter, OAdapter, TAdapter, XAdapter, Db>(
+		adapter: &str,
+		database_url: &str,
+	) where
+		CAdapter: Deletable<Db = Db> + ContactAdapter,
+		EAdapter: Deletable<Db = Db> + EmployeeAdapter,
+		JAdapter: Deletable<Db = Db> + JobAdapter,
+		LAdapter: Deletable<Db = Db> + LocationAdapter,
+		OAdapter: Deletable<Db = Db> + OrganizationAdapter,
+		TAdapter: Deletable<Db = Db> + TimesheetAdapter,
+		XAdapter: Deletable<Db = Db> + ExpensesAdapter,
+		Db: Database,
+		for<'connection> &'connection mut Db::Connection: Executor<'connection, Database = Db>,
+		for<'connection> &'connection mut Transaction<'connection, Db>: Executor<'connection, Database = Db>,
 	{
 		/// Runs the given `command`, then [assert](pretty_assertions)s that there are no rows in
 		/// the database matching the `condition` derived `from` the value specified.
-		async fn assert<R, T>(connection: &PgPool, command: DeleteCommand, config: Config, from: T, filepath: PathBuf)
-		where
-			R: Retrievable<Db = Postgres>,
+		async fn assert<R, Db, T>(
+			connection: &Pool<Db>,
+			command: DeleteCommand,
+			config: Config,
+			from: T,
+			filepath: PathBuf,
+		) where
+			Db: Database,
+			R: Retrievable<Db = Db>,
 			R::Entity: Debug + PartialEq,
 			R::Match: From<T> + Serialize,
 		{
 			let condition = R::Match::from(from);
 			utils::write_yaml(&filepath, &condition);
 			Delete { command, match_args: Some(filepath).into(), store_args: "default".into() }
-				.run(config)
+				.run(config, &AutoAccept)
 				.await
 				.unwrap();
 
-			assert_eq!(R::retrieve(&connection, condition).await.unwrap(), []);
+			assert_eq!(R::retrieve(connection, condition).await.unwrap(), []);
 		}
 
-		let database_url = utils::database_url().unwrap();
-		let connection_fut = PgPool::connect(&database_url);
+		let connection_fut = Pool::<Db>::connect(database_url);
 
 		let filepath = utils::temp_file::<Delete>("run-action");
 		let config: Config = toml::from_str(&format!(
@@ -159,7 +239,7 @@ mod tests
 			[organizations]
 
 			[stores.default]
-			adapter = 'postgres'
+			adapter = '{adapter}'
 			url = '{database_url}'",
 		))
 		.unwrap();
@@ -168,18 +248,18 @@ mod tests
 		let connection = connection_fut.await.unwrap();
 
 		let (contact, employee, location) = futures::try_join!(
-			PgContact::create(&connection, Other("Email".into()), "Preferred Contact".into()),
-			PgEmployee::create(&connection, "bob".into(), "bob status".into(), "bob title".into()),
-			PgLocation::create(&connection, "location".into(), None),
+			CAdapter::create(&connection, Other("Email".into()), "Preferred Contact".into()),
+			EAdapter::create(&connection, "bob".into(), "bob status".into(), "bob title".into()),
+			LAdapter::create(&connection, "location".into(), None),
 		)
 		.unwrap();
 
 		let (contact_label, employee_id, location_id) = (contact.label.clone(), employee.id, location.id);
 
-		let organization = PgOrganization::create(&connection, location, "Foo".into()).await.unwrap();
+		let organization = OAdapter::create(&connection, location, "Foo".into()).await.unwrap();
 		let organization_id = organization.id;
 
-		let job = PgJob::create(
+		let job = JAdapter::create(
 			&connection,
 			organization,
 			None,
@@ -194,13 +274,12 @@ mod tests
 		let job_id = job.id;
 
 		let mut transaction = connection.begin().await.unwrap();
-		let timesheet =
-			PgTimesheet::create(&mut transaction, employee, Vec::new(), job, Utc::now(), None, "Notes".into())
-				.await
-				.unwrap();
+		let timesheet = TAdapter::create(&mut transaction, employee, Vec::new(), job, Utc::now(), None, "Notes".into())
+			.await
+			.unwrap();
 		transaction.commit().await.unwrap();
 
-		let expense = PgExpenses::create(
+		let expense = XAdapter::create(
 			&connection,
 			vec![("Category".into(), Money::new(2, 0, Default::default()), "Desc".into())],
 			timesheet.id,
@@ -211,15 +290,15 @@ mod tests
 
 		/* }}}
 		 * Tests {{{ */
-		assert::<PgExpenses, _>(&connection, DeleteCommand::Expense, config.clone(), expense.id, filepath.clone())
+		assert::<XAdapter, _, _>(&connection, DeleteCommand::Expense, config.clone(), expense.id, filepath.clone())
 			.await;
 
-		assert::<PgTimesheet, _>(&connection, DeleteCommand::Timesheet, config.clone(), timesheet.id, filepath.clone())
+		assert::<TAdapter, _, _>(&connection, DeleteCommand::Timesheet, config.clone(), timesheet.id, filepath.clone())
 			.await;
 
-		assert::<PgJob, _>(&connection, DeleteCommand::Job, config.clone(), job_id, filepath.clone()).await;
+		assert::<JAdapter, _, _>(&connection, DeleteCommand::Job, config.clone(), job_id, filepath.clone()).await;
 
-		assert::<PgOrganization, _>(
+		assert::<OAdapter, _, _>(
 			&connection,
 			DeleteCommand::Organization,
 			config.clone(),
@@ -229,22 +308,102 @@ mod tests
 		.await;
 
 		futures::join!(
-			assert::<PgContact, _>(
+			assert::<CAdapter, _, _>(
 				&connection,
 				DeleteCommand::Contact,
 				config.clone(),
 				contact_label,
 				filepath.clone(),
 			),
-			assert::<PgEmployee, _>(
+			assert::<EAdapter, _, _>(
 				&connection,
 				DeleteCommand::Employee,
 				config.clone(),
 				employee_id,
 				filepath.clone(),
 			),
-			assert::<PgLocation, _>(&connection, DeleteCommand::Location, config, location_id, filepath,),
+			assert::<LAdapter, _, _>(&connection, DeleteCommand::Location, config.clone(), location_id, filepath,),
 		);
+
+		// {{{
+		let scripted = LAdapter::create(&connection, "scripted".into(), None).await.unwrap();
+		let condition = MatchLocation::from(scripted.id);
+
+		let answers = utils::temp_file::<Delete>("run-action-answers");
+		utils::write_yaml(&answers, [
+			Answer::Edit(Some(yaml::to_string(&MatchLocation::from(scripted.id + 1)).unwrap())),
+			Answer::Confirm(true),
+			Answer::Edit(Some(yaml::to_string(&condition).unwrap())),
+			Answer::Select(vec![0]),
+			Answer::Select(vec![0]),
+		]);
+
+		let prompter = Scripted::read(&answers).unwrap();
+		Delete { command: DeleteCommand::Location, match_args: None.into(), store_args: "default".into() }
+			.run(config.clone(), &prompter)
+			.await
+			.unwrap();
+
+		assert_eq!(LAdapter::retrieve(&connection, condition).await.unwrap(), []);
+
+		// nothing is left to retrieve, and there is no answer to "try again?"
+		utils::write_yaml(&answers, [Answer::Edit(None)]);
+		let prompter = Scripted::read(&answers).unwrap();
+		assert!(Delete { command: DeleteCommand::Location, match_args: None.into(), store_args: "default".into() }
+			.run(config, &prompter)
+			.await
+			.is_err());
+		// }}}
 		/* }}} */
 	}
+
+	#[cfg(feature = "postgres")]
+	#[tokio::test]
+	async fn postgres()
+	{
+		use winvoice_adapter_postgres::schema::{
+			PgContact,
+			PgEmployee,
+			PgExpenses,
+			PgJob,
+			PgLocation,
+			PgOrganization,
+			PgTimesheet,
+		};
+
+		let database_url = utils::postgres_database().await;
+		run_action::<PgContact, PgEmployee, PgJob, PgLocation, PgOrganization, PgTimesheet, PgExpenses, _>(
+			"postgres",
+			&database_url,
+		)
+		.await;
+	}
+
+	#[cfg(feature = "sqlite")]
+	#[tokio::test]
+	async fn sqlite()
+	{
+		use crate::sqlite::schema::{
+			SqliteContact,
+			SqliteEmployee,
+			SqliteExpenses,
+			SqliteJob,
+			SqliteLocation,
+			SqliteOrganization,
+			SqliteTimesheet,
+		};
+
+		let database_url = utils::temp_sqlite_database::<Delete>("run-action").await;
+		run_action::<
+			SqliteContact,
+			SqliteEmployee,
+			SqliteJob,
+			SqliteLocation,
+			SqliteOrganization,
+			SqliteTimesheet,
+			SqliteExpenses,
+			_,
+		>("sqlite", &database_url)
+		.await;
+	}
 }
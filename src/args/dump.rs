@@ -0,0 +1,29 @@
+mod as_ref;
+mod run_action;
+
+use std::path::PathBuf;
+
+use clap::Args as Clap;
+
+use super::store_args::StoreArgs;
+
+/// Write every entity in a store to an archive, which can be restored into any store with
+/// `winvoice restore`.
+///
+/// The archive is JSON if the file has a `.json` extension, and YAML otherwise. Data which is kept
+/// outside of the store (e.g. payments and invoice numbers) is not archived.
+#[derive(Clap, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Dump
+{
+	/// The file to write the archive to.
+	#[clap(value_name = "FILE", value_parser)]
+	file: PathBuf,
+
+	/// Overwrite the file if it already exists.
+	#[clap(action, long)]
+	force: bool,
+
+	/// Specifies the [`Store`](crate::config::Store) to [`Dump`].
+	#[clap(flatten)]
+	store_args: StoreArgs,
+}
@@ -0,0 +1,10 @@
+use super::Dump;
+use crate::args::store_args::StoreArgs;
+
+impl AsRef<StoreArgs> for Dump
+{
+	fn as_ref(&self) -> &StoreArgs
+	{
+		&self.store_args
+	}
+}
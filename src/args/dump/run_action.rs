@@ -0,0 +1,56 @@
+use sqlx::{Database, Executor, Pool, Transaction};
+use winvoice_adapter::{
+	schema::{
+		ContactAdapter,
+		EmployeeAdapter,
+		ExpensesAdapter,
+		JobAdapter,
+		LocationAdapter,
+		OrganizationAdapter,
+		TimesheetAdapter,
+	},
+	Deletable,
+};
+
+use super::Dump;
+use crate::{
+	archive::Archive,
+	args::RunAction,
+	config::Config,
+	discounts::Discounts,
+	input::Prompter,
+	numbering::Register,
+	payments::Ledger,
+	DynResult,
+};
+
+#[async_trait::async_trait(?Send)]
+impl RunAction for Dump
+{
+	async fn action<CAdapter, EAdapter, JAdapter, LAdapter, OAdapter, TAdapter, XAdapter, Db>(
+		self,
+		connection: Pool<Db>,
+		_config: Config,
+		_prompter: &dyn Prompter,
+	) -> DynResult<()>
+	where
+		CAdapter: Deletable<Db = Db> + ContactAdapter,
+		EAdapter: Deletable<Db = Db> + EmployeeAdapter,
+		JAdapter: Deletable<Db = Db> + JobAdapter,
+		LAdapter: Deletable<Db = Db> + LocationAdapter,
+		OAdapter: Deletable<Db = Db> + OrganizationAdapter,
+		TAdapter: Deletable<Db = Db> + TimesheetAdapter,
+		XAdapter: Deletable<Db = Db> + ExpensesAdapter,
+		Db: Database + Discounts + Ledger + Register,
+		for<'connection> &'connection mut Db::Connection: Executor<'connection, Database = Db>,
+		for<'connection> &'connection mut Transaction<'connection, Db>: Executor<'connection, Database = Db>,
+	{
+		let archive =
+			Archive::retrieve::<CAdapter, EAdapter, JAdapter, LAdapter, OAdapter, TAdapter, _>(&connection).await?;
+
+		archive.write(&self.file, self.force).await?;
+		archive.counts().into_iter().for_each(|(kind, count)| println!("Dumped {count} {kind}(s)"));
+
+		Ok(())
+	}
+}
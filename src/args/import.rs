@@ -0,0 +1,24 @@
+mod as_ref;
+mod command;
+mod run_action;
+
+use clap::Args as Clap;
+use command::ImportCommand;
+
+use super::store_args::StoreArgs;
+
+/// Import information which was recorded by some other program.
+///
+/// Every row which is imported is checked before anything is created, and nothing is created if
+/// any row has a problem.
+#[derive(Clap, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Import
+{
+	/// The specific object to [`Import`] and related arguments.
+	#[clap(subcommand)]
+	command: ImportCommand,
+
+	/// Specifies the [`Store`](crate::config::Store) to [`Import`] into.
+	#[clap(flatten)]
+	store_args: StoreArgs,
+}
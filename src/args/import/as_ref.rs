@@ -0,0 +1,10 @@
+use super::Import;
+use crate::args::store_args::StoreArgs;
+
+impl AsRef<StoreArgs> for Import
+{
+	fn as_ref(&self) -> &StoreArgs
+	{
+		&self.store_args
+	}
+}
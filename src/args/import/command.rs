@@ -0,0 +1,49 @@
+use std::path::PathBuf;
+
+use clap::{ArgGroup, Subcommand as Clap};
+
+/// The specific type of information that is being imported.
+#[derive(Clap, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum ImportCommand
+{
+	/// Import `Timesheet`s into the store (-s) specified.
+	///
+	/// `Timesheet`s which have the same `Job`, `time_begin`, and `time_end` as one which already
+	/// exists are skipped, so that the same file can be imported more than once.
+	#[clap(group(ArgGroup::new("file").required(true)))]
+	Timesheet
+	{
+		/// A CSV file with a header row, and one row per `Timesheet`.
+		///
+		/// The `employee` column is the `id` or `name` of an `Employee`, and the `job` column is
+		/// the `id` or `objectives` of a `Job`. The `begin` and `end` columns are local dates and
+		/// times (e.g. "2022-01-01 13:30"), and the `work_notes` (or `notes`) column is optional.
+		/// Files exported by `winvoice retrieve timesheet --export csv` can be imported.
+		#[clap(group = "file", long, value_name = "FILE", value_parser)]
+		csv: Option<PathBuf>,
+
+		/// A YAML file which maps the names used by other programs onto `Employee`s and `Job`s,
+		/// instead of `import-mappings.yaml` in the Winvoice data directory.
+		///
+		/// Each store has a map of `employees` (from users to the `id` or `name` of an `Employee`)
+		/// and a map of `jobs` (from tags or projects to the `id` or `objectives` of a `Job`).
+		#[clap(long, value_name = "FILE", value_parser)]
+		mapping: Option<PathBuf>,
+
+		/// A JSON file which was exported by `timew export`.
+		///
+		/// Each interval is worked on by the `Employee` specified by the `id` field of the
+		/// `[employees]` section of the Winvoice configuration file, for the `Job` which its tags
+		/// are mapped to (see --mapping).
+		#[clap(group = "file", long, value_name = "FILE", value_parser)]
+		timewarrior: Option<PathBuf>,
+
+		/// A CSV file which was exported from the detailed report of Toggl.
+		///
+		/// Each entry is worked on by the `Employee` which its user is mapped to (or the one
+		/// specified by the `id` field of the `[employees]` section of the Winvoice configuration
+		/// file), for the `Job` which its project and tags are mapped to (see --mapping).
+		#[clap(group = "file", long, value_name = "FILE", value_parser)]
+		toggl: Option<PathBuf>,
+	},
+}
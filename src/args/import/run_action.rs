@@ -0,0 +1,224 @@
+use std::{
+	collections::{BTreeMap, BTreeSet},
+	fs::File,
+};
+
+use sqlx::{Database, Executor, Pool, Transaction};
+use winvoice_adapter::{
+	schema::{
+		ContactAdapter,
+		EmployeeAdapter,
+		ExpensesAdapter,
+		JobAdapter,
+		LocationAdapter,
+		OrganizationAdapter,
+		TimesheetAdapter,
+	},
+	Deletable,
+	Retrievable,
+};
+use winvoice_match::{Match, MatchEmployee, MatchJob, MatchTimesheet};
+use winvoice_schema::{
+	chrono::{DateTime, Utc},
+	Employee,
+	Id,
+	Job,
+};
+
+use super::{command::ImportCommand, Import};
+use crate::{
+	args::RunAction,
+	config::Config,
+	import::{self, Entry, Invalid, Mappings, Rejected},
+	input::Prompter,
+	utils,
+	DynResult,
+};
+
+/// Retrieve the only entity which `key` (an `id` or some other unique field) refers to, for each
+/// distinct `key`.
+///
+/// When a `key` does not refer to exactly one entity, it is mapped to a message which explains why.
+async fn resolve<'key, R, Db>(
+	connection: &Pool<Db>,
+	keys: impl IntoIterator<Item = &'key str>,
+	kind: &str,
+	condition: impl Fn(&str) -> R::Match,
+) -> DynResult<BTreeMap<&'key str, Result<R::Entity, String>>>
+where
+	Db: Database,
+	R: Retrievable<Db = Db>,
+{
+	let mut resolved = BTreeMap::new();
+	for key in keys
+	{
+		if resolved.contains_key(key)
+		{
+			continue;
+		}
+
+		let mut retrieved = R::retrieve(connection, condition(key)).await?;
+		let entity = match retrieved.len()
+		{
+			0 => Err(format!("There is no {kind} {key:?}")),
+			1 => Ok(retrieved.remove(0)),
+			n => Err(format!("{key:?} refers to {n} {kind}s, so the {kind} must be specified by id")),
+		};
+
+		resolved.insert(key, entity);
+	}
+
+	Ok(resolved)
+}
+
+impl Import
+{
+	/// Create a `Timesheet` for each of the `entries`, in one transaction.
+	///
+	/// Each `Employee` and `Job` is resolved first. If any of them could not be resolved, or any of
+	/// the `invalid` entries exist, then nothing is created and every problem is reported. Entries
+	/// with the same `Job`, `time_begin`, and `time_end` as an existing `Timesheet` (or an earlier
+	/// entry) are skipped.
+	async fn import_timesheets<EAdapter, JAdapter, TAdapter, Db>(
+		connection: &Pool<Db>,
+		entries: Vec<Entry>,
+		mut invalid: Vec<Invalid>,
+	) -> DynResult<()>
+	where
+		EAdapter: Deletable<Db = Db> + EmployeeAdapter,
+		JAdapter: Deletable<Db = Db> + JobAdapter,
+		TAdapter: Deletable<Db = Db> + TimesheetAdapter,
+		Db: Database,
+		for<'connection> &'connection mut Transaction<'connection, Db>: Executor<'connection, Database = Db>,
+	{
+		let employees =
+			resolve::<EAdapter, _>(connection, entries.iter().map(|e| e.employee.as_str()), "Employee", |key| {
+				key.parse::<Id>().map_or_else(
+					|_| MatchEmployee { name: key.to_owned().into(), ..Default::default() },
+					MatchEmployee::from,
+				)
+			})
+			.await?;
+
+		let jobs = resolve::<JAdapter, _>(connection, entries.iter().map(|e| e.job.as_str()), "Job", |key| {
+			key.parse::<Id>()
+				.map_or_else(|_| MatchJob { objectives: key.to_owned().into(), ..Default::default() }, MatchJob::from)
+		})
+		.await?;
+
+		let mut resolved = Vec::<(Employee, Job, &Entry)>::with_capacity(entries.len());
+		entries.iter().for_each(|entry| match (&employees[entry.employee.as_str()], &jobs[entry.job.as_str()])
+		{
+			(Ok(employee), Ok(job)) => resolved.push((employee.clone(), job.clone(), entry)),
+			(employee, job) => invalid.extend(
+				[employee.as_ref().err(), job.as_ref().err()]
+					.into_iter()
+					.flatten()
+					.map(|message| Invalid { line: entry.line, message: message.clone() }),
+			),
+		});
+
+		if !invalid.is_empty()
+		{
+			invalid.sort();
+			return Err(Rejected(invalid).into());
+		}
+
+		let job_ids: BTreeSet<_> = resolved.iter().map(|(_, job, _)| job.id).collect();
+		let mut imported: BTreeSet<(Id, DateTime<Utc>, Option<DateTime<Utc>>)> = match job_ids.is_empty()
+		{
+			false => TAdapter::retrieve(connection, MatchTimesheet {
+				job: MatchJob { id: Match::Or(job_ids.into_iter().map(Match::from).collect()), ..Default::default() },
+				..Default::default()
+			})
+			.await?
+			.into_iter()
+			.map(|t| (t.job.id, t.time_begin, t.time_end))
+			.collect(),
+			true => BTreeSet::new(),
+		};
+
+		let count = resolved.len();
+		resolved.retain(|(_, job, entry)| imported.insert((job.id, entry.time_begin, entry.time_end)));
+
+		// {{{
+		let mut transaction = connection.begin().await?;
+
+		let mut created = Vec::with_capacity(resolved.len());
+		for (employee, job, entry) in resolved
+		{
+			created.push(
+				TAdapter::create(
+					&mut transaction,
+					employee,
+					Vec::new(),
+					job,
+					entry.time_begin,
+					entry.time_end,
+					entry.work_notes.clone(),
+				)
+				.await?,
+			);
+		}
+
+		transaction.commit().await?;
+		// }}}
+
+		created.iter().for_each(|t| utils::report_action("imported", t));
+		println!("Imported {} Timesheet(s)", created.len());
+		if created.len() < count
+		{
+			println!("Skipped {} Timesheet(s) which had already been imported", count - created.len());
+		}
+
+		Ok(())
+	}
+}
+
+#[async_trait::async_trait(?Send)]
+impl RunAction for Import
+{
+	async fn action<CAdapter, EAdapter, JAdapter, LAdapter, OAdapter, TAdapter, XAdapter, Db>(
+		self,
+		connection: Pool<Db>,
+		config: Config,
+		_prompter: &dyn Prompter,
+	) -> DynResult<()>
+	where
+		CAdapter: Deletable<Db = Db> + ContactAdapter,
+		EAdapter: Deletable<Db = Db> + EmployeeAdapter,
+		JAdapter: Deletable<Db = Db> + JobAdapter,
+		LAdapter: Deletable<Db = Db> + LocationAdapter,
+		OAdapter: Deletable<Db = Db> + OrganizationAdapter,
+		TAdapter: Deletable<Db = Db> + TimesheetAdapter,
+		XAdapter: Deletable<Db = Db> + ExpensesAdapter,
+		Db: Database,
+		for<'connection> &'connection mut Db::Connection: Executor<'connection, Database = Db>,
+		for<'connection> &'connection mut Transaction<'connection, Db>: Executor<'connection, Database = Db>,
+	{
+		match self.command
+		{
+			ImportCommand::Timesheet { csv, mapping, timewarrior, toggl } =>
+			{
+				let mapping = Mappings::read(mapping.as_deref())?.get(self.store_args.name());
+				let (entries, invalid) = match (csv, timewarrior, toggl)
+				{
+					(Some(filepath), ..) => import::csv(File::open(filepath)?),
+					(_, Some(filepath), _) =>
+					{
+						let employee = config.employees.id_or_err()?.to_string();
+						import::timewarrior(File::open(filepath)?, &employee, &mapping)
+					},
+					(.., Some(filepath)) =>
+					{
+						let employee = config.employees.id.map(|id| id.to_string());
+						import::toggl(File::open(filepath)?, employee.as_deref(), &mapping)
+					},
+					(None, None, None) => unreachable!("clap should require one of the files to import"),
+				};
+
+				Self::import_timesheets::<EAdapter, JAdapter, TAdapter, _>(&connection, entries, invalid).await
+			},
+		}
+	}
+}
@@ -1,18 +1,22 @@
 use clap::Args as Clap;
 use sqlx::Connection;
 use winvoice_adapter::Initializable;
-use winvoice_config::{Adapters, Config, Error};
 
 use super::store_args::StoreArgs;
-use crate::DynResult;
+use crate::{
+	config::{Adapter, Config, FeatureNotFound},
+	migration,
+	DynResult,
+};
 
 /// Prepare the specified store (-s) for use with Winvoice.
 ///
-/// Will not clobber existing data. Should only be run by administrators.
+/// Will not clobber existing data. Also applies any migrations which are pending (see `winvoice
+/// migrate`). Should only be run by administrators.
 #[derive(Clap, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct Init
 {
-	/// Specifies the [`Store`](winvoice_config::Store) to [`Init`].
+	/// Specifies the [`Store`](crate::config::Store) to [`Init`].
 	#[clap(flatten)]
 	store_args: StoreArgs,
 }
@@ -29,19 +33,32 @@ impl Init
 		match store.adapter
 		{
 			#[cfg(feature = "postgres")]
-			Adapters::Postgres =>
+			Adapter::Postgres =>
 			{
-				use sqlx::PgConnection;
+				use sqlx::{PgConnection, Postgres};
 				use winvoice_adapter_postgres::PgSchema;
 
 				let mut connection = PgConnection::connect(&store.url).await?;
 				PgSchema::init(&mut connection).await?;
+				migration::upgrade::<Postgres>(&mut connection, store.adapter).await?;
+			},
+
+			#[cfg(feature = "sqlite")]
+			Adapter::Sqlite =>
+			{
+				use sqlx::{Sqlite, SqliteConnection};
+
+				use crate::sqlite::{self, SqliteSchema};
+
+				let mut connection = SqliteConnection::connect_with(&sqlite::connect_options(&store.url)?).await?;
+				SqliteSchema::init(&mut connection).await?;
+				migration::upgrade::<Sqlite>(&mut connection, store.adapter).await?;
 			},
 
 			// NOTE: this is allowed because there may be additional adapters added later, and I
 			// want       to define this behavior now.
 			#[allow(unreachable_patterns)]
-			_ => return Err(Error::FeatureNotFound(store.adapter).into()),
+			_ => return Err(FeatureNotFound(store.adapter).into()),
 		};
 
 		Ok(())
@@ -17,4 +17,18 @@ pub struct MatchArgs
 	/// for deserialized, and you will not be prompted to write a new search.
 	#[clap(long, short, value_name = "FILE", value_parser)]
 	r#match: Option<PathBuf>,
+
+	/// The name of a query which was saved using `winvoice retrieve --save-query`, which can be
+	/// used instead of --match.
+	#[clap(conflicts_with_all(&["match", "where"]), long, short, value_name = "NAME")]
+	query: Option<String>,
+
+	/// An inline match condition, which can be used instead of --match.
+	///
+	/// Fields are compared to values using `=`, `!=`, `~` (contains), `=~` (regex), `<`, `<=`,
+	/// `>`, or `>=`, and comparisons can be combined using `and`, `or`, `not`, and parentheses.
+	/// Nested fields are separated by `.`, and values containing spaces must be "quoted". e.g.
+	/// `client.name ~ "Acme" and date_close = null`.
+	#[clap(conflicts_with("match"), long, short, value_name = "EXPRESSION")]
+	r#where: Option<String>,
 }
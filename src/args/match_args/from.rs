@@ -6,6 +6,6 @@ impl From<Option<PathBuf>> for MatchArgs
 {
 	fn from(args: Option<PathBuf>) -> Self
 	{
-		Self { r#match: args }
+		Self { r#match: args, query: None, r#where: None }
 	}
 }
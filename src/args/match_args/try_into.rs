@@ -12,7 +12,11 @@ use winvoice_match::{
 };
 
 use super::MatchArgs;
-use crate::input::{Error, Result};
+use crate::{
+	config::Extensions,
+	fmt,
+	input::{expression, Error, Result},
+};
 
 macro_rules! impl_try_into {
 	($T:ty) => {
@@ -22,6 +26,18 @@ macro_rules! impl_try_into {
 
 			fn try_into(self) -> Result<Option<$T>>
 			{
+				if let Some(name) = self.query
+				{
+					return Extensions::read()?.queries.get(&name)?.map(Some).ok_or_else(|| {
+						Error::UnknownQuery(name, fmt::type_name::<$T>().trim_start_matches("Match").into())
+					});
+				}
+
+				if let Some(expression) = self.r#where
+				{
+					return expression::parse(&expression).map(Some).map_err(Error::from);
+				}
+
 				let contents = self.r#match.map(fs::read_to_string).transpose()?;
 				let deserialized = contents.as_deref().map(yaml::from_str).transpose()?;
 				Ok(deserialized)
@@ -0,0 +1,93 @@
+use clap::Args as Clap;
+use sqlx::{ColumnIndex, Connection, Database, Decode, Executor, Type};
+
+use super::store_args::StoreArgs;
+use crate::{
+	config::{Adapter, Config, FeatureNotFound},
+	migration,
+	DynResult,
+};
+
+/// Upgrade the schema of the specified store (-s) to the version used by this version of Winvoice.
+///
+/// Migrations are applied in order, in one transaction. Should only be run by administrators.
+#[derive(Clap, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Migrate
+{
+	/// Print the SQL of the migrations which would be applied, instead of applying them.
+	#[clap(action, long)]
+	dry_run: bool,
+
+	/// Specifies the [`Store`](crate::config::Store) to [`Migrate`].
+	#[clap(flatten)]
+	store_args: StoreArgs,
+}
+
+impl Migrate
+{
+	/// Apply (or print) the migrations which are pending for the store which the `connection` is
+	/// to, using some `adapter`.
+	async fn migrate<Db>(self, connection: &mut Db::Connection, adapter: Adapter) -> DynResult<()>
+	where
+		Db: Database,
+		i64: Type<Db> + for<'row> Decode<'row, Db>,
+		usize: ColumnIndex<Db::Row>,
+		for<'connection> &'connection mut Db::Connection: Executor<'connection, Database = Db>,
+	{
+		let version = migration::version::<Db>(connection, adapter).await?;
+		let pending = migration::pending(version)?;
+
+		if pending.is_empty()
+		{
+			println!("The store is up to date (version {version})");
+		}
+		else if self.dry_run
+		{
+			print!("{}", migration::script(adapter, pending));
+		}
+		else
+		{
+			migration::apply::<Db>(connection, adapter, pending).await?;
+		}
+
+		Ok(())
+	}
+
+	/// # Summary
+	///
+	/// Execute the constructed command.
+	pub async fn run(self, config: &Config) -> DynResult<()>
+	{
+		let store = self.store_args.try_get_from(config)?;
+
+		match store.adapter
+		{
+			#[cfg(feature = "postgres")]
+			Adapter::Postgres =>
+			{
+				use sqlx::{PgConnection, Postgres};
+
+				let mut connection = PgConnection::connect(&store.url).await?;
+				self.migrate::<Postgres>(&mut connection, store.adapter).await?;
+			},
+
+			#[cfg(feature = "sqlite")]
+			Adapter::Sqlite =>
+			{
+				use sqlx::{Sqlite, SqliteConnection};
+
+				use crate::sqlite;
+
+				let mut connection = SqliteConnection::connect_with(&sqlite::connect_options(&store.url)?).await?;
+				self.migrate::<Sqlite>(&mut connection, store.adapter).await?;
+			},
+
+			// NOTE: this is allowed because there may be additional adapters added later, and I
+			// want       to define this behavior now.
+			#[allow(unreachable_patterns)]
+			_ => return Err(FeatureNotFound(store.adapter).into()),
+		};
+
+		Ok(())
+	}
+}
@@ -0,0 +1,274 @@
+mod as_ref;
+mod run_action;
+
+use std::{collections::BTreeMap, path::PathBuf};
+
+use clap::{Args as Clap, ValueEnum};
+use money2::{Currency, Decimal};
+use serde::Serialize;
+use winvoice_schema::{
+	chrono::{DateTime, Local, Utc},
+	Id,
+	Money,
+	Timesheet,
+};
+
+use super::{match_args::MatchArgs, store_args::StoreArgs};
+use crate::{
+	fmt::{self, OutputFormat},
+	utils::Billable,
+};
+
+/// Summarize the hours worked on `Timesheet`s, and the revenue they earned.
+///
+/// Hours are rounded up to the `increment` of each `Job`, and billed at its `hourly_rate`.
+#[derive(Clap, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Report
+{
+	/// Group the `Timesheet`s by their client, employee, and/or job (e.g. "client,job"). When not
+	/// specified, every `Timesheet` is summarized together.
+	#[clap(long, short, value_delimiter = ',', value_enum, value_name = "GROUP")]
+	by: Vec<Group>,
+
+	/// The currency to summarize revenue in. Defaults to the `default_currency` field of the
+	/// `[invoices]` section of the Winvoice configuration file.
+	///
+	/// See `winvoice retrieve job --rates` for how exchange rates are obtained.
+	#[clap(long, short)]
+	currency: Option<Currency>,
+
+	/// Print the summary in the specified format.
+	#[clap(default_value = "table", long, short, value_enum, value_name = "FORMAT")]
+	format: OutputFormat,
+
+	/// Specifies the `Timesheet`s to summarize. When not specified, every `Timesheet` is
+	/// summarized.
+	#[clap(flatten)]
+	match_args: MatchArgs,
+
+	/// Group the `Timesheet`s by the period of time in which they began.
+	#[clap(long, short, value_enum)]
+	period: Option<Period>,
+
+	/// A file of exchange rates to use instead of fetching them. See `winvoice retrieve job
+	/// --rates`.
+	#[clap(long, short, value_name = "FILE", value_parser)]
+	rates: Option<PathBuf>,
+
+	/// Specifies the [`Store`](crate::config::Store) containing the `Timesheet`s.
+	#[clap(flatten)]
+	store_args: StoreArgs,
+}
+
+/// Something which the `Timesheet`s of a [`Report`] can be grouped by.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, ValueEnum)]
+pub enum Group
+{
+	/// The `client` of each `Job`.
+	Client,
+
+	/// The `Employee` who did the work.
+	Employee,
+
+	/// The `Job` which was worked on.
+	Job,
+}
+
+/// A span of time which the `Timesheet`s of a [`Report`] can be grouped by.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, ValueEnum)]
+pub enum Period
+{
+	/// e.g. "2022-01-31".
+	Day,
+
+	/// An ISO 8601 week, e.g. "2022-W05".
+	Week,
+
+	/// e.g. "2022-01".
+	Month,
+
+	/// e.g. "2022".
+	Year,
+}
+
+impl Period
+{
+	/// The period which the `date` is within, in the [`Local`] timezone.
+	fn of(self, date: DateTime<Utc>) -> String
+	{
+		let format = match self
+		{
+			Self::Day => "%Y-%m-%d",
+			Self::Week => "%G-W%V",
+			Self::Month => "%Y-%m",
+			Self::Year => "%Y",
+		};
+
+		date.with_timezone(&Local).format(format).to_string()
+	}
+}
+
+/// A summary of some `Timesheet`s which share the same groups.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+struct Row
+{
+	/// See [`Report::period`].
+	#[serde(skip_serializing_if = "Option::is_none")]
+	period: Option<String>,
+
+	/// See [`Group::Client`].
+	#[serde(skip_serializing_if = "Option::is_none")]
+	client: Option<String>,
+
+	/// See [`Group::Job`].
+	#[serde(skip_serializing_if = "Option::is_none")]
+	job: Option<String>,
+
+	/// See [`Group::Employee`].
+	#[serde(skip_serializing_if = "Option::is_none")]
+	employee: Option<String>,
+
+	/// See [`Billable::hours`].
+	hours: Decimal,
+
+	/// See [`Billable::labor`].
+	labor: Money,
+
+	/// The sum of each `Expense`.
+	expenses: Money,
+
+	/// See [`Billable::total`].
+	total: Money,
+}
+
+impl Report
+{
+	/// Summarize the `timesheets`, grouping them by the `period` and each of the `groups`.
+	///
+	/// Each [`Row`] is in the order of its period, and then the [`Id`]s of its groups. All of the
+	/// `timesheets` must be in the `currency`.
+	fn summarize(timesheets: &[Timesheet], groups: &[Group], period: Option<Period>, currency: Currency) -> Vec<Row>
+	{
+		let grouped = |group| groups.contains(&group);
+		let zero = Money { amount: Decimal::ZERO, currency };
+
+		timesheets
+			.iter()
+			.fold(BTreeMap::<(Option<String>, Option<Id>, Option<Id>, Option<Id>), Row>::new(), |mut rows, t| {
+				let key = (
+					period.map(|p| p.of(t.time_begin)),
+					grouped(Group::Client).then_some(t.job.client.id),
+					grouped(Group::Job).then_some(t.job.id),
+					grouped(Group::Employee).then_some(t.employee.id),
+				);
+
+				let row = rows.entry(key).or_insert_with_key(|(p, client, job, employee)| Row {
+					period: p.clone(),
+					client: client.map(|_| t.job.client.name.clone()),
+					job: job.map(|id| {
+						format!("{} {}", fmt::id_num(id), t.job.objectives.lines().next().unwrap_or_default())
+					}),
+					employee: employee.map(|_| t.employee.name.clone()),
+					hours: Decimal::ZERO,
+					labor: zero,
+					expenses: zero,
+					total: zero,
+				});
+
+				let labor = t.labor();
+				let expenses = t.expenses.iter().fold(Decimal::ZERO, |sum, x| sum + x.cost.amount);
+
+				row.hours += t.hours();
+				row.labor.amount += labor.amount;
+				row.expenses.amount += expenses;
+				row.total.amount += labor.amount + expenses;
+				rows
+			})
+			.into_values()
+			.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use core::time::Duration;
+
+	use pretty_assertions::assert_eq;
+	use winvoice_schema::{
+		chrono::{TimeZone, Utc},
+		Currency,
+		Employee,
+		Expense,
+		Invoice,
+		Job,
+		Location,
+		Money,
+		Organization,
+		Timesheet,
+	};
+
+	use super::{Group, Period, Report};
+
+	#[test]
+	fn summarize()
+	{
+		let location = Location { id: 0, name: "Earth".into(), outer: None };
+		let job = Job {
+			client: Organization { id: 1, location, name: "Acme".into() },
+			date_close: None,
+			date_open: Utc.ymd(2022, 1, 1).and_hms(0, 0, 0),
+			id: 3,
+			increment: Duration::from_secs(15 * 60),
+			invoice: Invoice { date: None, hourly_rate: Money::new(20_00, 2, Currency::Usd) },
+			notes: String::new(),
+			objectives: "Write code\nand tests".into(),
+		};
+
+		let alice = Employee { id: 0, name: "Alice".into(), status: "Contractor".into(), title: "Dev".into() };
+		let bob = Employee { id: 1, name: "Bob".into(), ..alice.clone() };
+		let timesheet = |employee: &Employee, day, expenses| Timesheet {
+			id: 0,
+			employee: employee.clone(),
+			expenses,
+			job: job.clone(),
+			time_begin: Utc.ymd(2022, 1, day).and_hms(12, 0, 0),
+			time_end: Some(Utc.ymd(2022, 1, day).and_hms(13, 20, 0)),
+			work_notes: String::new(),
+		};
+
+		let lunch = Expense {
+			id: 0,
+			category: "Food".into(),
+			cost: Money::new(5_00, 2, Currency::Usd),
+			description: "Lunch".into(),
+			timesheet_id: 0,
+		};
+
+		let timesheets =
+			[timesheet(&bob, 3, vec![lunch]), timesheet(&alice, 4, Vec::new()), timesheet(&bob, 20, Vec::new())];
+
+		// 1h20m rounds up to 1.5h at 20.00 USD/h
+		let rows = Report::summarize(&timesheets, &[], None, Currency::Usd);
+		assert_eq!(rows.len(), 1);
+		assert_eq!(rows[0].client, None);
+		assert_eq!(rows[0].hours.to_string(), "4.50");
+		assert_eq!(rows[0].labor, Money::new(90_00, 2, Currency::Usd));
+		assert_eq!(rows[0].expenses, Money::new(5_00, 2, Currency::Usd));
+		assert_eq!(rows[0].total, Money::new(95_00, 2, Currency::Usd));
+
+		let rows = Report::summarize(&timesheets, &[Group::Employee, Group::Job], None, Currency::Usd);
+		assert_eq!(rows.len(), 2);
+		assert_eq!(rows[0].employee.as_deref(), Some("Alice"));
+		assert_eq!(rows[0].job.as_deref(), Some("№3 Write code"));
+		assert_eq!(rows[1].employee.as_deref(), Some("Bob"));
+		assert_eq!(rows[1].total, Money::new(65_00, 2, Currency::Usd));
+
+		let rows = Report::summarize(&timesheets, &[Group::Client], Some(Period::Week), Currency::Usd);
+		assert_eq!(rows.len(), 2);
+		assert_eq!(rows[0].client.as_deref(), Some("Acme"));
+		assert_eq!(rows[0].period.as_deref(), Some("2022-W01"));
+		assert_eq!(rows[0].labor, Money::new(60_00, 2, Currency::Usd));
+		assert_eq!(rows[1].period.as_deref(), Some("2022-W03"));
+	}
+}
@@ -0,0 +1,10 @@
+use super::Report;
+use crate::args::store_args::StoreArgs;
+
+impl AsRef<StoreArgs> for Report
+{
+	fn as_ref(&self) -> &StoreArgs
+	{
+		&self.store_args
+	}
+}
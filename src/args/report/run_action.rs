@@ -0,0 +1,58 @@
+use sqlx::{Database, Executor, Pool, Transaction};
+use winvoice_adapter::{
+	schema::{
+		ContactAdapter,
+		EmployeeAdapter,
+		ExpensesAdapter,
+		JobAdapter,
+		LocationAdapter,
+		OrganizationAdapter,
+		TimesheetAdapter,
+	},
+	Deletable,
+};
+use winvoice_match::MatchTimesheet;
+
+use super::Report;
+use crate::{
+	args::RunAction,
+	config::{Config, Extensions},
+	exchange,
+	input::Prompter,
+	DynResult,
+};
+
+#[async_trait::async_trait(?Send)]
+impl RunAction for Report
+{
+	async fn action<CAdapter, EAdapter, JAdapter, LAdapter, OAdapter, TAdapter, XAdapter, Db>(
+		self,
+		connection: Pool<Db>,
+		config: Config,
+		_prompter: &dyn Prompter,
+	) -> DynResult<()>
+	where
+		CAdapter: Deletable<Db = Db> + ContactAdapter,
+		EAdapter: Deletable<Db = Db> + EmployeeAdapter,
+		JAdapter: Deletable<Db = Db> + JobAdapter,
+		LAdapter: Deletable<Db = Db> + LocationAdapter,
+		OAdapter: Deletable<Db = Db> + OrganizationAdapter,
+		TAdapter: Deletable<Db = Db> + TimesheetAdapter,
+		XAdapter: Deletable<Db = Db> + ExpensesAdapter,
+		Db: Database,
+		for<'connection> &'connection mut Db::Connection: Executor<'connection, Database = Db>,
+		for<'connection> &'connection mut Transaction<'connection, Db>: Executor<'connection, Database = Db>,
+	{
+		let currency = self.currency.unwrap_or(config.invoices.default_currency);
+		let match_condition: Option<MatchTimesheet> = self.match_args.try_into()?;
+		let retrieved = TAdapter::retrieve(&connection, match_condition.unwrap_or_default()).await?;
+		let timesheets =
+			exchange::timesheets(retrieved, currency, self.rates.as_deref(), &Extensions::read()?.invoices.rates)
+				.await?;
+
+		let rows = Self::summarize(&timesheets, &self.by, self.period, currency);
+		print!("{}", self.format.render(&rows)?);
+
+		Ok(())
+	}
+}
@@ -0,0 +1,25 @@
+mod as_ref;
+mod run_action;
+
+use std::path::PathBuf;
+
+use clap::Args as Clap;
+
+use super::store_args::StoreArgs;
+
+/// Create every entity in an archive written by `winvoice dump`, in one transaction.
+///
+/// The store must already be initialized (see `winvoice init`). The entities are created anew, so
+/// their `id`s may differ from those in the archive, and anything which is already in the store is
+/// kept. Nothing is restored if any invoice number in the archive is already assigned in the store.
+#[derive(Clap, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Restore
+{
+	/// The archive to restore, which is JSON if it has a `.json` extension, and YAML otherwise.
+	#[clap(value_name = "FILE", value_parser)]
+	file: PathBuf,
+
+	/// Specifies the [`Store`](crate::config::Store) to [`Restore`] into.
+	#[clap(flatten)]
+	store_args: StoreArgs,
+}
@@ -0,0 +1,10 @@
+use super::Restore;
+use crate::args::store_args::StoreArgs;
+
+impl AsRef<StoreArgs> for Restore
+{
+	fn as_ref(&self) -> &StoreArgs
+	{
+		&self.store_args
+	}
+}
@@ -0,0 +1,72 @@
+use sqlx::{Database, Executor, Pool, Transaction};
+use winvoice_adapter::{
+	schema::{
+		ContactAdapter,
+		EmployeeAdapter,
+		ExpensesAdapter,
+		JobAdapter,
+		LocationAdapter,
+		OrganizationAdapter,
+		TimesheetAdapter,
+	},
+	Deletable,
+};
+
+use super::Restore;
+use crate::{
+	archive::Archive,
+	args::RunAction,
+	config::Config,
+	discounts::Discounts,
+	input::Prompter,
+	numbering::Register,
+	payments::Ledger,
+	DynResult,
+};
+
+#[async_trait::async_trait(?Send)]
+impl RunAction for Restore
+{
+	async fn action<CAdapter, EAdapter, JAdapter, LAdapter, OAdapter, TAdapter, XAdapter, Db>(
+		self,
+		connection: Pool<Db>,
+		_config: Config,
+		_prompter: &dyn Prompter,
+	) -> DynResult<()>
+	where
+		CAdapter: Deletable<Db = Db> + ContactAdapter,
+		EAdapter: Deletable<Db = Db> + EmployeeAdapter,
+		JAdapter: Deletable<Db = Db> + JobAdapter,
+		LAdapter: Deletable<Db = Db> + LocationAdapter,
+		OAdapter: Deletable<Db = Db> + OrganizationAdapter,
+		TAdapter: Deletable<Db = Db> + TimesheetAdapter,
+		XAdapter: Deletable<Db = Db> + ExpensesAdapter,
+		Db: Database + Discounts + Ledger + Register,
+		for<'connection> &'connection mut Db::Connection: Executor<'connection, Database = Db>,
+		for<'connection> &'connection mut Transaction<'connection, Db>: Executor<'connection, Database = Db>,
+	{
+		let archive = Archive::read(&self.file).await?;
+
+		// NOTE: the `Location`s, `Organization`s, and `Employee`s which exist are not reused, so the
+		//       `Job`s in the archive are never skipped either; only the invoice numbers are needed to
+		//       report which ones collide before anything is created.
+		let existing = Archive::existing::<EAdapter, JAdapter, LAdapter, OAdapter, _>(&connection).await?;
+
+		// {{{
+		let mut transaction = connection.begin().await?;
+
+		let (restored, _) = archive
+			.restore::<CAdapter, EAdapter, JAdapter, LAdapter, OAdapter, TAdapter, _>(&mut transaction, &Archive {
+				invoice_numbers: existing.invoice_numbers,
+				..Default::default()
+			})
+			.await?;
+
+		transaction.commit().await?;
+		// }}}
+
+		restored.counts().into_iter().for_each(|(kind, count)| println!("Restored {count} {kind}(s)"));
+
+		Ok(())
+	}
+}
@@ -1,11 +1,13 @@
 mod as_ref;
 mod command;
+mod invoiced;
 mod run_action;
 
 use clap::Args as Clap;
 use command::RetrieveCommand;
 
 use super::{match_args::MatchArgs, store_args::StoreArgs};
+use crate::fmt::OutputFormat;
 
 /// Retrieve information being stored by Winvoice.
 #[derive(Clap, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
@@ -15,11 +17,20 @@ pub struct Retrieve
 	#[clap(subcommand)]
 	command: RetrieveCommand,
 
+	/// Print what was retrieved in the specified format, rather than as human-readable text.
+	#[clap(long, short, value_enum, value_name = "FORMAT")]
+	format: Option<OutputFormat>,
+
 	/// Specifies a file which can be used in place of the prompt of a user query.
 	#[clap(flatten)]
 	match_args: MatchArgs,
 
-	/// Specifies the [`Store`](winvoice_config::Store) to [`Retrieve`] from.
+	/// Save the match condition used to retrieve information under the given name, so that it can
+	/// be used again with --query.
+	#[clap(global = true, long, value_name = "NAME")]
+	save_query: Option<String>,
+
+	/// Specifies the [`Store`](crate::config::Store) to [`Retrieve`] from.
 	#[clap(flatten)]
 	store_args: StoreArgs,
 }
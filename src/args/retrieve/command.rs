@@ -2,7 +2,8 @@ use std::path::PathBuf;
 
 use clap::Subcommand as Clap;
 use money2::Currency;
-use winvoice_export::Format;
+
+use crate::export::{Filename, Format, TimesheetFormat};
 
 /// The specific type of information that is being retrieved.
 #[derive(Clap, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
@@ -33,19 +34,67 @@ pub enum RetrieveCommand
 	/// Retrieve `Job`s from the store (-s) specified.
 	Job
 	{
+		/// When exporting, combine the selected `Job`s of each client into one invoice, rather
+		/// than exporting each `Job` to its own file.
+		#[clap(action, long, requires("export"))]
+		combine: bool,
+
 		/// Provide the currency to use when exporting
 		#[clap(default_value_t, long, short, requires("export"))]
 		currency: Currency,
 
 		/// Select a number of closed `Job`s and export them to a file of the specified format.
 		///
-		/// Supported formats are: markdown.
-		#[clap(long, short, value_name = "FORMAT")]
+		/// Supported formats are: html, markdown, pdf, template, ubl.
+		#[clap(long, short, value_enum, value_name = "FORMAT")]
 		export: Option<Format>,
 
+		/// The name of the files to --export, without their extension (e.g. "{date}--{client}").
+		///
+		/// The placeholders are {client}, {date} (that the invoice was issued), {id} (of the
+		/// `Job`), and {number} (of the invoice, or the {id} if it has none). Defaults to the
+		/// `filename` field of the `[invoices]` section of the Winvoice configuration file, or
+		/// "{client}--{number}".
+		#[clap(long, requires("export"), value_name = "TEMPLATE")]
+		filename: Option<Filename>,
+
+		/// Overwrite files which already exist when exporting.
+		#[clap(action, long, requires("export"))]
+		force: bool,
+
+		/// The file to --export to, instead of one named by the --filename template. When "-",
+		/// what is exported is written to stdout.
+		///
+		/// Only one invoice can be exported to a file, so the selected `Job`s must be of one client
+		/// and --combine'd if there is more than one.
+		#[clap(conflicts_with("output-dir"), long, requires("export"), value_name = "FILE", value_parser)]
+		output: Option<PathBuf>,
+
+		/// A file of exchange rates to use instead of fetching them, in the CSV format published by
+		/// the European Central Bank (i.e. the `eurofxref.csv` file).
+		///
+		/// When not specified, the `rates` field of the `[invoices]` section of the Winvoice
+		/// configuration file is used. If that is empty, rates are fetched, and cached for the
+		/// next time that they cannot be fetched.
+		#[clap(long, short, requires("export"), value_name = "FILE", value_parser)]
+		rates: Option<PathBuf>,
+
 		/// Which directory to --export files into.
 		#[clap(long, short, requires("export"), value_name = "DIR", value_parser)]
 		output_dir: Option<PathBuf>,
+
+		/// The Handlebars template to use when `--export template` is specified.
+		///
+		/// Defaults to the `template` field of the `[invoices]` section of the Winvoice
+		/// configuration file. Files are exported with the extension of the template, ignoring
+		/// `.hbs` (e.g. "invoice.tex.hbs" exports ".tex" files).
+		#[clap(long, short, requires("export"), value_name = "FILE", value_parser)]
+		template: Option<PathBuf>,
+
+		/// When exchange rates cannot be obtained, export `Job`s in the currency that they were
+		/// recorded in instead of failing.
+		#[clap(action, long, requires("export"))]
+		unconverted: bool,
 	},
 
 	/// Retrieve `Location`s from the store (-s) specified.
@@ -67,6 +116,30 @@ pub enum RetrieveCommand
 		set_employer: bool,
 	},
 
+	/// Retrieve the payments made towards `Job`s from the store (-s) specified.
+	///
+	/// The --match argument specifies the `Job`s to retrieve payments for.
+	Payment,
+
 	/// Retrieve `Timesheet`s from the store (-s) specified.
-	Timesheet,
+	Timesheet
+	{
+		/// Export the `Timesheet`s to a file of the specified format, rather than printing them.
+		///
+		/// Supported formats are: csv.
+		#[clap(long, short, value_enum, value_name = "FORMAT")]
+		export: Option<TimesheetFormat>,
+
+		/// When exporting, add a row after each `Timesheet` for each of its `Expense`s.
+		#[clap(action, long, requires("export"))]
+		expenses: bool,
+
+		/// Overwrite the file if it already exists when exporting.
+		#[clap(action, long, requires("export"))]
+		force: bool,
+
+		/// Which directory to --export files into.
+		#[clap(long, short, requires("export"), value_name = "DIR", value_parser)]
+		output_dir: Option<PathBuf>,
+	},
 }
@@ -0,0 +1,31 @@
+use core::fmt::{Display, Formatter, Result};
+
+use serde::Serialize;
+use winvoice_schema::{
+	chrono::{DateTime, Local, Utc},
+	Job,
+};
+
+/// A `Job` which is displayed along with the date that its invoice is due, if there is one.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub(super) struct Invoiced<'job>
+{
+	/// See [`Invoices::due`](crate::config::Invoices::due).
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub due: Option<DateTime<Utc>>,
+
+	/// The fields of the `Job` itself.
+	#[serde(flatten)]
+	pub job: &'job Job,
+}
+
+impl Display for Invoiced<'_>
+{
+	fn fmt(&self, f: &mut Formatter<'_>) -> Result
+	{
+		write!(f, "{}", self.job)?;
+		self.due
+			.filter(|_| self.job.invoice.date.is_some_and(|d| d.paid.is_none()))
+			.map_or(Ok(()), |d| write!(f, "\n\tDue by {}", d.with_timezone(&Local).format("%Y-%m-%d %H:%M")))
+	}
+}
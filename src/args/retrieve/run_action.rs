@@ -1,11 +1,14 @@
 use core::fmt::Display;
-use std::error::Error as StdError;
+use std::{
+	collections::BTreeMap,
+	error::Error as StdError,
+	io::{self, Write},
+};
 
 use futures::{future, stream, TryFutureExt, TryStreamExt};
-use money2::{Exchange, ExchangeRates};
+use money2::Exchange;
 use serde::{de::DeserializeOwned, Serialize};
 use sqlx::{Database, Executor, Pool};
-use tokio::fs;
 use winvoice_adapter::{
 	schema::{
 		ContactAdapter,
@@ -19,12 +22,23 @@ use winvoice_adapter::{
 	Deletable,
 	Retrievable,
 };
-use winvoice_config::Config;
+use winvoice_config::Error as ConfigError;
 use winvoice_match::{MatchOrganization, MatchTimesheet};
 use winvoice_schema::{chrono::Utc, InvoiceDate};
 
-use super::{Retrieve, RetrieveCommand};
-use crate::{args::RunAction, fmt, input, DynResult};
+use super::{invoiced::Invoiced, Retrieve, RetrieveCommand};
+use crate::{
+	args::RunAction,
+	config::{self, Config, Extensions},
+	discounts::Discounts,
+	exchange,
+	export::{self, Discount, Format, Section, Template, TooMany},
+	fmt::{self, OutputFormat},
+	input::{self, Prompter},
+	numbering::Register,
+	payments::{CurrencyMismatch, Ledger},
+	DynResult,
+};
 
 #[async_trait::async_trait(?Send)]
 impl RunAction for Retrieve
@@ -33,9 +47,10 @@ impl RunAction for Retrieve
 		self,
 		connection: Pool<Db>,
 		config: Config,
+		prompter: &dyn Prompter,
 	) -> DynResult<()>
 	where
-		Db: Database,
+		Db: Database + Discounts + Ledger + Register,
 		CAdapter: Deletable<Db = Db> + ContactAdapter,
 		EAdapter: Deletable<Db = Db> + EmployeeAdapter,
 		JAdapter: Deletable<Db = Db> + JobAdapter,
@@ -45,47 +60,66 @@ impl RunAction for Retrieve
 		XAdapter: Deletable<Db = Db> + ExpensesAdapter,
 		for<'connection> &'connection mut Db::Connection: Executor<'connection, Database = Db>,
 	{
-		/// [`Display`] every element of some `array` using [`println!`].
-		fn print_all<T>(array: &[T])
+		/// Print every element of some `array` using [`println!`], either in the given `format` or
+		/// using its [`Display`] implementation.
+		fn print_all<T>(array: &[T], format: Option<OutputFormat>) -> DynResult<()>
 		where
-			T: Display,
+			T: Display + Serialize,
 		{
-			array.iter().for_each(|element| {
-				println!("{element}");
-			});
+			match format
+			{
+				Some(f) => print!("{}", f.render(array)?),
+				None => array.iter().for_each(|element| {
+					println!("{element}");
+				}),
+			};
+
+			Ok(())
 		}
 
 		/// A generic deletion function which works for any of the provided adapters in the outer
 		/// function, as they all implement `Retr` at the minimum.
 		async fn retrieve<Retr, Db, Match>(
+			prompter: &dyn Prompter,
 			connection: &Pool<Db>,
 			match_condition: Match,
 			print: bool,
+			format: Option<OutputFormat>,
+			save_query: Option<&str>,
 		) -> DynResult<Vec<Retr::Entity>>
 		where
 			Db: Database,
 			Match: TryInto<Option<Retr::Match>>,
 			Match::Error: 'static + StdError,
 			Retr: Retrievable<Db = Db>,
-			Retr::Entity: Clone + Display + Sync,
-			Retr::Match: Default + DeserializeOwned + Serialize,
+			Retr::Entity: Clone + Display + Serialize + Sync,
+			Retr::Match: Clone + Default + DeserializeOwned + Serialize,
 			for<'connection> &'connection mut Db::Connection: Executor<'connection, Database = Db>,
 		{
-			let retrieved = match match_condition.try_into()?
+			let (condition, retrieved) = match match_condition.try_into()?
 			{
-				Some(condition) => Retr::retrieve(connection, condition).await?,
+				Some(condition) => Retr::retrieve(connection, condition.clone()).await.map(|r| (condition, r))?,
 
 				#[rustfmt::skip]
-				None => input::retrieve::<Retr, _, _>(
+				None => input::retrieve_with_condition::<Retr, _, _>(
+					prompter,
 					connection,
 					format!("Query the {} to delete", fmt::type_name::<Retr::Entity>()),
 				)
 				.await?,
 			};
 
+			if let Some(name) = save_query
+			{
+				let mut extensions = Extensions::read()?;
+				extensions.queries.insert(name, &condition)?;
+				extensions.write()?;
+				println!("Saved the query as {}", fmt::quoted(name));
+			}
+
 			if print
 			{
-				print_all(&retrieved);
+				print_all(&retrieved, format)?;
 			}
 
 			Ok(retrieved)
@@ -95,7 +129,15 @@ impl RunAction for Retrieve
 		{
 			RetrieveCommand::Contact =>
 			{
-				retrieve::<CAdapter, _, _>(&connection, self.match_args, true).await?;
+				retrieve::<CAdapter, _, _>(
+					prompter,
+					&connection,
+					self.match_args,
+					true,
+					self.format,
+					self.save_query.as_deref(),
+				)
+				.await?;
 			},
 
 			RetrieveCommand::Employee { default, set_default } =>
@@ -106,32 +148,82 @@ impl RunAction for Retrieve
 					true => config.employees.id_or_err().map(|id| Some(id.into()))?,
 				};
 
-				let retrieved = retrieve::<EAdapter, _, _>(&connection, match_condition, !set_default).await?;
+				let retrieved = retrieve::<EAdapter, _, _>(
+					prompter,
+					&connection,
+					match_condition,
+					!set_default,
+					self.format,
+					self.save_query.as_deref(),
+				)
+				.await?;
 
 				if set_default
 				{
-					let selected = input::select_one(retrieved, "Select the Employee to set as the default")?;
+					let selected = input::select_one(prompter, retrieved, "Select the Employee to set as the default")?;
 					let mut c = config;
 					c.employees.id = Some(selected.id);
-					c.write()?;
+					config::write(&c)?;
 				}
 			},
 
 			RetrieveCommand::Expense =>
 			{
-				retrieve::<XAdapter, _, _>(&connection, self.match_args, true).await?;
+				retrieve::<XAdapter, _, _>(
+					prompter,
+					&connection,
+					self.match_args,
+					true,
+					self.format,
+					self.save_query.as_deref(),
+				)
+				.await?;
 			},
 
-			RetrieveCommand::Job { currency, export, output_dir } =>
+			RetrieveCommand::Job {
+				combine,
+				currency,
+				export,
+				filename,
+				force,
+				output,
+				output_dir,
+				rates,
+				template,
+				unconverted,
+			} =>
 			{
-				let retrieved = retrieve::<JAdapter, _, _>(&connection, self.match_args, export.is_none()).await?;
+				let retrieved = retrieve::<JAdapter, _, _>(
+					prompter,
+					&connection,
+					self.match_args,
+					false,
+					self.format,
+					self.save_query.as_deref(),
+				)
+				.await?;
+
+				let extensions = Extensions::read()?;
+				let job_ids: Vec<_> = retrieved.iter().map(|j| j.id).collect();
+				let due_dates = Db::retrieve_due_dates(&mut *connection.acquire().await?, &job_ids).await?;
+				if export.is_none()
+				{
+					let invoiced: Vec<_> = retrieved
+						.iter()
+						.map(|job| Invoiced {
+							due: extensions.invoices.due_by(self.store_args.name(), job, &due_dates),
+							job,
+						})
+						.collect();
+					print_all(&invoiced, self.format)?;
+				}
 
 				if let Some(format) = export
 				{
 					let match_all_contacts = Default::default();
 					let match_employer = config.organizations.employer_id_or_err().map(MatchOrganization::from)?;
 
-					let exchange_rates_fut = ExchangeRates::new().map_ok(Some);
+					let exchange_rates_fut = exchange::rates(rates.as_deref(), &extensions.invoices.rates);
 					let (contact_information, employer) = futures::try_join!(
 						CAdapter::retrieve(&connection, match_all_contacts).map_ok(|mut vec| {
 							vec.sort_by(|lhs, rhs| lhs.label.cmp(&rhs.label));
@@ -141,50 +233,153 @@ impl RunAction for Retrieve
 							.and_then(|mut vec| { future::ready(vec.pop().ok_or(sqlx::Error::RowNotFound)) }),
 					)?;
 
-					let exchange_rates = exchange_rates_fut.await?;
-					let mut selected = input::select(retrieved, "Select the Jobs to export")?;
+					let template = match format
+					{
+						Format::Template =>
+						{
+							let filepath = match template
+							{
+								Some(t) => t,
+								None => Extensions::read()?
+									.invoices
+									.template
+									.ok_or_else(|| ConfigError::NotConfigured("template".into(), "invoices".into()))?,
+							};
+
+							Some(Template::read(&filepath).await?)
+						},
+						_ => None,
+					};
+
+					let exchange_rates = match exchange_rates_fut.await
+					{
+						Ok(r) => Some(r),
+						Err(_) if unconverted => None,
+						Err(e) => return Err(e.into()),
+					};
+
+					let mut selected = input::select(prompter, retrieved, "Select the Jobs to export")?;
 
-					selected.iter_mut().filter(|j| j.invoice.date.and_then(|d| d.paid).is_none()).for_each(|j| {
+					// NOTE: invoices which have not been issued are shown as if they were issued now, but
+					//       invoices which have keep the date they were issued (and are due by).
+					selected.iter_mut().filter(|j| j.invoice.date.is_none()).for_each(|j| {
 						j.invoice.date = Some(InvoiceDate { issued: Utc::now(), paid: None });
 					});
 
+					let job_ids: Vec<_> = selected.iter().map(|j| j.id).collect();
+					let mut acquired = connection.acquire().await?;
+					let discounts = Db::retrieve_discounts(&mut acquired, &job_ids).await?;
+					let numbers = Db::retrieve_numbers(&mut acquired, &job_ids).await?;
+					drop(acquired);
+
+					let filename = filename.or_else(|| extensions.invoices.filename.clone()).unwrap_or_default();
+					let grouped: Vec<Vec<_>> = match combine
+					{
+						false => selected.into_iter().map(|j| vec![j]).collect(),
+						true => selected
+							.into_iter()
+							.fold(BTreeMap::<_, Vec<_>>::new(), |mut grouped, j| {
+								grouped.entry(j.client.id).or_default().push(j);
+								grouped
+							})
+							.into_values()
+							.collect(),
+					};
+
+					if output.is_some() && grouped.len() > 1
+					{
+						return Err(TooMany(grouped.len()).into());
+					}
+
 					#[rustfmt::skip]
-					stream::iter(selected.into_iter().map(Ok)).try_for_each_concurrent(None, |j| {
+					stream::iter(grouped.into_iter().map(Ok)).try_for_each_concurrent(None, |jobs| {
 						let connection = &connection;
 						let contact_information = &contact_information;
 						let employer = &employer;
 						let exchange_rates = exchange_rates.as_ref();
-						let match_condition = MatchTimesheet { job: j.id.into(), ..Default::default() };
+						let invoices = &extensions.invoices;
+						let filename = &filename;
+						let output = output.as_deref();
 						let output_dir = output_dir.as_ref();
+						let discounts = &discounts;
+						let due_dates = &due_dates;
+						let numbers = &numbers;
+						let store = self.store_args.name();
+						let template = template.as_ref();
 
 						async move {
-							let timesheets_fut = TAdapter::retrieve(connection, match_condition)
-								.map_ok(|mut v| {
+							let timesheets_fut = future::try_join_all(jobs.iter().map(|j| {
+								let match_condition = MatchTimesheet { job: j.id.into(), ..Default::default() };
+								TAdapter::retrieve(connection, match_condition).map_ok(|mut v| {
 									v.sort_by(|lhs, rhs| lhs.time_begin.cmp(&rhs.time_begin));
 									v
-								});
+								})
+							}));
 
-							let filename =
-								format!("{}--{}.{}", j.client.name.replace(' ', "-"), j.id, format.extension());
+							let (client, expected) = jobs
+								.first()
+								.map(|j| (j.client.clone(), j.invoice.hourly_rate.currency))
+								.ok_or(sqlx::Error::RowNotFound)?;
 
 							let timesheets = timesheets_fut.await?;
 
-							let exported = format.export_job(
-								&match exchange_rates
+							let terms: Vec<_> = jobs.iter().map(|j| {
+								let discount = discounts.get(&j.id).copied();
+								let number = numbers.get(&j.id).map(String::as_str);
+								invoices.terms(store, j, discount, number, invoices.due_by(store, j, due_dates))
+							}).collect();
+							let (jobs, terms, timesheets): (Vec<_>, Vec<_>, Vec<Vec<_>>) = match exchange_rates
+							{
+								Some(r) => (
+									jobs.into_iter().map(|j| j.exchange(currency, r)).collect(),
+									terms.into_iter().map(|t| t.exchange(currency, r)).collect(),
+									timesheets
+										.into_iter()
+										.map(|v| v.into_iter().map(|t| t.exchange(currency, r)).collect())
+										.collect(),
+								),
+								None =>
 								{
-									Some(r) => j.exchange(currency, r),
-									None => j,
+									if let Some(j) = jobs.iter().find(|j| j.invoice.hourly_rate.currency != expected)
+									{
+										let found = j.invoice.hourly_rate.currency;
+										return Err(CurrencyMismatch { expected, found }.into());
+									}
+
+									if let Some(found) = terms.iter().find_map(|t| match t.discount
+									{
+										Some(Discount::Fixed(m)) if m.currency != expected => Some(m.currency),
+										_ => None,
+									})
+									{
+										return Err(CurrencyMismatch { expected, found }.into());
+									}
+
+									(jobs, terms, timesheets)
 								},
-								contact_information,
-								employer,
-								&timesheets,
-							);
+							};
+
+							let sections: Vec<_> = jobs
+								.iter()
+								.zip(&terms)
+								.zip(&timesheets)
+								.map(|((job, terms), timesheets)| Section { job, terms, timesheets })
+								.collect();
 
-							match output_dir
+							let exported =
+								format.export(template, &client, &sections, contact_information, employer)?;
+
+							match output
 							{
-								Some(d) => fs::write(d.join(filename), exported).await,
-								None => fs::write(filename, exported).await,
-							}?;
+								Some(o) if o.as_os_str() == "-" => io::stdout().lock().write_all(&exported)?,
+								Some(o) => export::write(o, &exported, force).await?,
+								None =>
+								{
+									let filename = filename.render(&sections, format.extension(template));
+									let path = output_dir.map_or_else(|| filename.clone().into(), |d| d.join(&filename));
+									export::write(&path, &exported, force).await?;
+								},
+							};
 
 							DynResult::Ok(())
 						}
@@ -195,7 +390,15 @@ impl RunAction for Retrieve
 
 			RetrieveCommand::Location =>
 			{
-				retrieve::<LAdapter, _, _>(&connection, self.match_args, true).await?;
+				retrieve::<LAdapter, _, _>(
+					prompter,
+					&connection,
+					self.match_args,
+					true,
+					self.format,
+					self.save_query.as_deref(),
+				)
+				.await?;
 			},
 
 			RetrieveCommand::Organization { employer, set_employer } =>
@@ -206,20 +409,62 @@ impl RunAction for Retrieve
 					true => config.organizations.employer_id_or_err().map(|id| Some(id.into()))?,
 				};
 
-				let retrieved = retrieve::<OAdapter, _, _>(&connection, match_condition, !set_employer).await?;
+				let retrieved = retrieve::<OAdapter, _, _>(
+					prompter,
+					&connection,
+					match_condition,
+					!set_employer,
+					self.format,
+					self.save_query.as_deref(),
+				)
+				.await?;
 
 				if set_employer
 				{
-					let selected = input::select_one(retrieved, "Select the Employer to use in your configuration")?;
+					let selected =
+						input::select_one(prompter, retrieved, "Select the Employer to use in your configuration")?;
 					let mut c = config;
 					c.organizations.employer_id = Some(selected.id);
-					c.write()?;
+					config::write(&c)?;
 				}
 			},
 
-			RetrieveCommand::Timesheet =>
+			RetrieveCommand::Payment =>
 			{
-				retrieve::<TAdapter, _, _>(&connection, self.match_args, true).await?;
+				let retrieved = retrieve::<JAdapter, _, _>(
+					prompter,
+					&connection,
+					self.match_args,
+					false,
+					self.format,
+					self.save_query.as_deref(),
+				)
+				.await?;
+
+				let job_ids: Vec<_> = retrieved.iter().map(|j| j.id).collect();
+				let payments = Db::retrieve_payments(&mut *connection.acquire().await?, &job_ids).await?;
+				print_all(&payments, self.format)?;
+			},
+
+			RetrieveCommand::Timesheet { export, expenses, force, output_dir } =>
+			{
+				let mut retrieved = retrieve::<TAdapter, _, _>(
+					prompter,
+					&connection,
+					self.match_args,
+					export.is_none(),
+					self.format,
+					self.save_query.as_deref(),
+				)
+				.await?;
+
+				if let Some(format) = export
+				{
+					retrieved.sort_by(|lhs, rhs| lhs.time_begin.cmp(&rhs.time_begin));
+					let exported = format.export(&retrieved, expenses)?;
+					let path = output_dir.unwrap_or_default().join(format!("timesheets.{}", format.extension()));
+					export::write(&path, &exported, force).await?;
+				}
 			},
 		};
 
@@ -11,22 +11,31 @@ use winvoice_adapter::{
 	},
 	Deletable,
 };
-use winvoice_config::{Adapters, Config, Error};
 
 use super::store_args::StoreArgs;
-use crate::DynResult;
+use crate::{
+	config::{Adapter, Config, FeatureNotFound},
+	discounts::Discounts,
+	input::Prompter,
+	migration,
+	numbering::Register,
+	payments::Ledger,
+	DynResult,
+};
 
 #[async_trait::async_trait(?Send)]
 pub trait RunAction: AsRef<StoreArgs> + Sized
 {
-	/// Perform this command's action using a specific set of database-struct adapters.
+	/// Perform this command's action using a specific set of database-struct adapters, asking any
+	/// questions using the `prompter`.
 	async fn action<CAdapter, EAdapter, JAdapter, LAdapter, OAdapter, TAdapter, XAdapter, Db>(
 		self,
 		connection: Pool<Db>,
 		config: Config,
+		prompter: &dyn Prompter,
 	) -> DynResult<()>
 	where
-		Db: Database,
+		Db: Database + Discounts + Ledger + Register,
 		CAdapter: Deletable<Db = Db> + ContactAdapter,
 		EAdapter: Deletable<Db = Db> + EmployeeAdapter,
 		JAdapter: Deletable<Db = Db> + JobAdapter,
@@ -37,15 +46,15 @@ pub trait RunAction: AsRef<StoreArgs> + Sized
 		for<'connection> &'connection mut Db::Connection: Executor<'connection, Database = Db>,
 		for<'connection> &'connection mut Transaction<'connection, Db>: Executor<'connection, Database = Db>;
 
-	/// Execute this command given the user's [`Config`].
-	async fn run(self, config: Config) -> DynResult<()>
+	/// Execute this command given the user's [`Config`], asking any questions using the `prompter`.
+	async fn run(self, config: Config, prompter: &dyn Prompter) -> DynResult<()>
 	{
 		let store = self.as_ref().try_get_from(&config)?;
 
 		match store.adapter
 		{
 			#[cfg(feature = "postgres")]
-			Adapters::Postgres =>
+			Adapter::Postgres =>
 			{
 				use winvoice_adapter_postgres::schema::{
 					PgContact,
@@ -58,16 +67,47 @@ pub trait RunAction: AsRef<StoreArgs> + Sized
 				};
 
 				let pool = Pool::connect_lazy(&store.url)?;
+				migration::check(&pool, store.adapter).await?;
+
 				self.action::<PgContact, PgEmployee, PgJob, PgLocation, PgOrganization, PgTimesheet, PgExpenses, _>(
-					pool, config,
+					pool, config, prompter,
 				)
 				.await?
 			},
 
+			#[cfg(feature = "sqlite")]
+			Adapter::Sqlite =>
+			{
+				use crate::sqlite::schema::{
+					SqliteContact,
+					SqliteEmployee,
+					SqliteExpenses,
+					SqliteJob,
+					SqliteLocation,
+					SqliteOrganization,
+					SqliteTimesheet,
+				};
+
+				let pool = Pool::connect_lazy_with(crate::sqlite::connect_options(&store.url)?);
+				migration::check(&pool, store.adapter).await?;
+
+				self.action::<
+					SqliteContact,
+					SqliteEmployee,
+					SqliteJob,
+					SqliteLocation,
+					SqliteOrganization,
+					SqliteTimesheet,
+					SqliteExpenses,
+					_,
+				>(pool, config, prompter)
+				.await?
+			},
+
 			// NOTE: this is allowed because there may be additional adapters added later, and I
 			// want       to define this behavior now.
 			#[allow(unreachable_patterns)]
-			_ => return Err(Error::FeatureNotFound(store.adapter).into()),
+			_ => return Err(FeatureNotFound(store.adapter).into()),
 		};
 
 		Ok(())
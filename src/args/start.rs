@@ -0,0 +1,34 @@
+mod as_ref;
+mod run_action;
+
+use clap::Args as Clap;
+use winvoice_schema::chrono::NaiveDateTime;
+
+use super::{match_args::MatchArgs, store_args::StoreArgs};
+
+/// Start working on a `Job`, creating a `Timesheet` for the `Employee` specified by the `id` field
+/// of the `[employees]` section of the Winvoice configuration file.
+///
+/// Any `Timesheet`s of that `Employee` which have not been stopped are stopped when the new one
+/// begins. If no `Job` is specified, the one most recently started in the store (-s) is used.
+#[derive(Clap, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Start
+{
+	/// Specifies the `Job` to start working on.
+	#[clap(flatten)]
+	match_args: MatchArgs,
+
+	/// Specifies the [`Store`](crate::config::Store) to create the `Timesheet` in.
+	#[clap(flatten)]
+	store_args: StoreArgs,
+
+	/// The `time_begin` of the `Timesheet` to create. Defaults to the current date and time.
+	///
+	/// e.g. December 12th, 2022 at 1:30:00pm is "2022-12-31T13:30:00"
+	#[clap(long)]
+	time_begin: Option<NaiveDateTime>,
+
+	/// The `work_notes` of the `Timesheet` to create.
+	#[clap(long, short = 'n')]
+	work_notes: Option<String>,
+}
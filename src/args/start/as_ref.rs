@@ -0,0 +1,10 @@
+use super::Start;
+use crate::args::store_args::StoreArgs;
+
+impl AsRef<StoreArgs> for Start
+{
+	fn as_ref(&self) -> &StoreArgs
+	{
+		&self.store_args
+	}
+}
@@ -0,0 +1,107 @@
+use sqlx::{Database, Executor, Pool, Transaction};
+use winvoice_adapter::{
+	schema::{
+		ContactAdapter,
+		EmployeeAdapter,
+		ExpensesAdapter,
+		JobAdapter,
+		LocationAdapter,
+		OrganizationAdapter,
+		TimesheetAdapter,
+	},
+	Deletable,
+};
+use winvoice_match::MatchJob;
+use winvoice_schema::chrono::Utc;
+
+use super::Start;
+use crate::{
+	args::{stop::Stop, RunAction},
+	config::{Config, Extensions},
+	input::{self, Prompter},
+	utils,
+	DynResult,
+};
+
+#[async_trait::async_trait(?Send)]
+impl RunAction for Start
+{
+	async fn action<CAdapter, EAdapter, JAdapter, LAdapter, OAdapter, TAdapter, XAdapter, Db>(
+		self,
+		connection: Pool<Db>,
+		config: Config,
+		prompter: &dyn Prompter,
+	) -> DynResult<()>
+	where
+		CAdapter: Deletable<Db = Db> + ContactAdapter,
+		EAdapter: Deletable<Db = Db> + EmployeeAdapter,
+		JAdapter: Deletable<Db = Db> + JobAdapter,
+		LAdapter: Deletable<Db = Db> + LocationAdapter,
+		OAdapter: Deletable<Db = Db> + OrganizationAdapter,
+		TAdapter: Deletable<Db = Db> + TimesheetAdapter,
+		XAdapter: Deletable<Db = Db> + ExpensesAdapter,
+		Db: Database,
+		for<'connection> &'connection mut Db::Connection: Executor<'connection, Database = Db>,
+		for<'connection> &'connection mut Transaction<'connection, Db>: Executor<'connection, Database = Db>,
+	{
+		let mut extensions = Extensions::read()?;
+		let store = self.store_args.name().to_owned();
+
+		let employee = input::select_one_retrieved::<EAdapter, _, _>(
+			prompter,
+			&connection,
+			config.employees.id_or_err().map(|id| Some(id.into()))?,
+			"Query the Employee who is responsible for the work",
+		)
+		.await?;
+
+		let match_job = match self.match_args.try_into()?
+		{
+			Some(condition) => Some(condition),
+			None => extensions.timesheets.last_job_id.get(&store).copied().map(MatchJob::from),
+		};
+
+		let job = input::select_one_retrieved::<JAdapter, _, _>(
+			prompter,
+			&connection,
+			match_job,
+			"Query the Job being worked on",
+		)
+		.await?;
+
+		let time_begin = self.time_begin.map_or_else(Utc::now, utils::naive_local_datetime_to_utc);
+		let mut open = TAdapter::retrieve(&connection, Stop::open_timesheets(&config)?).await?;
+		Stop::stop(&mut open, time_begin, None);
+
+		let job_id = job.id;
+
+		// {{{
+		let mut transaction = connection.begin().await?;
+
+		if !open.is_empty()
+		{
+			TAdapter::update(&mut transaction, open.iter().inspect(|t| utils::report_action("stopped", *t))).await?;
+		}
+
+		let created = TAdapter::create(
+			&mut transaction,
+			employee,
+			Vec::new(),
+			job,
+			time_begin,
+			None,
+			self.work_notes.unwrap_or_default(),
+		)
+		.await?;
+
+		transaction.commit().await?;
+		// }}}
+
+		utils::report_action("started", &created);
+
+		extensions.timesheets.last_job_id.insert(store, job_id);
+		extensions.write()?;
+
+		Ok(())
+	}
+}
@@ -0,0 +1,48 @@
+mod as_ref;
+mod run_action;
+
+use clap::Args as Clap;
+use money2::Decimal;
+use winvoice_schema::{chrono::Duration, Money};
+
+use super::store_args::StoreArgs;
+
+/// Show how long the `Employee` specified by the `id` field of the `[employees]` section of the
+/// Winvoice configuration file has been working on each `Timesheet` which has not been stopped,
+/// and how much that work has cost so far.
+#[derive(Clap, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Status
+{
+	/// Specifies the [`Store`](crate::config::Store) containing the `Timesheet`s.
+	#[clap(flatten)]
+	store_args: StoreArgs,
+}
+
+impl Status
+{
+	/// The cost of working for some amount of time which has `elapsed` at an `hourly_rate`.
+	fn running_cost(hourly_rate: Money, elapsed: Duration) -> Money
+	{
+		let hours = Decimal::from(elapsed.num_seconds().max(0)) / Decimal::from(3600);
+		Money { amount: (hourly_rate.amount * hours).round_dp(2), ..hourly_rate }
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use pretty_assertions::assert_eq;
+	use winvoice_schema::{chrono::Duration, Currency, Money};
+
+	use super::Status;
+
+	#[test]
+	fn running_cost()
+	{
+		let hourly_rate = Money::new(20_00, 2, Currency::Usd);
+
+		assert_eq!(Status::running_cost(hourly_rate, Duration::minutes(90)), Money::new(30_00, 2, Currency::Usd));
+		assert_eq!(Status::running_cost(hourly_rate, Duration::seconds(61)), Money::new(34, 2, Currency::Usd));
+		assert_eq!(Status::running_cost(hourly_rate, Duration::minutes(-5)), Money::new(0, 2, Currency::Usd));
+	}
+}
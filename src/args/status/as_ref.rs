@@ -0,0 +1,10 @@
+use super::Status;
+use crate::args::store_args::StoreArgs;
+
+impl AsRef<StoreArgs> for Status
+{
+	fn as_ref(&self) -> &StoreArgs
+	{
+		&self.store_args
+	}
+}
@@ -0,0 +1,70 @@
+use sqlx::{Database, Executor, Pool, Transaction};
+use winvoice_adapter::{
+	schema::{
+		ContactAdapter,
+		EmployeeAdapter,
+		ExpensesAdapter,
+		JobAdapter,
+		LocationAdapter,
+		OrganizationAdapter,
+		TimesheetAdapter,
+	},
+	Deletable,
+};
+use winvoice_schema::chrono::Utc;
+
+use super::Status;
+use crate::{
+	args::{stop::Stop, RunAction},
+	config::Config,
+	fmt,
+	input::Prompter,
+	DynResult,
+};
+
+#[async_trait::async_trait(?Send)]
+impl RunAction for Status
+{
+	async fn action<CAdapter, EAdapter, JAdapter, LAdapter, OAdapter, TAdapter, XAdapter, Db>(
+		self,
+		connection: Pool<Db>,
+		config: Config,
+		_prompter: &dyn Prompter,
+	) -> DynResult<()>
+	where
+		CAdapter: Deletable<Db = Db> + ContactAdapter,
+		EAdapter: Deletable<Db = Db> + EmployeeAdapter,
+		JAdapter: Deletable<Db = Db> + JobAdapter,
+		LAdapter: Deletable<Db = Db> + LocationAdapter,
+		OAdapter: Deletable<Db = Db> + OrganizationAdapter,
+		TAdapter: Deletable<Db = Db> + TimesheetAdapter,
+		XAdapter: Deletable<Db = Db> + ExpensesAdapter,
+		Db: Database,
+		for<'connection> &'connection mut Db::Connection: Executor<'connection, Database = Db>,
+		for<'connection> &'connection mut Transaction<'connection, Db>: Executor<'connection, Database = Db>,
+	{
+		let open = TAdapter::retrieve(&connection, Stop::open_timesheets(&config)?).await?;
+		if open.is_empty()
+		{
+			println!("There are no open Timesheets");
+			return Ok(());
+		}
+
+		let now = Utc::now();
+		open.into_iter().for_each(|t| {
+			let elapsed = now - t.time_begin;
+			println!(
+				"Timesheet {} for Job {} ({}): {} elapsed, {} so far",
+				fmt::id_num(t.id),
+				fmt::id_num(t.job.id),
+				t.job.objectives.lines().next().unwrap_or_default(),
+				humantime::format_duration(core::time::Duration::from_secs(
+					elapsed.num_seconds().max(0).unsigned_abs()
+				)),
+				Self::running_cost(t.job.invoice.hourly_rate, elapsed),
+			);
+		});
+
+		Ok(())
+	}
+}
@@ -0,0 +1,63 @@
+mod as_ref;
+mod run_action;
+
+use clap::Args as Clap;
+use winvoice_config::Result;
+use winvoice_match::{MatchOption, MatchTimesheet};
+use winvoice_schema::{
+	chrono::{DateTime, NaiveDateTime, Utc},
+	Timesheet,
+};
+
+use super::store_args::StoreArgs;
+use crate::config::Config;
+
+/// Stop working on the `Timesheet`s of the `Employee` specified by the `id` field of the
+/// `[employees]` section of the Winvoice configuration file.
+#[derive(Clap, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Stop
+{
+	/// Specifies the [`Store`](crate::config::Store) containing the `Timesheet`s.
+	#[clap(flatten)]
+	store_args: StoreArgs,
+
+	/// The `time_end` of the `Timesheet`s to stop. Defaults to the current date and time.
+	///
+	/// e.g. December 12th, 2022 at 1:30:00pm is "2022-12-31T13:30:00"
+	#[clap(long)]
+	time_end: Option<NaiveDateTime>,
+
+	/// Notes to add to the `work_notes` of the `Timesheet`s to stop.
+	#[clap(long, short = 'n')]
+	work_notes: Option<String>,
+}
+
+impl Stop
+{
+	/// A condition matching the `Timesheet`s of the default `Employee` which have no `time_end`.
+	pub(super) fn open_timesheets(config: &Config) -> Result<MatchTimesheet>
+	{
+		config.employees.id_or_err().map(|id| MatchTimesheet {
+			employee: id.into(),
+			time_end: MatchOption::None,
+			..Default::default()
+		})
+	}
+
+	/// Set the `time_end` of the `timesheets`, adding any `work_notes` on a new line.
+	pub(super) fn stop(timesheets: &mut [Timesheet], time_end: DateTime<Utc>, work_notes: Option<&str>)
+	{
+		timesheets.iter_mut().for_each(|t| {
+			t.time_end = Some(time_end);
+			if let Some(notes) = work_notes
+			{
+				if !t.work_notes.is_empty()
+				{
+					t.work_notes.push('\n');
+				}
+
+				t.work_notes.push_str(notes);
+			}
+		});
+	}
+}
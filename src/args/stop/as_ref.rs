@@ -0,0 +1,10 @@
+use super::Stop;
+use crate::args::store_args::StoreArgs;
+
+impl AsRef<StoreArgs> for Stop
+{
+	fn as_ref(&self) -> &StoreArgs
+	{
+		&self.store_args
+	}
+}
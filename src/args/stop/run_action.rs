@@ -0,0 +1,61 @@
+use sqlx::{Database, Executor, Pool, Transaction};
+use winvoice_adapter::{
+	schema::{
+		ContactAdapter,
+		EmployeeAdapter,
+		ExpensesAdapter,
+		JobAdapter,
+		LocationAdapter,
+		OrganizationAdapter,
+		TimesheetAdapter,
+	},
+	Deletable,
+};
+use winvoice_schema::chrono::Utc;
+
+use super::Stop;
+use crate::{
+	args::RunAction,
+	config::Config,
+	input::{Error, Prompter},
+	utils,
+	DynResult,
+};
+
+#[async_trait::async_trait(?Send)]
+impl RunAction for Stop
+{
+	async fn action<CAdapter, EAdapter, JAdapter, LAdapter, OAdapter, TAdapter, XAdapter, Db>(
+		self,
+		connection: Pool<Db>,
+		config: Config,
+		_prompter: &dyn Prompter,
+	) -> DynResult<()>
+	where
+		CAdapter: Deletable<Db = Db> + ContactAdapter,
+		EAdapter: Deletable<Db = Db> + EmployeeAdapter,
+		JAdapter: Deletable<Db = Db> + JobAdapter,
+		LAdapter: Deletable<Db = Db> + LocationAdapter,
+		OAdapter: Deletable<Db = Db> + OrganizationAdapter,
+		TAdapter: Deletable<Db = Db> + TimesheetAdapter,
+		XAdapter: Deletable<Db = Db> + ExpensesAdapter,
+		Db: Database,
+		for<'connection> &'connection mut Db::Connection: Executor<'connection, Database = Db>,
+		for<'connection> &'connection mut Transaction<'connection, Db>: Executor<'connection, Database = Db>,
+	{
+		let mut open = TAdapter::retrieve(&connection, Self::open_timesheets(&config)?).await?;
+		if open.is_empty()
+		{
+			return Err(Error::NoData("open `Timesheet`".into()).into());
+		}
+
+		let time_end = self.time_end.map_or_else(Utc::now, utils::naive_local_datetime_to_utc);
+		Self::stop(&mut open, time_end, self.work_notes.as_deref());
+
+		let mut transaction = connection.begin().await?;
+		TAdapter::update(&mut transaction, open.iter().inspect(|t| utils::report_action("stopped", *t))).await?;
+
+		transaction.commit().await?;
+		Ok(())
+	}
+}
@@ -1,8 +1,11 @@
 #[cfg(test)]
 mod from;
+mod from_str;
 
 use clap::Args as Clap;
-use winvoice_config::{Config, Error, Result, Store};
+use winvoice_config::{Error, Result};
+
+use crate::config::{Config, Store};
 
 /// Reusable arguments used for specifying a store.
 #[derive(Clap, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
@@ -20,10 +23,16 @@ pub struct StoreArgs
 
 impl StoreArgs
 {
+	/// The name of the store, which is a key in the `[stores]` section of the configuration file.
+	pub fn name(&self) -> &str
+	{
+		&self.store
+	}
+
 	/// Try to get the store named `store_name` from `config` and return it, erroring if it does not
 	/// exist.
-	pub fn try_get_from<'connection>(&self, config: &'connection Config) -> Result<&'connection Store>
+	pub fn try_get_from(&self, config: &Config) -> Result<Store>
 	{
-		config.get_store(&self.store).ok_or_else(|| Error::NotConfigured(self.store.clone(), "stores".into()))
+		config.get_store(&self.store).unwrap_or_else(|| Err(Error::NotConfigured(self.store.clone(), "stores".into())))
 	}
 }
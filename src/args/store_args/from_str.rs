@@ -0,0 +1,13 @@
+use core::{convert::Infallible, str::FromStr};
+
+use super::StoreArgs;
+
+impl FromStr for StoreArgs
+{
+	type Err = Infallible;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err>
+	{
+		Ok(Self { store: s.to_owned() })
+	}
+}
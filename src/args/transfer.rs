@@ -0,0 +1,45 @@
+mod as_ref;
+mod destination;
+mod run_action;
+
+use clap::Args as Clap;
+
+use super::{match_args::MatchArgs, store_args::StoreArgs};
+
+/// Copy `Job`s from one store to another, along with their `Timesheet`s and `Expense`s, and
+/// everything which those refer to (i.e. the client `Organization` and its `Location`s, and the
+/// `Employee` of each `Timesheet`).
+///
+/// Everything is created anew in the destination store, in one transaction, except for the
+/// `Location`s, `Organization`s, and `Employee`s which already exist there (i.e. those with the
+/// same name and details), which are reused. The payments and invoice numbers of each `Job` are
+/// copied as well.
+///
+/// `Job`s which are already in the destination store (e.g. because they were copied before) are
+/// skipped. Nothing is copied if any of the other `Job`s has an invoice number which is already
+/// assigned there.
+#[derive(Clap, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Transfer
+{
+	/// A key from the `[stores]` section of the configuration file, which the `Job`s are copied
+	/// from.
+	#[clap(long, value_name = "STORE")]
+	from: StoreArgs,
+
+	/// Specifies the `Job`s to transfer.
+	#[clap(flatten)]
+	match_args: MatchArgs,
+
+	/// Delete the `Job`s (and their `Timesheet`s, `Expense`s, payments, and invoice numbers) from
+	/// the --from store once they have been copied, rather than keeping them in both stores.
+	///
+	/// The client `Organization`, `Location`s, and `Employee`s are kept, since other `Job`s may
+	/// refer to them.
+	#[clap(action, long = "move")]
+	r#move: bool,
+
+	/// A key from the `[stores]` section of the configuration file, which the `Job`s are copied
+	/// to.
+	#[clap(long, value_name = "STORE")]
+	to: StoreArgs,
+}
@@ -0,0 +1,10 @@
+use super::Transfer;
+use crate::args::store_args::StoreArgs;
+
+impl AsRef<StoreArgs> for Transfer
+{
+	fn as_ref(&self) -> &StoreArgs
+	{
+		&self.from
+	}
+}
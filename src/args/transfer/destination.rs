@@ -0,0 +1,87 @@
+use sqlx::{Database, Executor, Pool, Transaction};
+use winvoice_adapter::{
+	schema::{
+		ContactAdapter,
+		EmployeeAdapter,
+		ExpensesAdapter,
+		JobAdapter,
+		LocationAdapter,
+		OrganizationAdapter,
+		TimesheetAdapter,
+	},
+	Deletable,
+};
+
+use crate::{
+	archive::Archive,
+	args::{store_args::StoreArgs, RunAction},
+	config::Config,
+	discounts::Discounts,
+	fmt,
+	input::Prompter,
+	numbering::Register,
+	payments::Ledger,
+	DynResult,
+};
+
+/// The store which a [`Transfer`](super::Transfer) copies an [`Archive`] of `Job`s into.
+///
+/// This is separate from the [`Transfer`](super::Transfer) so that the adapters of the destination
+/// store can be chosen independently of those of the source store.
+pub(super) struct Destination
+{
+	/// The `Job`s being transferred, and everything they refer to.
+	pub archive: Archive,
+
+	/// The store which the `Job`s are being transferred to.
+	pub store_args: StoreArgs,
+}
+
+impl AsRef<StoreArgs> for Destination
+{
+	fn as_ref(&self) -> &StoreArgs
+	{
+		&self.store_args
+	}
+}
+
+#[async_trait::async_trait(?Send)]
+impl RunAction for Destination
+{
+	async fn action<CAdapter, EAdapter, JAdapter, LAdapter, OAdapter, TAdapter, XAdapter, Db>(
+		self,
+		connection: Pool<Db>,
+		_config: Config,
+		_prompter: &dyn Prompter,
+	) -> DynResult<()>
+	where
+		CAdapter: Deletable<Db = Db> + ContactAdapter,
+		EAdapter: Deletable<Db = Db> + EmployeeAdapter,
+		JAdapter: Deletable<Db = Db> + JobAdapter,
+		LAdapter: Deletable<Db = Db> + LocationAdapter,
+		OAdapter: Deletable<Db = Db> + OrganizationAdapter,
+		TAdapter: Deletable<Db = Db> + TimesheetAdapter,
+		XAdapter: Deletable<Db = Db> + ExpensesAdapter,
+		Db: Database + Discounts + Ledger + Register,
+		for<'connection> &'connection mut Db::Connection: Executor<'connection, Database = Db>,
+		for<'connection> &'connection mut Transaction<'connection, Db>: Executor<'connection, Database = Db>,
+	{
+		let to = self.store_args.name();
+		let existing = Archive::existing::<EAdapter, JAdapter, LAdapter, OAdapter, _>(&connection).await?;
+
+		// {{{
+		let mut transaction = connection.begin().await?;
+
+		let (restored, skipped) = self
+			.archive
+			.restore::<CAdapter, EAdapter, JAdapter, LAdapter, OAdapter, TAdapter, _>(&mut transaction, &existing)
+			.await?;
+
+		transaction.commit().await?;
+		// }}}
+
+		skipped.into_iter().for_each(|j| println!("Skipped Job {}, which is already in {to}", fmt::id_num(j.id)));
+		restored.counts().into_iter().for_each(|(kind, count)| println!("Copied {count} {kind}(s) to {to}"));
+		Ok(())
+	}
+}
@@ -0,0 +1,115 @@
+use sqlx::{Database, Executor, Pool, Transaction};
+use winvoice_adapter::{
+	schema::{
+		ContactAdapter,
+		EmployeeAdapter,
+		ExpensesAdapter,
+		JobAdapter,
+		LocationAdapter,
+		OrganizationAdapter,
+		TimesheetAdapter,
+	},
+	Deletable,
+};
+use winvoice_match::{Match, MatchJob, MatchTimesheet};
+
+use super::{destination::Destination, Transfer};
+use crate::{
+	archive::Archive,
+	args::{delete::Delete, RunAction},
+	config::Config,
+	discounts::Discounts,
+	input::{self, Prompter},
+	numbering::Register,
+	payments::Ledger,
+	DynResult,
+};
+
+#[async_trait::async_trait(?Send)]
+impl RunAction for Transfer
+{
+	async fn action<CAdapter, EAdapter, JAdapter, LAdapter, OAdapter, TAdapter, XAdapter, Db>(
+		self,
+		connection: Pool<Db>,
+		config: Config,
+		prompter: &dyn Prompter,
+	) -> DynResult<()>
+	where
+		CAdapter: Deletable<Db = Db> + ContactAdapter,
+		EAdapter: Deletable<Db = Db> + EmployeeAdapter,
+		JAdapter: Deletable<Db = Db> + JobAdapter,
+		LAdapter: Deletable<Db = Db> + LocationAdapter,
+		OAdapter: Deletable<Db = Db> + OrganizationAdapter,
+		TAdapter: Deletable<Db = Db> + TimesheetAdapter,
+		XAdapter: Deletable<Db = Db> + ExpensesAdapter,
+		Db: Database + Discounts + Ledger + Register,
+		for<'connection> &'connection mut Db::Connection: Executor<'connection, Database = Db>,
+		for<'connection> &'connection mut Transaction<'connection, Db>: Executor<'connection, Database = Db>,
+	{
+		let match_condition: Option<_> = self.match_args.try_into()?;
+		let narrowed = match_condition.is_some();
+		let retrieved = input::select_retrieved::<JAdapter, _, _>(
+			prompter,
+			&connection,
+			match_condition,
+			"Query the Jobs to transfer",
+		)
+		.await?;
+
+		let jobs = match self.r#move
+		{
+			false => input::select(prompter, retrieved, "Select the Jobs to transfer")?,
+			true => input::select_destructive(prompter, retrieved, narrowed, "Select the Jobs to move")?,
+		};
+		if jobs.is_empty()
+		{
+			return Ok(());
+		}
+
+		let timesheets = TAdapter::retrieve(&connection, MatchTimesheet {
+			job: MatchJob { id: Match::Or(jobs.iter().map(|j| j.id.into()).collect()), ..Default::default() },
+			..Default::default()
+		})
+		.await?;
+
+		let job_ids: Vec<_> = jobs.iter().map(|j| j.id).collect();
+		let mut acquired = connection.acquire().await?;
+		let payments = Db::retrieve_payments(&mut acquired, &job_ids).await?;
+		let discounts = Db::retrieve_discounts(&mut acquired, &job_ids).await?;
+		let invoice_numbers = Db::retrieve_numbers(&mut acquired, &job_ids).await?;
+		let invoice_series = Db::retrieve_series(&mut acquired).await?;
+		let invoice_due_dates = Db::retrieve_due_dates(&mut acquired, &job_ids).await?;
+		drop(acquired);
+
+		// NOTE: the jobs are only deleted from the source once they exist in the destination, so
+		//       that they are never lost (though they may be duplicated if deleting fails).
+		Destination {
+			archive: Archive {
+				discounts,
+				invoice_numbers,
+				invoice_series,
+				invoice_due_dates,
+				..Archive::of_jobs(jobs.clone(), timesheets.clone(), payments.clone())
+			},
+			store_args: self.to,
+		}
+		.run(config, prompter)
+		.await?;
+
+		if self.r#move
+		{
+			// {{{
+			let mut transaction = connection.begin().await?;
+
+			Db::delete_payments(&mut transaction, &payments).await?;
+			XAdapter::delete(&mut *transaction, timesheets.iter().flat_map(|t| t.expenses.iter())).await?;
+			TAdapter::delete(&mut *transaction, timesheets.iter()).await?;
+			JAdapter::delete(&mut *transaction, jobs.iter().inspect(|j| Delete::report_deleted(*j))).await?;
+
+			transaction.commit().await?;
+			// }}}
+		}
+
+		Ok(())
+	}
+}
@@ -24,7 +24,7 @@ pub struct Update
 	#[clap(flatten)]
 	match_args: MatchArgs,
 
-	/// Specifies the [`Store`](winvoice_config::Store) to send [`Update`]s to.
+	/// Specifies the [`Store`](crate::config::Store) to send [`Update`]s to.
 	#[clap(flatten)]
 	store_args: StoreArgs,
 }
@@ -1,7 +1,8 @@
 use clap::Subcommand as Clap;
+use money2::Money;
 use winvoice_schema::chrono::NaiveDateTime;
 
-use crate::args::flag_or_argument::FlagOrArgument;
+use crate::{args::flag_or_argument::FlagOrArgument, export::Discount};
 
 /// The specific type of information that is being updated.
 #[derive(Clap, Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
@@ -27,6 +28,12 @@ pub enum UpdateCommand
 	/// Update `Job`s in the store (-s) specified.
 	Job
 	{
+		/// How much was paid towards the invoice of each of the selected `Job`s with
+		/// --invoice-paid, in the same currency as the `hourly_rate` of the `Job` (e.g. "50.00
+		/// USD").
+		#[clap(long, requires = "invoice-paid", short = '$')]
+		amount: Option<Money>,
+
 		/// Select a number of `Job`s that are currently being worked on, in order to mark them as
 		/// having been completed.
 		///
@@ -43,6 +50,16 @@ pub enum UpdateCommand
 		)]
 		close: FlagOrArgument<NaiveDateTime>,
 
+		/// Set the discount on the invoices of the selected `Job`s, which is either a percentage of
+		/// the subtotal (e.g. "10%") or a fixed amount in the currency of the `Job` (e.g. "50.00
+		/// USD").
+		///
+		/// The discount is kept in the store alongside the `Job`s, so that it follows them when they
+		/// are restored or transferred. A discount of "0%" removes it. Discounts cannot be negative,
+		/// nor more than 100%.
+		#[clap(long, value_name = "DISCOUNT")]
+		discount: Option<Discount>,
+
 		/// Select a number of `Job`s that have been export and sent to their respective clients,
 		/// marking them as having been paid for.
 		///
@@ -60,16 +77,18 @@ pub enum UpdateCommand
 		invoice_issued: FlagOrArgument<NaiveDateTime>,
 
 		/// Select a number of `Job`s that have been export and sent to their respective clients,
-		/// marking them as having been paid for.
+		/// recording a payment of some --amount towards each of them.
 		///
-		/// You may *optionally* provide the time that the invoice was paid (e.g.
-		/// "2022-01-01T14:00:00").
+		/// You may *optionally* provide the time that the payment was made (e.g.
+		/// "2022-01-01T14:00:00"). The invoice is only marked as paid once the payments towards it
+		/// add up to its total.
 		#[clap(
 			default_missing_value = stringify!(true),
 			default_value_t,
 			group = "paid-reopen",
 			hide_default_value = true,
 			long,
+			requires = "amount",
 			short = 'p',
 			value_name = "(OPTIONAL) DATE"
 		)]
@@ -77,6 +96,9 @@ pub enum UpdateCommand
 
 		/// Select a number of `Job`s that were --closed in order to mark them as being currently
 		/// worked on.
+		///
+		/// Their invoices are no longer issued, so any payments towards them and the invoice numbers
+		/// which were assigned to them are deleted.
 		#[clap(action, groups = &["close-reopen", "issued-reopen", "paid-reopen"], long, short)]
 		reopen: bool,
 	},
@@ -16,14 +16,18 @@ use winvoice_adapter::{
 	Deletable,
 	Updatable,
 };
-use winvoice_config::Config;
-use winvoice_schema::{ContactKind, InvoiceDate, RestorableSerde};
+use winvoice_schema::{chrono::Local, ContactKind, InvoiceDate, RestorableSerde};
 
 use super::{Update, UpdateCommand};
 use crate::{
-	args::RunAction,
+	args::{create::Create, delete::Delete, RunAction},
+	config::{Config, Extensions},
+	discounts::Discounts,
+	export::Discount,
 	fmt,
-	input::{self, expense},
+	input::{self, expense, Prompter},
+	numbering::{self, Register},
+	payments::{self, CurrencyMismatch, Ledger},
 	utils::Identifiable,
 	DynResult,
 };
@@ -35,6 +39,7 @@ impl RunAction for Update
 		self,
 		connection: Pool<Db>,
 		config: Config,
+		prompter: &dyn Prompter,
 	) -> DynResult<()>
 	where
 		CAdapter: Deletable<Db = Db> + ContactAdapter,
@@ -44,13 +49,15 @@ impl RunAction for Update
 		OAdapter: Deletable<Db = Db> + OrganizationAdapter,
 		TAdapter: Deletable<Db = Db> + TimesheetAdapter,
 		XAdapter: Deletable<Db = Db> + ExpensesAdapter,
-		Db: Database,
+		Db: Database + Discounts + Ledger + Register,
 		for<'connection> &'connection mut Db::Connection: Executor<'connection, Database = Db>,
 		for<'connection> &'connection mut Transaction<'connection, Db>: Executor<'connection, Database = Db>,
 	{
 		/// Uses [`Iterator::filter_map`] to filter out items of `iter` which return [`None`] from
-		/// [`input::confirm_then_some`], otherwise mapping
+		/// [`input::confirm_then_some`], otherwise mapping them with `try_fn`. Stops at the first
+		/// error, including any error that occurs while asking.
 		async fn filter_then_try_for_each<'input, Iter, Input, PromptFn, Prompt, TryFn, TryFnFut>(
+			prompter: &dyn Prompter,
 			iter: Iter,
 			prompt: PromptFn,
 			try_fn: TryFn,
@@ -58,14 +65,16 @@ impl RunAction for Update
 		where
 			Input: 'input,
 			Iter: Iterator<Item = &'input mut Input>,
-			Prompt: Into<String>,
+			Prompt: AsRef<str>,
 			PromptFn: Fn(&Input) -> Prompt,
 			TryFn: Fn(&'input mut Input) -> TryFnFut,
 			TryFnFut: Future<Output = DynResult<()>>,
 		{
-			stream::iter(iter.filter_map(move |item| input::confirm_then_some(prompt(item), Ok(item))))
-				.try_for_each(try_fn)
-				.await
+			stream::iter(iter.filter_map(move |item| {
+				input::confirm_then_some(prompter, prompt(item), item).map_err(Into::into).transpose()
+			}))
+			.try_for_each(try_fn)
+			.await
 		}
 
 		/// Gets the first line of any given [`&str`] `s`.
@@ -76,7 +85,11 @@ impl RunAction for Update
 
 		/// A generic deletion function which works for any of the provided adapters in the outer
 		/// function, as they all implement `Updatable` at the minimum.
-		async fn update<Upd, Db>(connection: &Pool<Db>, entities: &mut [Upd::Entity]) -> DynResult<()>
+		async fn update<Upd, Db>(
+			prompter: &dyn Prompter,
+			connection: &Pool<Db>,
+			entities: &mut [Upd::Entity],
+		) -> DynResult<()>
 		where
 			Db: Database,
 			Upd: Updatable<Db = Db>,
@@ -85,7 +98,7 @@ impl RunAction for Update
 		{
 			#[rustfmt::skip]
 			entities.iter_mut().try_for_each(|e| {
-				*e = input::edit_and_restore(e, format!(
+				*e = input::edit_and_restore(prompter, e, format!(
 					"Make any desired edits to the {}",
 					fmt::type_name::<Upd::Entity>()
 				))?;
@@ -107,6 +120,7 @@ impl RunAction for Update
 			{
 				let match_condition = self.match_args.try_into()?;
 				let mut selected = input::select_retrieved::<CAdapter, _, _>(
+					prompter,
 					&connection,
 					match_condition,
 					"Query the Contacts to update",
@@ -117,15 +131,19 @@ impl RunAction for Update
 				stream::iter(selected.iter_mut().filter_map(|contact| match contact.kind
 				{
 					ContactKind::Address(_) => input::confirm_then_some(
+						prompter,
 						format!("Do you want to change the location of {}?", fmt::quoted(&contact.label)),
-						Ok(contact),
-					),
+						contact,
+					)
+					.map_err(Into::into)
+					.transpose(),
 					_ => None,
 				}))
 				.try_for_each(|contact| {
 					let connection = &connection;
 					async {
 						contact.kind = input::select_one_retrieved::<LAdapter, _, _>(
+							prompter,
 							connection,
 							None,
 							"Query the Location you want to set this address to",
@@ -138,7 +156,7 @@ impl RunAction for Update
 				})
 				.await?;
 
-				update::<CAdapter, _>(&connection, &mut selected).await?;
+				update::<CAdapter, _>(prompter, &connection, &mut selected).await?;
 			},
 
 			UpdateCommand::Employee { default } =>
@@ -150,19 +168,21 @@ impl RunAction for Update
 				};
 
 				let mut selected = input::select_retrieved::<EAdapter, _, _>(
+					prompter,
 					&connection,
 					match_condition,
 					"Query the Employees to update",
 				)
 				.await?;
 
-				update::<EAdapter, _>(&connection, &mut selected).await?;
+				update::<EAdapter, _>(prompter, &connection, &mut selected).await?;
 			},
 
 			UpdateCommand::Expense =>
 			{
 				let match_condition = self.match_args.try_into()?;
 				let mut selected = input::select_retrieved::<XAdapter, _, _>(
+					prompter,
 					&connection,
 					match_condition,
 					"Query the Expenses to update",
@@ -171,12 +191,14 @@ impl RunAction for Update
 
 				#[rustfmt::skip]
 				filter_then_try_for_each(
+					prompter,
 					selected.iter_mut(),
 					|x| format!("Do you want to change the Timesheet of {x}?"),
 					|x| {
 						let connection = &connection;
 						async {
 							x.timesheet_id = input::select_one_retrieved::<TAdapter, _, _>(
+								prompter,
 								connection,
 								None,
 								"Query the Timesheet to attach this Expense to",
@@ -190,13 +212,14 @@ impl RunAction for Update
 				)
 				.await?;
 
-				update::<XAdapter, _>(&connection, &mut selected).await?;
+				update::<XAdapter, _>(prompter, &connection, &mut selected).await?;
 			},
 
 			UpdateCommand::Location =>
 			{
 				let match_condition = self.match_args.try_into()?;
 				let mut selected = input::select_retrieved::<LAdapter, _, _>(
+					prompter,
 					&connection,
 					match_condition,
 					"Query the Locations to update",
@@ -205,6 +228,7 @@ impl RunAction for Update
 
 				#[rustfmt::skip]
 				filter_then_try_for_each(
+					prompter,
 					selected.iter_mut(),
 					|l| format!(
 						"Do you want to put {} into a new Location",
@@ -214,6 +238,7 @@ impl RunAction for Update
 						let connection = &connection;
 						async {
 							location.outer = input::select_one_retrieved::<LAdapter, _, _>(
+								prompter,
 								connection,
 								None,
 								format!(
@@ -230,18 +255,23 @@ impl RunAction for Update
 				)
 				.await?;
 
-				update::<LAdapter, _>(&connection, &mut selected).await?;
+				update::<LAdapter, _>(prompter, &connection, &mut selected).await?;
 			},
 
-			UpdateCommand::Job { close, invoice_issued, invoice_paid, reopen } =>
+			UpdateCommand::Job { amount, close, discount, invoice_issued, invoice_paid, reopen } =>
 			{
 				let match_condition = self.match_args.try_into()?;
-				let mut selected =
-					input::select_retrieved::<JAdapter, _, _>(&connection, match_condition, "Query the Jobs to update")
-						.await?;
+				let mut selected = input::select_retrieved::<JAdapter, _, _>(
+					prompter,
+					&connection,
+					match_condition,
+					"Query the Jobs to update",
+				)
+				.await?;
 
 				#[rustfmt::skip]
 				filter_then_try_for_each(
+					prompter,
 					selected.iter_mut(),
 					|j| format!(
 						"Do you want to change the client {} of Job {} ({})?",
@@ -253,6 +283,7 @@ impl RunAction for Update
 						let connection = &connection;
 						async {
 							j.client = input::select_one_retrieved::<OAdapter, _, _>(
+								prompter,
 								connection,
 								None,
 								"Query the Organization you want to set this Job's client to",
@@ -264,9 +295,27 @@ impl RunAction for Update
 					},
 				).await?;
 
-				if !(close.flag() || invoice_issued.flag() || invoice_paid.flag() || reopen)
+				if let Some(Discount::Fixed(m)) = discount
+				{
+					if let Some(j) = selected.iter().find(|j| j.invoice.hourly_rate.currency != m.currency)
+					{
+						let expected = j.invoice.hourly_rate.currency;
+						return Err(CurrencyMismatch { expected, found: m.currency }.into());
+					}
+				}
+
+				if let Some(a) = amount
 				{
-					return update::<JAdapter, _>(&connection, &mut selected).await;
+					if let Some(j) = selected.iter().find(|j| j.invoice.hourly_rate.currency != a.currency)
+					{
+						let expected = j.invoice.hourly_rate.currency;
+						return Err(CurrencyMismatch { expected, found: a.currency }.into());
+					}
+				}
+
+				if !(close.flag() || discount.is_some() || invoice_issued.flag() || invoice_paid.flag() || reopen)
+				{
+					return update::<JAdapter, _>(prompter, &connection, &mut selected).await;
 				}
 
 				let close_arg = close.iff_flagged_utc_or_now();
@@ -285,7 +334,7 @@ impl RunAction for Update
 					if close_arg.is_some()
 					{
 						s.date_close = close_arg;
-						let expenses = expense::menu()?;
+						let expenses = expense::menu(prompter)?;
 						XAdapter::create(&connection, expenses, s.id).await?;
 					}
 
@@ -293,17 +342,84 @@ impl RunAction for Update
 					{
 						s.invoice.date = Some(InvoiceDate { issued: arg, paid: None });
 					}
+				}
+
+				let invoices = match issued_arg.is_some() || paid_arg.is_some() || reopen
+				{
+					false => None,
+					true => Some(Extensions::read()?.invoices),
+				};
+
+				// {{{
+				let mut transaction = connection.begin().await?;
+				let job_ids: Vec<_> = selected.iter().map(|j| j.id).collect();
 
-					if let Some((_, date)) = paid_arg.zip(s.invoice.date)
+				if reopen
+				{
+					let payments = Db::retrieve_payments(&mut transaction, &job_ids).await?;
+					Db::delete_payments(&mut transaction, &payments).await?;
+					payments.iter().for_each(Delete::report_deleted);
+					Db::delete_numbers(&mut transaction, &job_ids).await?;
+				}
+
+				if let Some(d) = discount
+				{
+					for j in &selected
 					{
-						s.invoice.date = Some(InvoiceDate { paid: paid_arg, ..date });
+						Db::create_discount(&mut transaction, j.id, d).await?;
+					}
+				}
+
+				let mut balances = Vec::new();
+				if let Some(((date, a), i)) = paid_arg.zip(amount).zip(invoices.as_ref())
+				{
+					for j in &selected
+					{
+						let created = Db::create_payment(&mut transaction, j.id, a, date, String::new()).await?;
+						Create::report_created(&created);
+					}
+
+					let discounts = Db::retrieve_discounts(&mut transaction, &job_ids).await?;
+					let payments = Db::retrieve_payments(&mut transaction, &job_ids).await?;
+					let store = self.store_args.name();
+					for j in &mut selected
+					{
+						let balance =
+							payments::balance::<TAdapter, _>(&connection, &payments, &discounts, i, store, j, None)
+								.await?;
+						payments::reconcile(j, balance, date);
+						balances.push((j.id, balance));
 					}
 				}
 
-				let mut transaction = connection.begin().await?;
 				JAdapter::update(&mut transaction, selected.iter().inspect(|e| Self::report_updated(*e))).await?;
 
+				if let Some(i) = invoices.as_ref().filter(|_| issued_arg.is_some() || reopen)
+				{
+					numbering::assign::<Db, _>(&mut transaction, i.numbering.as_ref(), &selected).await?;
+					numbering::schedule::<Db, _>(&mut transaction, i, self.store_args.name(), &selected).await?;
+				}
+
 				transaction.commit().await?;
+				// }}}
+
+				balances.into_iter().for_each(|(id, balance)| {
+					println!("The remaining balance of Job {} is {balance}", fmt::id_num(id));
+				});
+
+				if let Some(invoices) = invoices.filter(|_| issued_arg.is_some())
+				{
+					selected
+						.iter()
+						.filter_map(|j| invoices.due(self.store_args.name(), j).map(|d| (j.id, d)))
+						.for_each(|(id, d)| {
+							println!(
+								"Job {} is due by {}",
+								fmt::id_num(id),
+								d.with_timezone(&Local).format("%Y-%m-%d %H:%M")
+							);
+						});
+				}
 			},
 
 			UpdateCommand::Organization { employer } =>
@@ -315,6 +431,7 @@ impl RunAction for Update
 				};
 
 				let mut selected = input::select_retrieved::<OAdapter, _, _>(
+					prompter,
 					&connection,
 					match_condition,
 					"Query the Organizations to update",
@@ -323,12 +440,14 @@ impl RunAction for Update
 
 				#[rustfmt::skip]
 				filter_then_try_for_each(
+					prompter,
 					selected.iter_mut(),
 					|o| format!("Do you want to change the Location of {}?", fmt::quoted(&o.name)),
 					|o| {
 						let connection = &connection;
 						async {
 							o.location = input::select_one_retrieved::<LAdapter, _, _>(
+								prompter,
 								connection,
 								None,
 								"Query the Location you want to move this Organization to",
@@ -341,13 +460,14 @@ impl RunAction for Update
 				)
 				.await?;
 
-				update::<OAdapter, _>(&connection, &mut selected).await?;
+				update::<OAdapter, _>(prompter, &connection, &mut selected).await?;
 			},
 
 			UpdateCommand::Timesheet { restart, stop } =>
 			{
 				let match_condition = self.match_args.try_into()?;
 				let mut selected = input::select_retrieved::<TAdapter, _, _>(
+					prompter,
 					&connection,
 					match_condition,
 					"Query the Timesheets to update",
@@ -356,6 +476,7 @@ impl RunAction for Update
 
 				#[rustfmt::skip]
 				filter_then_try_for_each(
+					prompter,
 					selected.iter_mut(),
 					|t| format!(
 						"Do you want to change the employee {} attached to Timesheet {} ({})?",
@@ -367,6 +488,7 @@ impl RunAction for Update
 						let connection = &connection;
 						async {
 							t.employee = input::select_one_retrieved::<EAdapter, _, _>(
+								prompter,
 								connection,
 								None,
 								"Query the Employee you want to assign to this Timesheet",
@@ -381,6 +503,7 @@ impl RunAction for Update
 
 				#[rustfmt::skip]
 				filter_then_try_for_each(
+					prompter,
 					selected.iter_mut(),
 					|t| format!(
 						"Do you want to change the job {} ({}) that Timesheet {} ({}) is assigned to?",
@@ -393,6 +516,7 @@ impl RunAction for Update
 						let connection = &connection;
 						async {
 							t.job = input::select_one_retrieved::<JAdapter, _, _>(
+								prompter,
 								connection,
 								None,
 								"Query the Job you want to assign this Timesheet to",
@@ -407,7 +531,7 @@ impl RunAction for Update
 
 				if !(restart.flag() || stop.flag())
 				{
-					return update::<TAdapter, _>(&connection, &mut selected).await;
+					return update::<TAdapter, _>(prompter, &connection, &mut selected).await;
 				}
 
 				let restart_arg = restart.iff_flagged_utc_or_now();
@@ -0,0 +1,385 @@
+//! This module contains the sections of the Winvoice configuration file which are specific to this
+//! program, and are therefore not part of [`winvoice_config::Config`].
+//!
+//! Since [`winvoice_config::Config`] does not know about these sections (or about `[stores]` which
+//! use an [`Adapter`] it does not support), [`winvoice_config::Config::read`] and
+//! [`winvoice_config::Config::write`] should not be used. Use [`read`] and [`write`] instead, which
+//! separate the sections from the rest of the configuration file at [`path`].
+
+mod invoices;
+mod queries;
+mod stores;
+mod timesheets;
+
+use core::ops::{Deref, DerefMut};
+use std::{
+	fs,
+	io,
+	path::{Path, PathBuf},
+	sync::OnceLock,
+};
+
+pub use invoices::Invoices;
+pub use queries::Queries;
+use serde::{de::Error as _, ser::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+pub use stores::{Adapter, FeatureNotFound, Store, Stores};
+pub use timesheets::Timesheets;
+use toml::{value::Table, Value};
+
+use crate::input::Result;
+
+/// The path to the configuration file, when it is not the [default](winvoice_config::Config::path).
+static PATH: OnceLock<PathBuf> = OnceLock::new();
+
+/// A [`winvoice_config::Config`], along with the `[stores]` which it does not know about (see
+/// [`Stores`]).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Config
+{
+	/// Everything in the configuration file except for the [`Extensions`] and the [`Stores`].
+	config: winvoice_config::Config,
+
+	/// The `[stores]` which the `config` does not know about.
+	stores: Stores,
+}
+
+impl Config
+{
+	/// The [`Store`] with some `name`, if there is one.
+	pub fn get_store(&self, name: &str) -> Option<winvoice_config::Result<Store>>
+	{
+		self.stores
+			.get(name)
+			.map_or_else(|| self.config.get_store(name).map(Store::try_from), |store| Some(Ok(store.clone())))
+	}
+}
+
+impl Deref for Config
+{
+	type Target = winvoice_config::Config;
+
+	fn deref(&self) -> &Self::Target
+	{
+		&self.config
+	}
+}
+
+impl DerefMut for Config
+{
+	fn deref_mut(&mut self) -> &mut Self::Target
+	{
+		&mut self.config
+	}
+}
+
+impl<'de> Deserialize<'de> for Config
+{
+	fn deserialize<D>(deserializer: D) -> core::result::Result<Self, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		let mut table = Table::deserialize(deserializer)?;
+		let stores = Stores::remove_from(&mut table);
+		let config = Value::Table(table).try_into().map_err(D::Error::custom)?;
+		Ok(Self { config, stores })
+	}
+}
+
+impl Serialize for Config
+{
+	fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
+	where
+		S: Serializer,
+	{
+		let mut table = match Value::try_from(&self.config).map_err(S::Error::custom)?
+		{
+			Value::Table(t) => t,
+			_ => Table::new(),
+		};
+
+		self.stores.insert_into(&mut table).map_err(S::Error::custom)?;
+		table.serialize(serializer)
+	}
+}
+
+/// The sections of the configuration file which are specific to this program.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Extensions
+{
+	/// Settings for exporting `Job`s as invoices.
+	#[serde(default, skip_serializing_if = "Invoices::is_empty")]
+	pub invoices: Invoices,
+
+	/// Match conditions which have been saved under a name, so that they can be reused.
+	#[serde(default, skip_serializing_if = "Queries::is_empty")]
+	pub queries: Queries,
+
+	/// Information used by the punch clock (i.e. `winvoice start`).
+	#[serde(default, skip_serializing_if = "Timesheets::is_empty")]
+	pub timesheets: Timesheets,
+}
+
+impl Extensions
+{
+	/// Read the [`Extensions`] from the configuration file.
+	pub fn read() -> Result<Self>
+	{
+		Self::read_from(path())
+	}
+
+	/// Read the [`Extensions`] from the configuration file at `filepath`.
+	fn read_from(filepath: &Path) -> Result<Self>
+	{
+		read_table(filepath).and_then(|table| Value::Table(table).try_into().map_err(Into::into))
+	}
+
+	/// Remove the sections of a configuration file's `table` which belong to the [`Extensions`].
+	fn remove_from(table: &mut Table)
+	{
+		table.remove("queries");
+		table.remove("timesheets");
+		if let Some(Value::Table(invoices)) = table.get_mut("invoices")
+		{
+			Invoices::KEYS.into_iter().for_each(|key| drop(invoices.remove(key)));
+		}
+	}
+
+	/// Replace the [`Extensions`] in the configuration file.
+	pub fn write(&self) -> Result<()>
+	{
+		write_replaced(path(), self)
+	}
+}
+
+/// Recursively merge `from` into `into`, replacing values which are not tables.
+fn merge(into: &mut Table, from: Table)
+{
+	from.into_iter().for_each(|(key, value)| match (into.get_mut(&key), value)
+	{
+		(Some(Value::Table(into_table)), Value::Table(from_table)) => merge(into_table, from_table),
+		(_, value) =>
+		{
+			into.insert(key, value);
+		},
+	});
+}
+
+/// The path to the Winvoice configuration file: the one [set](set_path) by `--config`, or else
+/// the [default](winvoice_config::Config::path).
+pub fn path() -> &'static Path
+{
+	PATH.get_or_init(winvoice_config::Config::path)
+}
+
+/// Read the [`Config`] from the configuration file at [`path`], without the [`Extensions`].
+pub fn read() -> Result<Config>
+{
+	read_config(path())
+}
+
+/// Read the [`Config`] from the configuration file at `filepath`, without the [`Extensions`].
+fn read_config(filepath: &Path) -> Result<Config>
+{
+	let mut table = match fs::read_to_string(filepath)
+	{
+		Ok(contents) => toml::from_str(&contents)?,
+		Err(e) => return Err(io::Error::new(e.kind(), format!("{}: {e}", filepath.display())).into()),
+	};
+
+	Extensions::remove_from(&mut table);
+	Value::Table(table).try_into().map_err(Into::into)
+}
+
+/// Read the configuration file at `filepath` as a [`Table`], which is empty if there is no file.
+fn read_table(filepath: &Path) -> Result<Table>
+{
+	match fs::read_to_string(filepath)
+	{
+		Ok(contents) => toml::from_str(&contents).map_err(Into::into),
+		Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Table::new()),
+		Err(e) => Err(e.into()),
+	}
+}
+
+/// Use the configuration file at `filepath` instead of the [default](winvoice_config::Config::path).
+///
+/// Has no effect once the [`path`] has been used.
+pub fn set_path(filepath: PathBuf)
+{
+	PATH.set(filepath).ok();
+}
+
+/// Serialize the `config` and the `extensions` as they would appear together in the configuration
+/// file.
+fn to_string(config: &Config, extensions: &Extensions) -> Result<String>
+{
+	let mut table = Table::new();
+	[Value::try_from(config)?, Value::try_from(extensions)?].into_iter().for_each(|value| {
+		if let Value::Table(t) = value
+		{
+			merge(&mut table, t);
+		}
+	});
+
+	toml::to_string_pretty(&table).map_err(Into::into)
+}
+
+/// Serialize the `config` and the [`Extensions`] as they would appear together in the
+/// configuration file.
+pub fn to_string_pretty(config: &Config) -> Result<String>
+{
+	to_string(config, &Extensions::read()?)
+}
+
+/// Write the `config` to the configuration file at [`path`], preserving the [`Extensions`].
+pub fn write(config: &Config) -> Result<()>
+{
+	write_to(path(), &to_string(config, &Extensions::read()?)?)
+}
+
+/// Replace the configuration file at [`path`] with some `contents`, which must contain both a
+/// valid [`Config`] and valid [`Extensions`].
+pub fn write_str(contents: &str) -> Result<()>
+{
+	let mut table: Table = toml::from_str(contents)?;
+	let extensions: Extensions = Value::Table(table.clone()).try_into()?;
+	Extensions::remove_from(&mut table);
+	let config: Config = Value::Table(table).try_into()?;
+
+	write_to(path(), &to_string(&config, &extensions)?)
+}
+
+/// Replace the [`Extensions`] in the configuration file at `filepath` with some `value`.
+fn write_replaced<T>(filepath: &Path, value: &T) -> Result<()>
+where
+	T: Serialize,
+{
+	let mut table = read_table(filepath)?;
+	Extensions::remove_from(&mut table);
+	if let Value::Table(t) = Value::try_from(value)?
+	{
+		merge(&mut table, t);
+	}
+
+	write_to(filepath, &toml::to_string_pretty(&table)?)
+}
+
+/// Write some `contents` to the configuration file at `filepath`.
+fn write_to(filepath: &Path, contents: &str) -> Result<()>
+{
+	if let Some(parent) = filepath.parent()
+	{
+		fs::create_dir_all(parent)?;
+	}
+
+	fs::write(filepath, contents).map_err(Into::into)
+}
+
+#[cfg(test)]
+mod tests
+{
+	use std::fs;
+
+	use pretty_assertions::assert_eq;
+	use toml::{value::Table, Value};
+	use winvoice_match::{MatchJob, MatchStr};
+
+	use super::{Adapter, Config, Extensions, Queries, Store};
+	use crate::utils;
+
+	/// A [`Config`] without any [`Extensions`].
+	const CONFIG: &str = "[employees]
+id = 3
+
+[invoices]
+default_currency = 'USD'
+
+[jobs]
+default_increment = '15min'
+
+[organizations]
+
+[stores.default]
+adapter = 'postgres'
+url = 'postgres://'
+";
+
+	#[test]
+	fn read_config()
+	{
+		let mut filepath = utils::temp_file::<Config>("read-config");
+		filepath.set_extension("toml");
+		fs::write(
+			&filepath,
+			format!(
+				"{CONFIG}
+[invoices.client_tax_rates.default]
+3 = 8.25
+
+[queries.job.foo]
+notes.contains = 'foo'
+
+[timesheets.last_job_id]
+default = 4"
+			),
+		)
+		.unwrap();
+
+		assert_eq!(super::read_config(&filepath).unwrap(), toml::from_str::<Config>(CONFIG).unwrap());
+		assert!(super::read_config(&filepath.with_extension("missing")).is_err());
+	}
+
+	#[test]
+	fn stores()
+	{
+		let config: Config = toml::from_str(&format!(
+			"{CONFIG}
+[stores.local]
+adapter = 'sqlite'
+url = 'sqlite://winvoice.db'"
+		))
+		.unwrap();
+
+		assert_eq!(config.get_store("default").unwrap().unwrap(), Store {
+			adapter: Adapter::Postgres,
+			url: "postgres://".into()
+		});
+		assert_eq!(config.get_store("local").unwrap().unwrap(), Store {
+			adapter: Adapter::Sqlite,
+			url: "sqlite://winvoice.db".into()
+		});
+		assert!(config.get_store("missing").is_none());
+
+		let serialized = super::to_string(&config, &Extensions::default()).unwrap();
+		assert_eq!(toml::from_str::<Config>(&serialized).unwrap(), config);
+	}
+
+	#[test]
+	fn write_replaced()
+	{
+		let mut filepath = utils::temp_file::<Extensions>("write-replaced");
+		filepath.set_extension("toml");
+		fs::write(&filepath, CONFIG).unwrap();
+
+		let mut extensions = Extensions::read_from(&filepath).unwrap();
+		assert_eq!(extensions, Extensions::default());
+
+		let condition = MatchJob { notes: MatchStr::Contains("foo".into()), ..Default::default() };
+		extensions.queries.insert("foo", &condition).unwrap();
+		super::write_replaced(&filepath, &extensions).unwrap();
+
+		let read = Extensions::read_from(&filepath).unwrap();
+		assert_eq!(read, extensions);
+		assert_eq!(read.queries.get::<MatchJob>("foo").unwrap(), Some(condition));
+
+		let table = super::read_table(&filepath).unwrap();
+		assert_eq!(table["employees"]["id"], Value::Integer(3));
+		assert_eq!(table["invoices"]["default_currency"], Value::String("USD".into()));
+		assert_eq!(table["stores"]["default"]["url"], Value::String("postgres://".into()));
+
+		extensions.queries = Queries::default();
+		super::write_replaced(&filepath, &extensions).unwrap();
+		assert_eq!(Extensions::read_from(&filepath).unwrap(), Extensions::default());
+		assert_eq!(super::read_table(&filepath).unwrap(), toml::from_str::<Table>(CONFIG).unwrap());
+	}
+}
@@ -0,0 +1,327 @@
+use core::iter;
+use std::{collections::BTreeMap, path::PathBuf};
+
+use money2::{Currency, Decimal};
+use serde::{de, Deserialize, Deserializer, Serialize};
+use winvoice_schema::{
+	chrono::{DateTime, Duration, Utc},
+	Id,
+	Job,
+};
+
+use crate::{
+	export::{Discount, Filename, Terms},
+	numbering::Pattern,
+};
+
+/// Settings for exporting `Job`s as invoices which are specific to this program, and are kept
+/// alongside those of [`winvoice_config::Invoices`].
+///
+/// ```toml
+/// [invoices]
+/// filename = "{date}--{client}--{number}"
+/// numbering = "INV-{year}-{seq:04}"
+/// payment_terms = 30
+/// template = "path/to/invoice.html.hbs"
+///
+/// # The `id`s below are of entities in the "default" store.
+/// [invoices.client_payment_terms.default]
+/// 3 = 15
+///
+/// [invoices.client_tax_rates.default]
+/// 3 = 0
+///
+/// [invoices.location_tax_rates.default]
+/// 1 = 20
+/// 5 = 8.25
+///
+/// [invoices.rates]
+/// JPY = 160.5
+/// USD = 1.08
+/// ```
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Invoices
+{
+	/// Overrides of the [`payment_terms`](Invoices::payment_terms) for specific clients, keyed by
+	/// the name of the store and then the `id` of the `Organization`.
+	#[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+	pub client_payment_terms: ByStore<u32>,
+
+	/// The percentage of tax charged to specific clients (e.g. 8.25 for 8.25%), keyed by the name
+	/// of the store and then the `id` of the `Organization`. Takes precedence over the
+	/// [`location_tax_rates`](Invoices::location_tax_rates).
+	#[serde(default, deserialize_with = "tax_rates", skip_serializing_if = "BTreeMap::is_empty")]
+	pub client_tax_rates: ByStore<Decimal>,
+
+	/// The [`Filename`] of exported invoices, which is `{client}--{number}` by default.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub filename: Option<Filename>,
+
+	/// The percentage of tax charged to clients in specific `Location`s, keyed by the name of the
+	/// store and then the `id` of the `Location`. A client is charged the rate of the innermost
+	/// `Location` it is in which has one.
+	#[serde(default, deserialize_with = "tax_rates", skip_serializing_if = "BTreeMap::is_empty")]
+	pub location_tax_rates: ByStore<Decimal>,
+
+	/// The [`Pattern`] of the numbers which are assigned to invoices when they are issued. When
+	/// there is none, invoices are not numbered, and the `id` of their `Job` is used instead.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub numbering: Option<Pattern>,
+
+	/// The number of days after an invoice is issued that it must be paid by (e.g. 30 for "Net
+	/// 30").
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub payment_terms: Option<u32>,
+
+	/// Exchange rates to use instead of fetching them, as the number of units of each currency
+	/// which are equal to one EUR. See [`exchange::rates`](crate::exchange::rates).
+	#[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+	pub rates: BTreeMap<Currency, Decimal>,
+
+	/// The [`Template`](crate::export::Template) to render when exporting with `--export template`.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub template: Option<PathBuf>,
+}
+
+impl Invoices
+{
+	/// The keys of the `[invoices]` section which belong to [`Invoices`] rather than
+	/// [`winvoice_config::Invoices`].
+	pub const KEYS: [&'static str; 8] = [
+		"client_payment_terms",
+		"client_tax_rates",
+		"filename",
+		"location_tax_rates",
+		"numbering",
+		"payment_terms",
+		"rates",
+		"template",
+	];
+
+	/// The date that the invoice of the `job` in the `store` must be paid by, according to the
+	/// current payment terms of its `client`.
+	///
+	/// Returns [`None`] if the invoice has not been issued, or there are no payment terms.
+	pub fn due(&self, store: &str, job: &Job) -> Option<DateTime<Utc>>
+	{
+		let terms = get(&self.client_payment_terms, store, job.client.id).or(self.payment_terms);
+		job.invoice.date.zip(terms).map(|(d, t)| d.issued + Duration::days(t.into()))
+	}
+
+	/// The date that the invoice of the `job` in the `store` must be paid by: the one which was
+	/// [recorded](crate::numbering::schedule) in the `due_dates` when it was issued, or else (for
+	/// invoices which were issued before due dates were recorded) the one it is [`due`](Self::due)
+	/// by now.
+	///
+	/// Returns [`None`] if the invoice has not been issued, or it has no due date.
+	pub fn due_by(
+		&self,
+		store: &str,
+		job: &Job,
+		due_dates: &BTreeMap<Id, Option<DateTime<Utc>>>,
+	) -> Option<DateTime<Utc>>
+	{
+		job.invoice.date.and_then(|_| due_dates.get(&job.id).copied().unwrap_or_else(|| self.due(store, job)))
+	}
+
+	/// The tax rate which applies to the `client` of the `job` in the `store`: either its own rate,
+	/// or that of the innermost `Location` it is in which has one.
+	pub fn tax_rate(&self, store: &str, job: &Job) -> Option<Decimal>
+	{
+		get(&self.client_tax_rates, store, job.client.id).or_else(|| {
+			iter::successors(Some(&job.client.location), |l| l.outer.as_deref())
+				.find_map(|l| get(&self.location_tax_rates, store, l.id))
+		})
+	}
+
+	/// The [`Terms`] of the invoice of the `job` in the `store`, which has some `discount` if it
+	/// was [given](crate::discounts::Discounts::create_discount) one, some invoice `number` if it
+	/// was [assigned](crate::numbering::Register::assign_number) one, and is `due` by some date
+	/// (see [`due_by`](Self::due_by)).
+	pub fn terms(
+		&self,
+		store: &str,
+		job: &Job,
+		discount: Option<Discount>,
+		number: Option<&str>,
+		due: Option<DateTime<Utc>>,
+	) -> Terms
+	{
+		Terms { discount, due, number: number.map(Into::into), tax: self.tax_rate(store, job) }
+	}
+
+	/// Whether there are no settings for invoices.
+	pub fn is_empty(&self) -> bool
+	{
+		self.client_payment_terms.is_empty() &&
+			self.client_tax_rates.is_empty() &&
+			self.filename.is_none() &&
+			self.location_tax_rates.is_empty() &&
+			self.numbering.is_none() &&
+			self.payment_terms.is_none() &&
+			self.rates.is_empty() &&
+			self.template.is_none()
+	}
+}
+
+/// Settings which apply to specific entities, keyed by the name of the store they are in and then
+/// their `id`.
+pub type ByStore<T> = BTreeMap<String, BTreeMap<String, T>>;
+
+/// Get the setting for the entity with some `id` in the `store`.
+fn get<T>(settings: &ByStore<T>, store: &str, id: Id) -> Option<T>
+where
+	T: Copy,
+{
+	settings.get(store).and_then(|s| s.get(&id.to_string())).copied()
+}
+
+/// Deserialize tax rates, making sure that each is a percentage between 0 and 100.
+fn tax_rates<'de, D>(deserializer: D) -> Result<ByStore<Decimal>, D::Error>
+where
+	D: Deserializer<'de>,
+{
+	let rates = ByStore::<Decimal>::deserialize(deserializer)?;
+	if let Some(r) =
+		rates.values().flat_map(BTreeMap::values).find(|r| **r < Decimal::ZERO || **r > Decimal::ONE_HUNDRED)
+	{
+		return Err(de::Error::custom(format!("{r}% is not a tax rate between 0% and 100%")));
+	}
+
+	Ok(rates)
+}
+
+#[cfg(test)]
+mod tests
+{
+	use core::time::Duration;
+
+	use money2::Decimal;
+	use pretty_assertions::assert_eq;
+	use winvoice_schema::{
+		chrono::{TimeZone, Utc},
+		Currency,
+		Invoice,
+		InvoiceDate,
+		Job,
+		Location,
+		Money,
+		Organization,
+	};
+
+	use super::Invoices;
+	use crate::export::{Discount, Terms};
+
+	#[test]
+	fn due()
+	{
+		let issued = Utc.ymd(2022, 1, 1).and_hms(0, 0, 0);
+		let mut job = Job {
+			client: Organization {
+				id: 3,
+				location: Location { id: 0, name: "Earth".into(), outer: None },
+				name: "Foo".into(),
+			},
+			date_close: None,
+			date_open: issued,
+			id: 0,
+			increment: Duration::from_secs(15 * 60),
+			invoice: Invoice { date: None, hourly_rate: Money::new(20_00, 2, Currency::Usd) },
+			notes: String::new(),
+			objectives: "Write code".into(),
+		};
+
+		let mut invoices = Invoices { payment_terms: Some(30), ..Default::default() };
+		assert_eq!(invoices.due("default", &job), None);
+
+		job.invoice.date = Some(InvoiceDate { issued, paid: None });
+		assert_eq!(invoices.due("default", &job), Some(Utc.ymd(2022, 1, 31).and_hms(0, 0, 0)));
+
+		invoices.client_payment_terms.entry("default".into()).or_default().insert("3".into(), 15);
+		assert_eq!(invoices.due("other", &job), Some(Utc.ymd(2022, 1, 31).and_hms(0, 0, 0)));
+		assert_eq!(invoices.due("default", &job), Some(Utc.ymd(2022, 1, 16).and_hms(0, 0, 0)));
+
+		let recorded = Utc.ymd(2022, 2, 1).and_hms(0, 0, 0);
+		let mut due_dates = [(1, Some(recorded))].into();
+		assert_eq!(invoices.due_by("default", &job, &due_dates), Some(Utc.ymd(2022, 1, 16).and_hms(0, 0, 0)));
+
+		due_dates.insert(job.id, Some(recorded));
+		assert_eq!(invoices.due_by("default", &job, &due_dates), Some(recorded));
+
+		invoices.payment_terms = None;
+		job.client.id = 4;
+		assert_eq!(invoices.due("default", &job), None);
+		assert_eq!(invoices.due_by("default", &job, &due_dates), Some(recorded));
+
+		job.invoice.date = None;
+		assert_eq!(invoices.due_by("default", &job, &due_dates), None);
+	}
+
+	#[test]
+	fn keys()
+	{
+		let mut invoices: Invoices = toml::from_str(
+			"filename = '{client}--{number}'
+			numbering = 'INV-{year}-{seq:04}'
+			payment_terms = 30
+			template = 'invoice.html.hbs'
+			client_payment_terms.default = { 3 = 15 }
+			client_tax_rates.default = { 3 = 0 }
+			location_tax_rates.default = { 1 = 20 }",
+		)
+		.unwrap();
+
+		invoices.rates.insert(Currency::Usd, Decimal::ONE);
+
+		let serialized = toml::Value::try_from(invoices).unwrap();
+		assert_eq!(serialized.as_table().unwrap().keys().collect::<Vec<_>>(), Invoices::KEYS);
+
+		assert!(toml::from_str::<Invoices>("client_tax_rates.default = { 3 = -1 }").is_err());
+		assert!(toml::from_str::<Invoices>("location_tax_rates.default = { 1 = 101 }").is_err());
+	}
+
+	#[test]
+	fn terms()
+	{
+		let earth = Location { id: 0, name: "Earth".into(), outer: None };
+		let usa = Location { id: 1, name: "USA".into(), outer: Some(earth.into()) };
+		let mut job = Job {
+			client: Organization {
+				id: 3,
+				location: Location { id: 2, name: "Arizona".into(), outer: Some(usa.into()) },
+				name: "Foo".into(),
+			},
+			date_close: None,
+			date_open: Utc.ymd(2022, 1, 1).and_hms(0, 0, 0),
+			id: 7,
+			increment: Duration::from_secs(15 * 60),
+			invoice: Invoice { date: None, hourly_rate: Money::new(20_00, 2, Currency::Usd) },
+			notes: String::new(),
+			objectives: "Write code".into(),
+		};
+
+		let mut invoices = Invoices::default();
+		assert_eq!(invoices.terms("default", &job, None, None, None), Terms::default());
+
+		let location_tax_rates = invoices.location_tax_rates.entry("default".into()).or_default();
+		location_tax_rates.insert("0".into(), Decimal::TEN);
+		location_tax_rates.insert("1".into(), Decimal::new(5, 0));
+		assert_eq!(invoices.tax_rate("default", &job), Some(Decimal::new(5, 0)));
+		assert_eq!(invoices.tax_rate("other", &job), None);
+
+		invoices.client_tax_rates.entry("default".into()).or_default().insert("3".into(), Decimal::ZERO);
+		assert_eq!(invoices.tax_rate("default", &job), Some(Decimal::ZERO));
+
+		job.client.id = 4;
+		job.client.location.outer = None;
+		assert_eq!(invoices.tax_rate("default", &job), None);
+
+		let discount = Some(Discount::Percent(Decimal::TEN));
+		assert_eq!(invoices.terms("default", &job, discount, Some("INV-7"), None), Terms {
+			discount,
+			due: None,
+			number: Some("INV-7".into()),
+			tax: None,
+		});
+	}
+}
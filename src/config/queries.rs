@@ -0,0 +1,153 @@
+use std::collections::BTreeMap;
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_yaml as yaml;
+use toml::Value;
+
+use crate::{
+	fmt,
+	input::{Error, Result},
+};
+
+/// Match conditions (e.g. [`MatchJob`](winvoice_match::MatchJob)) which have been saved under a
+/// name, grouped by the kind of entity they match.
+///
+/// ```toml
+/// [queries.job.open-acme]
+/// client.name.contains = "Acme"
+/// date_close = "none"
+/// ```
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct Queries(BTreeMap<String, BTreeMap<String, Value>>);
+
+impl Queries
+{
+	/// Get the match condition saved as `name`.
+	///
+	/// # Errors
+	///
+	/// When the saved query does not describe a valid `Match`.
+	pub fn get<Match>(&self, name: &str) -> Result<Option<Match>>
+	where
+		Match: DeserializeOwned,
+	{
+		self.0
+			.get(&kind::<Match>())
+			.and_then(|queries| queries.get(name))
+			.map(|query| yaml::from_value(to_yaml(query.clone())).map_err(Error::from))
+			.transpose()
+	}
+
+	/// Save a `condition` as `name`, replacing any other condition of the same kind by that name.
+	///
+	/// # Errors
+	///
+	/// When the `condition` cannot be serialized.
+	pub fn insert<Match>(&mut self, name: &str, condition: &Match) -> Result<()>
+	where
+		Match: Serialize,
+	{
+		let mut value = yaml::to_value(condition).map(to_toml)?.unwrap_or_else(|| Value::Table(Default::default()));
+		prune(&mut value);
+
+		self.0.entry(kind::<Match>()).or_default().insert(name.into(), value);
+		Ok(())
+	}
+
+	/// Whether there are no saved queries.
+	pub fn is_empty(&self) -> bool
+	{
+		self.0.values().all(BTreeMap::is_empty)
+	}
+}
+
+/// The kind of entity matched by a `Match` (e.g. "job" for `MatchJob`), which is used to group
+/// [`Queries`].
+fn kind<Match>() -> String
+{
+	let type_name = fmt::type_name::<Match>();
+	type_name.strip_prefix("Match").unwrap_or(type_name).to_lowercase()
+}
+
+/// Remove the fields of a match condition which match anything (since they are the default), so
+/// that only the meaningful parts are saved.
+fn prune(value: &mut Value)
+{
+	/// The names of the variants of `Match`, `MatchOption`, and `MatchStr`, which contain values
+	/// rather than fields (and should not be pruned).
+	const VARIANTS: [&str; 10] =
+		["and", "contains", "equal_to", "greater_than", "in_range", "less_than", "not", "or", "regex", "some"];
+
+	if let Value::Table(table) = value
+	{
+		table.iter_mut().for_each(|(_, v)| prune(v));
+
+		let matches_anything: Vec<_> = table
+			.iter()
+			.filter(|(k, v)| match v
+			{
+				_ if VARIANTS.contains(&k.as_str()) => false,
+				Value::String(s) => s == "any",
+				Value::Table(t) => t.is_empty(),
+				_ => false,
+			})
+			.map(|(k, _)| k.clone())
+			.collect();
+
+		matches_anything.into_iter().for_each(|k| {
+			table.remove(&k);
+		});
+	}
+}
+
+/// Convert a YAML `value` into TOML, or [`None`] if it is `null` (which TOML cannot represent).
+///
+/// This is used instead of [`Value::try_from`], which does not support `enum`s with fields (e.g.
+/// [`Match::EqualTo`](winvoice_match::Match::EqualTo)).
+fn to_toml(value: yaml::Value) -> Option<Value>
+{
+	Some(match value
+	{
+		yaml::Value::Bool(b) => Value::Boolean(b),
+		yaml::Value::Mapping(m) => Value::Table(
+			m.into_iter()
+				.filter_map(|(k, v)| {
+					let key = match k
+					{
+						yaml::Value::String(s) => s,
+						k => yaml::to_string(&k).ok()?.trim_start_matches("---").trim().to_owned(),
+					};
+
+					to_toml(v).map(|v| (key, v))
+				})
+				.collect(),
+		),
+		yaml::Value::Null => return None,
+		yaml::Value::Number(n) => match n.as_i64()
+		{
+			Some(i) => Value::Integer(i),
+			None => Value::Float(n.as_f64()?),
+		},
+		yaml::Value::Sequence(s) => Value::Array(s.into_iter().filter_map(to_toml).collect()),
+		yaml::Value::String(s) => Value::String(s),
+	})
+}
+
+/// Convert a TOML `value` into YAML, so that it can be deserialized as a match condition.
+fn to_yaml(value: Value) -> yaml::Value
+{
+	match value
+	{
+		Value::Array(a) => yaml::Value::Sequence(a.into_iter().map(to_yaml).collect()),
+		Value::Boolean(b) => yaml::Value::Bool(b),
+		Value::Datetime(d) => yaml::Value::String(d.to_string()),
+		Value::Float(f) => yaml::Value::Number(f.into()),
+		Value::Integer(i) => yaml::Value::Number(i.into()),
+		Value::String(s) => yaml::Value::String(s),
+		Value::Table(t) =>
+		{
+			yaml::Value::Mapping(t.into_iter().map(|(k, v)| (yaml::Value::String(k), to_yaml(v))).collect())
+		},
+	}
+}
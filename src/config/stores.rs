@@ -0,0 +1,130 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use strum::Display;
+use thiserror::Error;
+use toml::{value::Table, Value};
+
+use crate::input::Result;
+
+/// The adapters which a [`Store`] can use.
+#[derive(Clone, Copy, Debug, Deserialize, Display, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum Adapter
+{
+	/// Keep data in a PostgreSQL database.
+	Postgres,
+
+	/// Keep data in a SQLite database (see [`sqlite`](crate::sqlite)).
+	Sqlite,
+}
+
+impl Adapter
+{
+	/// Whether a [`winvoice_config::Store`] can use this [`Adapter`]. If not, the [`Store`] is
+	/// kept in the [`Stores`] instead.
+	const fn is_upstream(self) -> bool
+	{
+		matches!(self, Self::Postgres)
+	}
+}
+
+/// An [`Error`](std::error::Error) indicating that a [`Store`] uses an [`Adapter`] which was not
+/// enabled when Winvoice was built.
+#[derive(Clone, Copy, Debug, Eq, Error, Hash, Ord, PartialEq, PartialOrd)]
+#[error("The {0} adapter was not enabled when Winvoice was built; rebuild it with `--features {0}`")]
+pub struct FeatureNotFound(pub Adapter);
+
+/// Where a store keeps its data, and which [`Adapter`] is used to access it.
+///
+/// ```toml
+/// [stores.default]
+/// adapter = "sqlite"
+/// url = "sqlite:///home/user/winvoice.db?mode=rwc"
+/// ```
+#[derive(Clone, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+pub struct Store
+{
+	/// The [`Adapter`] used to access the store.
+	pub adapter: Adapter,
+
+	/// The URL which the [`Adapter`] connects to.
+	pub url: String,
+}
+
+impl TryFrom<&winvoice_config::Store> for Store
+{
+	type Error = winvoice_config::Error;
+
+	fn try_from(store: &winvoice_config::Store) -> winvoice_config::Result<Self>
+	{
+		let adapter = match store.adapter
+		{
+			winvoice_config::Adapters::Postgres => Adapter::Postgres,
+
+			// NOTE: this is allowed because there may be additional adapters added later.
+			#[allow(unreachable_patterns)]
+			_ => return Err(winvoice_config::Error::FeatureNotFound(store.adapter)),
+		};
+
+		Ok(Self { adapter, url: store.url.clone() })
+	}
+}
+
+/// The `[stores]` which use an [`Adapter`] that [`winvoice_config::Store`] does not know about,
+/// by name.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Stores(BTreeMap<String, Store>);
+
+impl Stores
+{
+	/// The [`Store`] with some `name`.
+	pub fn get(&self, name: &str) -> Option<&Store>
+	{
+		self.0.get(name)
+	}
+
+	/// Insert the [`Stores`] into the `[stores]` of a configuration file's `table`.
+	pub(super) fn insert_into(&self, table: &mut Table) -> Result<()>
+	{
+		if self.0.is_empty()
+		{
+			return Ok(());
+		}
+
+		if let Value::Table(stores) = table.entry("stores").or_insert_with(|| Value::Table(Table::new()))
+		{
+			for (name, store) in &self.0
+			{
+				stores.insert(name.clone(), Value::try_from(store)?);
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Remove the [`Stores`] from the `[stores]` of a configuration file's `table`.
+	pub(super) fn remove_from(table: &mut Table) -> Self
+	{
+		let Some(Value::Table(stores)) = table.get_mut("stores")
+		else
+		{
+			return Self::default();
+		};
+
+		let names = stores
+			.iter()
+			.filter_map(|(name, store)| {
+				Store::deserialize(store.clone()).ok().filter(|s| !s.adapter.is_upstream()).map(|_| name.clone())
+			})
+			.collect::<Vec<_>>();
+
+		Self(
+			names
+				.into_iter()
+				.filter_map(|name| stores.remove(&name).and_then(|s| s.try_into().ok()).map(|s| (name, s)))
+				.collect(),
+		)
+	}
+}
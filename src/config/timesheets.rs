@@ -0,0 +1,27 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use winvoice_schema::Id;
+
+/// Information about `Timesheet`s which allows the punch clock to fill in what the user did last.
+///
+/// ```toml
+/// [timesheets.last_job_id]
+/// default = 3
+/// ```
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Timesheets
+{
+	/// The `id` of the `Job` most recently started, by the name of the store it is in.
+	#[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+	pub last_job_id: BTreeMap<String, Id>,
+}
+
+impl Timesheets
+{
+	/// Whether there is no information about `Timesheet`s.
+	pub fn is_empty(&self) -> bool
+	{
+		self.last_job_id.is_empty()
+	}
+}
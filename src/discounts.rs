@@ -0,0 +1,145 @@
+//! [`Discount`]s on the invoices of specific `Job`s.
+//!
+//! Discounts are kept in the store alongside the `Job`s they apply to (see [`Discounts`]), so that
+//! they follow those `Job`s when they are restored or transferred to another store.
+
+use std::collections::BTreeMap;
+
+use sqlx::{Database, Result};
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+use sqlx::{Executor, Row};
+use winvoice_schema::Id;
+
+use crate::{export::Discount, migration::Migration};
+
+/// The [`Migration`] which creates the table that [`Discounts`] are kept in.
+pub(crate) const MIGRATION: Migration = Migration {
+	description: "Keep the discounts on the invoices of jobs",
+	postgres: &["CREATE TABLE winvoice_discounts (
+		job_id BIGINT NOT NULL PRIMARY KEY REFERENCES jobs(id) ON DELETE CASCADE,
+		discount TEXT NOT NULL
+	)"],
+	sqlite: &["CREATE TABLE winvoice_discounts (
+		job_id INTEGER NOT NULL PRIMARY KEY REFERENCES jobs(id) ON DELETE CASCADE,
+		discount TEXT NOT NULL
+	)"],
+	version: 5,
+};
+
+/// A [`Database`] which keeps the [`Discount`] of each `Job` in the `winvoice_discounts` table (see
+/// [`migration`](crate::migration)).
+///
+/// Each method takes a connection rather than a [`Pool`](sqlx::Pool), so that it can be part of
+/// the same [`Transaction`](sqlx::Transaction) as the changes to the `Job`s which the
+/// [`Discount`]s apply to.
+#[async_trait::async_trait(?Send)]
+pub trait Discounts: Database
+{
+	/// Set the [`Discount`] of the invoice of the `Job` with `job_id`, or remove it if it [is
+	/// zero](Discount::is_zero).
+	async fn create_discount(connection: &mut Self::Connection, job_id: Id, discount: Discount) -> Result<()>;
+
+	/// The [`Discount`]s of the invoices of the `Job`s with `job_ids` which have one, by `id`.
+	async fn retrieve_discounts(connection: &mut Self::Connection, job_ids: &[Id]) -> Result<BTreeMap<Id, Discount>>;
+}
+
+/// Implement [`Discounts`] for some [`Database`].
+macro_rules! impl_discounts {
+	($Db:ty) => {
+		#[async_trait::async_trait(?Send)]
+		impl Discounts for $Db
+		{
+			async fn create_discount(connection: &mut Self::Connection, job_id: Id, discount: Discount) -> Result<()>
+			{
+				let query = match discount.is_zero()
+				{
+					false => sqlx::query(
+						"INSERT INTO winvoice_discounts (job_id, discount) VALUES ($1, $2) ON CONFLICT (job_id) DO \
+						 UPDATE SET discount = excluded.discount",
+					)
+					.bind(job_id)
+					.bind(discount.to_string()),
+					true => sqlx::query("DELETE FROM winvoice_discounts WHERE job_id = $1").bind(job_id),
+				};
+
+				query.execute(connection).await.map(|_| ())
+			}
+
+			async fn retrieve_discounts(
+				connection: &mut Self::Connection,
+				job_ids: &[Id],
+			) -> Result<BTreeMap<Id, Discount>>
+			{
+				if job_ids.is_empty()
+				{
+					return Ok(BTreeMap::new());
+				}
+
+				let ids = job_ids.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(", ");
+				let query = format!("SELECT job_id, discount FROM winvoice_discounts WHERE job_id IN ({ids})");
+
+				connection
+					.fetch_all(query.as_str())
+					.await?
+					.into_iter()
+					.map(|row| {
+						let discount = row
+							.try_get::<String, _>("discount")?
+							.parse()
+							.map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+
+						Ok((row.try_get("job_id")?, discount))
+					})
+					.collect()
+			}
+		}
+	};
+}
+
+#[cfg(feature = "postgres")]
+impl_discounts!(sqlx::Postgres);
+
+#[cfg(feature = "sqlite")]
+impl_discounts!(sqlx::Sqlite);
+
+#[cfg(test)]
+mod tests
+{
+	#[cfg(feature = "sqlite")]
+	#[tokio::test]
+	async fn discounts()
+	{
+		use std::collections::BTreeMap;
+
+		use money2::Decimal;
+		use pretty_assertions::assert_eq;
+		use sqlx::{Connection, Executor, Sqlite, SqliteConnection};
+		use winvoice_schema::{Currency, Money};
+
+		use super::Discounts;
+		use crate::{config::Adapter, export::Discount, migration, sqlite};
+
+		let options = sqlite::connect_options("sqlite::memory:").unwrap();
+		let mut connection = SqliteConnection::connect_with(&options).await.unwrap();
+		connection.execute("CREATE TABLE jobs (id INTEGER NOT NULL PRIMARY KEY)").await.unwrap();
+		connection.execute("INSERT INTO jobs (id) VALUES (3), (4), (5)").await.unwrap();
+		migration::upgrade::<Sqlite>(&mut connection, Adapter::Sqlite).await.unwrap();
+
+		let fixed = Discount::Fixed(Money::new(50_00, 2, Currency::Usd));
+		Sqlite::create_discount(&mut connection, 3, Discount::Percent(Decimal::TEN)).await.unwrap();
+		Sqlite::create_discount(&mut connection, 3, fixed).await.unwrap();
+		Sqlite::create_discount(&mut connection, 4, Discount::Percent(Decimal::ONE)).await.unwrap();
+		Sqlite::create_discount(&mut connection, 5, Discount::Percent(Decimal::ZERO)).await.unwrap();
+		assert!(Sqlite::create_discount(&mut connection, 6, fixed).await.is_err());
+
+		assert_eq!(Sqlite::retrieve_discounts(&mut connection, &[]).await.unwrap(), BTreeMap::new());
+		assert_eq!(
+			Sqlite::retrieve_discounts(&mut connection, &[3, 4, 5]).await.unwrap(),
+			BTreeMap::from([(3, fixed), (4, Discount::Percent(Decimal::ONE))])
+		);
+
+		Sqlite::create_discount(&mut connection, 4, Discount::Percent(Decimal::ZERO)).await.unwrap();
+		connection.execute("DELETE FROM jobs WHERE id = 3").await.unwrap();
+		assert_eq!(Sqlite::retrieve_discounts(&mut connection, &[3, 4]).await.unwrap(), BTreeMap::new());
+	}
+}
@@ -0,0 +1,202 @@
+//! Sources of [`ExchangeRates`] which continue to work without a network connection.
+//!
+//! Rates are written in the CSV format published by the [European Central
+//! Bank](https://www.ecb.europa.eu/stats/eurofxref/eurofxref.zip), where each rate is the number
+//! of units of a currency that are equal to one EUR.
+
+#![allow(clippy::std_instead_of_core)]
+
+use core::result::Result as StdResult;
+use std::{
+	collections::BTreeMap,
+	io,
+	path::{Path, PathBuf},
+};
+
+use money2::{Currency, Decimal, Error as MoneyError, Exchange, ExchangeRates};
+use strum::IntoEnumIterator;
+use thiserror::Error;
+use tokio::fs;
+use winvoice_schema::{chrono::Local, Timesheet};
+
+use crate::export::{Discount, Terms};
+
+/// An [`Error`](std::error::Error) for obtaining [`ExchangeRates`].
+#[derive(Debug, Error)]
+pub enum Error
+{
+	#[allow(missing_docs)]
+	#[error(transparent)]
+	Io(#[from] io::Error),
+
+	#[allow(missing_docs)]
+	#[error("The exchange rate of {0} was not among those fetched")]
+	Missing(Currency),
+
+	#[allow(missing_docs)]
+	#[error("The exchange rates could not be parsed: {0}")]
+	Parse(#[from] MoneyError),
+
+	#[allow(missing_docs)]
+	#[error(
+		"The exchange rates could not be fetched ({0}), and none have been cached. Provide them using --rates, or the \
+		 `rates` field of the `[invoices]` section of the configuration file"
+	)]
+	Unavailable(MoneyError),
+}
+
+/// A [`Result`](StdResult) for obtaining [`ExchangeRates`].
+pub type Result<T> = StdResult<T, Error>;
+
+/// The path to the file which the most recently fetched [`ExchangeRates`] are cached in, or [`None`]
+/// if the platform does not have a cache directory (in which case they are not cached).
+pub fn cache_path() -> Option<PathBuf>
+{
+	dirs::cache_dir().map(|mut path| {
+		path.push("winvoice");
+		path.push("exchange-rates.csv");
+		path
+	})
+}
+
+/// Obtain [`ExchangeRates`] from the first of these which is available:
+///
+/// 1. the file at `filepath`.
+/// 2. the `manual` rates, which are the number of units of each currency equal to one EUR.
+/// 3. the European Central Bank, in which case the rates are written to the [cache](cache_path).
+/// 4. the [cache](cache_path).
+///
+/// # Errors
+///
+/// * If the `filepath` could not be read or parsed.
+/// * If the rates could not be fetched, and there is no cache.
+/// * If the fetched rates are [missing](Error::Missing) a currency.
+pub async fn rates(filepath: Option<&Path>, manual: &BTreeMap<Currency, Decimal>) -> Result<ExchangeRates>
+{
+	if let Some(f) = filepath
+	{
+		return fs::read_to_string(f).await?.parse().map_err(Error::Parse);
+	}
+
+	if !manual.is_empty()
+	{
+		return to_csv(manual).parse().map_err(Error::Parse);
+	}
+
+	let cache = cache_path();
+	match ExchangeRates::new().await
+	{
+		Ok(fetched) =>
+		{
+			let rates = Currency::iter()
+				.filter(|c| *c != Currency::Eur)
+				.map(|c| fetched.get(&c).map(|r| (c, *r)).ok_or(Error::Missing(c)))
+				.collect::<Result<_>>()?;
+
+			// a failure to cache the rates should not prevent them from being used
+			if let Some(c) = cache
+			{
+				if let Some(parent) = c.parent()
+				{
+					fs::create_dir_all(parent).await.ok();
+				}
+
+				fs::write(&c, to_csv(&rates)).await.ok();
+			}
+
+			Ok(fetched)
+		},
+		Err(e) => match cache
+		{
+			Some(c) => fs::read_to_string(&c)
+				.await
+				.map_or_else(|_| Err(Error::Unavailable(e)), |cached| cached.parse().map_err(Error::Parse)),
+			None => Err(Error::Unavailable(e)),
+		},
+	}
+}
+
+/// Exchange the `timesheets` into the `currency`, only obtaining [`rates`] from the `filepath` or
+/// `manual` rates when some amount is in another currency.
+///
+/// # Errors
+///
+/// See [`rates`].
+pub async fn timesheets(
+	timesheets: Vec<Timesheet>,
+	currency: Currency,
+	filepath: Option<&Path>,
+	manual: &BTreeMap<Currency, Decimal>,
+) -> Result<Vec<Timesheet>>
+{
+	if timesheets.iter().all(|t| {
+		t.job.invoice.hourly_rate.currency == currency && t.expenses.iter().all(|x| x.cost.currency == currency)
+	})
+	{
+		return Ok(timesheets);
+	}
+
+	let exchange_rates = rates(filepath, manual).await?;
+	Ok(timesheets.into_iter().map(|t| t.exchange(currency, &exchange_rates)).collect())
+}
+
+/// Exchange the [`Discount`](crate::export::Discount) of the `terms` into the `currency`, only
+/// obtaining [`rates`] from the `filepath` or `manual` rates when it is a fixed amount in another
+/// currency.
+///
+/// # Errors
+///
+/// See [`rates`].
+pub async fn terms(
+	terms: Terms,
+	currency: Currency,
+	filepath: Option<&Path>,
+	manual: &BTreeMap<Currency, Decimal>,
+) -> Result<Terms>
+{
+	match terms.discount
+	{
+		Some(Discount::Fixed(m)) if m.currency != currency =>
+		{
+			let exchange_rates = rates(filepath, manual).await?;
+			Ok(terms.exchange(currency, &exchange_rates))
+		},
+		_ => Ok(terms),
+	}
+}
+
+/// Write the `rates` in the CSV format of the European Central Bank.
+fn to_csv(rates: &BTreeMap<Currency, Decimal>) -> String
+{
+	let (currencies, values): (Vec<_>, Vec<_>) =
+		rates.iter().filter(|(c, _)| **c != Currency::Eur).map(|(c, r)| (c.to_string(), r.to_string())).unzip();
+
+	format!("Date, {}\n{}, {}\n", currencies.join(", "), Local::now().format("%d %B %Y"), values.join(", "))
+}
+
+#[cfg(test)]
+mod tests
+{
+	use std::collections::BTreeMap;
+
+	use money2::{Currency, Decimal};
+
+	#[test]
+	fn to_csv()
+	{
+		let rates: BTreeMap<_, _> = [
+			(Currency::Eur, Decimal::ONE),
+			(Currency::Jpy, Decimal::new(1605, 1)),
+			(Currency::Usd, Decimal::new(1_08, 2)),
+		]
+		.into_iter()
+		.collect();
+
+		let csv = super::to_csv(&rates);
+		let mut lines = csv.lines();
+
+		assert_eq!(lines.next(), Some("Date, JPY, USD"));
+		assert!(lines.next().unwrap().ends_with(", 160.5, 1.08"));
+		assert_eq!(lines.next(), None);
+	}
+}
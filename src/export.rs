@@ -0,0 +1,164 @@
+//! Formats which `Job`s (and `Timesheet`s) can be exported to, in addition to those provided by
+//! [`winvoice_export`].
+
+mod filename;
+mod format;
+mod html;
+mod pdf;
+mod template;
+mod terms;
+mod timesheet_format;
+mod ubl;
+
+use std::{
+	io,
+	path::{Path, PathBuf},
+};
+
+pub use filename::Filename;
+pub use format::Format;
+use money2::Decimal;
+pub use template::Template;
+pub use terms::{Charges, Discount, Terms};
+use thiserror::Error;
+pub use timesheet_format::TimesheetFormat;
+use tokio::fs::{self, OpenOptions};
+use winvoice_schema::{
+	chrono::{DateTime, Local, Utc},
+	Job,
+	Money,
+	Timesheet,
+};
+
+use crate::{utils::Billable, DynResult};
+
+/// An [`Error`](std::error::Error) indicating that exporting would overwrite an existing file.
+#[derive(Clone, Debug, Eq, Error, Hash, Ord, PartialEq, PartialOrd)]
+#[error("{} already exists, and will not be overwritten without --force", .0.display())]
+pub struct Exists(pub PathBuf);
+
+/// An [`Error`](std::error::Error) indicating that more than one document would be exported to the
+/// same file.
+#[derive(Clone, Copy, Debug, Eq, Error, Hash, Ord, PartialEq, PartialOrd)]
+#[error(
+	"{0} invoices would be exported to --output, which only holds one. Select the Jobs of one client and --combine \
+	 them, or use --output-dir instead"
+)]
+pub struct TooMany(pub usize);
+
+/// One `Job` on an invoice, which may list several `Job`s for the same client.
+#[derive(Clone, Copy, Debug)]
+pub struct Section<'job>
+{
+	/// The `Job` being invoiced.
+	pub job: &'job Job,
+
+	/// The [`Terms`] of the invoice of the `job`.
+	pub terms: &'job Terms,
+
+	/// The `Timesheet`s of the `job`.
+	pub timesheets: &'job [Timesheet],
+}
+
+impl Section<'_>
+{
+	/// The [`Charges`] for the `timesheets` of this [`Section`].
+	pub fn charges(&self) -> Charges
+	{
+		self.terms.charges(sum(self.job, self.timesheets.iter().map(Billable::total)))
+	}
+}
+
+/// Display a `date` in the [`Local`] timezone.
+fn date(d: DateTime<Utc>) -> String
+{
+	d.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string()
+}
+
+/// The sum of some `amounts`, which are in the same currency as the hourly rate of the `job`.
+fn sum<I>(job: &Job, amounts: I) -> Money
+where
+	I: IntoIterator<Item = Money>,
+{
+	amounts.into_iter().fold(Money { amount: Decimal::ZERO, ..job.invoice.hourly_rate }, |sum, m| Money {
+		amount: sum.amount + m.amount,
+		..sum
+	})
+}
+
+/// The title of an invoice with some `sections`, which lists the
+/// [reference](Terms::reference) of each.
+fn title(sections: &[Section<'_>]) -> String
+{
+	match sections
+	{
+		[s] => s.terms.title(s.job),
+		_ => format!("Invoice {}", sections.iter().map(|s| s.terms.reference(s.job)).collect::<Vec<_>>().join(", ")),
+	}
+}
+
+/// The combined [`Charges`] of an invoice with multiple `sections`, which are in the same
+/// currency.
+///
+/// Returns [`None`] unless there are multiple `sections`.
+fn total(sections: &[Section<'_>]) -> Option<Charges>
+{
+	match sections.len() > 1
+	{
+		false => None,
+		true => sections.iter().map(Section::charges).reduce(|lhs, rhs| lhs + rhs),
+	}
+}
+
+/// Write what was `exported` to the file at `path`, which is only overwritten if it exists and
+/// `force` is specified.
+///
+/// # Errors
+///
+/// * [`Exists`], if the file exists and `force` is not specified.
+/// * If the file could not be written.
+pub async fn write(path: &Path, exported: &[u8], force: bool) -> DynResult<()>
+{
+	if !force
+	{
+		// NOTE: creating the file first ensures that it did not exist, even if another was written to
+		//       the same `path` concurrently.
+		match OpenOptions::new().create_new(true).write(true).open(path).await
+		{
+			Err(e) if e.kind() == io::ErrorKind::AlreadyExists => return Err(Exists(path.to_owned()).into()),
+			result => result?,
+		};
+	}
+
+	fs::write(path, exported).await?;
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests
+{
+	use std::fs;
+
+	use super::Exists;
+	use crate::utils;
+
+	#[tokio::test]
+	async fn write()
+	{
+		let filepath = utils::temp_file::<Exists>("write");
+		if filepath.is_file()
+		{
+			fs::remove_file(&filepath).unwrap();
+		}
+
+		super::write(&filepath, b"foo", false).await.unwrap();
+		assert_eq!(
+			super::write(&filepath, b"bar", false).await.unwrap_err().to_string(),
+			Exists(filepath.clone()).to_string()
+		);
+		assert_eq!(fs::read(&filepath).unwrap(), b"foo");
+
+		super::write(&filepath, b"bar", true).await.unwrap();
+		assert_eq!(fs::read(&filepath).unwrap(), b"bar");
+	}
+}
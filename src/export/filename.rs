@@ -0,0 +1,175 @@
+use core::{
+	fmt::{Display, Formatter, Result as FmtResult},
+	str::FromStr,
+};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use winvoice_schema::chrono::Local;
+
+use super::Section;
+
+/// The placeholders which a [`Filename`] can use.
+const PLACEHOLDERS: [&str; 4] = ["client", "date", "id", "number"];
+
+/// An [`Error`](std::error::Error) for parsing a [`Filename`].
+#[derive(Clone, Debug, Eq, Error, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Error
+{
+	#[allow(missing_docs)]
+	#[error("The filename template has an unclosed {{")]
+	Unclosed,
+
+	#[allow(missing_docs)]
+	#[error("{{{0}}} is not a placeholder that filename templates can use")]
+	UnknownPlaceholder(String),
+}
+
+/// A template for the names of exported invoices (without their extension), such as
+/// `{client}--{number}`.
+///
+/// The placeholders are the name of the `{client}`, the `{id}` of the `Job`, the `{date}` that the
+/// invoice was issued, and the `{number}` of the invoice (or the `{id}` if it does not have one).
+/// Invoices which combine several `Job`s list the `{id}` and `{number}` of each.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(into = "String", try_from = "String")]
+pub struct Filename(String);
+
+impl Filename
+{
+	/// The name of the file which the `sections` of an invoice are exported to, with some
+	/// `extension`.
+	pub fn render(&self, sections: &[Section<'_>], extension: &str) -> String
+	{
+		let join = |f: &dyn Fn(&Section<'_>) -> String| sections.iter().map(f).collect::<Vec<_>>().join("+");
+		let date = sections
+			.iter()
+			.filter_map(|s| s.job.invoice.date.map(|d| d.issued))
+			.max()
+			.map(|d| d.with_timezone(&Local).format("%Y-%m-%d").to_string())
+			.unwrap_or_default();
+
+		let name = self
+			.0
+			.replace("{client}", &sections.first().map(|s| s.job.client.name.replace(' ', "-")).unwrap_or_default())
+			.replace("{date}", &date)
+			.replace("{id}", &join(&|s| s.job.id.to_string()))
+			.replace("{number}", &join(&|s| s.terms.number.clone().unwrap_or_else(|| s.job.id.to_string())));
+
+		format!("{}.{extension}", name.replace(['/', '\\'], "-"))
+	}
+}
+
+impl Default for Filename
+{
+	fn default() -> Self
+	{
+		Self("{client}--{number}".into())
+	}
+}
+
+impl Display for Filename
+{
+	fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult
+	{
+		self.0.fmt(f)
+	}
+}
+
+impl From<Filename> for String
+{
+	fn from(filename: Filename) -> Self
+	{
+		filename.0
+	}
+}
+
+impl FromStr for Filename
+{
+	type Err = Error;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err>
+	{
+		s.split('{').skip(1).try_for_each(|rest| {
+			let placeholder = rest.split_once('}').map(|(p, _)| p).ok_or(Error::Unclosed)?;
+			match PLACEHOLDERS.contains(&placeholder)
+			{
+				false => Err(Error::UnknownPlaceholder(placeholder.into())),
+				true => Ok(()),
+			}
+		})?;
+
+		Ok(Self(s.into()))
+	}
+}
+
+impl TryFrom<String> for Filename
+{
+	type Error = Error;
+
+	fn try_from(s: String) -> Result<Self, Self::Error>
+	{
+		s.parse()
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use core::time::Duration;
+
+	use pretty_assertions::assert_eq;
+	use winvoice_schema::{
+		chrono::{Local, TimeZone, Utc},
+		Currency,
+		Invoice,
+		InvoiceDate,
+		Job,
+		Location,
+		Money,
+		Organization,
+	};
+
+	use super::{Error, Filename};
+	use crate::export::{Section, Terms};
+
+	#[test]
+	fn render()
+	{
+		let job = Job {
+			client: Organization {
+				id: 1,
+				location: Location { id: 0, name: "Earth".into(), outer: None },
+				name: "Bar and Sons".into(),
+			},
+			date_close: None,
+			date_open: Utc.ymd(2022, 1, 1).and_hms(0, 0, 0),
+			id: 3,
+			increment: Duration::from_secs(15 * 60),
+			invoice: Invoice {
+				date: Some(InvoiceDate { issued: Local.ymd(2022, 2, 3).and_hms(12, 0, 0).into(), paid: None }),
+				hourly_rate: Money::new(20_00, 2, Currency::Usd),
+			},
+			notes: String::new(),
+			objectives: "Write code".into(),
+		};
+
+		let mut other = job.clone();
+		other.id = 4;
+
+		let terms = Terms::default();
+		let numbered = Terms { number: Some("INV/2022/0001".into()), ..Default::default() };
+		let section = Section { job: &job, terms: &terms, timesheets: &[] };
+
+		assert_eq!(Filename::default().render(&[section], "pdf"), "Bar-and-Sons--3.pdf");
+		assert_eq!(
+			Filename::default().render(&[section, Section { job: &other, terms: &numbered, timesheets: &[] }], "pdf"),
+			"Bar-and-Sons--3+INV-2022-0001.pdf"
+		);
+
+		let filename: Filename = "{date}_{client}_{id}".parse().unwrap();
+		assert_eq!(filename.render(&[section], "html"), "2022-02-03_Bar-and-Sons_3.html");
+		assert_eq!("{client}--{job}".parse::<Filename>(), Err(Error::UnknownPlaceholder("job".into())));
+		assert_eq!("{client".parse::<Filename>(), Err(Error::Unclosed));
+	}
+}
@@ -0,0 +1,216 @@
+use clap::ValueEnum;
+use winvoice_config::Error as ConfigError;
+use winvoice_export::Format as MarkdownFormat;
+use winvoice_schema::{Contact, Organization};
+
+use super::{html, pdf, ubl, Charges, Section, Template, Terms};
+use crate::{fmt, DynResult};
+
+/// A format which `Job`s can be exported to.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, ValueEnum)]
+pub enum Format
+{
+	/// A self-contained HTML document, which can be printed from a web browser.
+	Html,
+
+	/// A Markdown document.
+	Markdown,
+
+	/// A paginated PDF document.
+	///
+	/// Only the characters of Latin-1 (and a few others, such as `€`) can be shown; any others are
+	/// written as `?`, with a warning.
+	Pdf,
+
+	/// A user-supplied [`Template`].
+	Template,
+
+	/// A UBL 2.1 invoice, which is an XML document that e-invoicing systems can process.
+	Ubl,
+}
+
+impl Format
+{
+	/// The extension of files in this [`Format`].
+	///
+	/// The extension of a [`Format::Template`] is that of the `template`, or "txt" if there is
+	/// none.
+	pub fn extension(self, template: Option<&Template>) -> &str
+	{
+		match self
+		{
+			Self::Html => "html",
+			Self::Markdown => MarkdownFormat::Markdown.extension(),
+			Self::Pdf => "pdf",
+			Self::Template => template.map_or("txt", Template::extension),
+			Self::Ubl => "xml",
+		}
+	}
+
+	/// Export the `sections` of an invoice from the `employer` to the `client`, listing the
+	/// `contact_info` of the `employer` and the `timesheets` of each `Job`. The subtotal, discount,
+	/// tax, and total of each `Job` are listed according to its `terms`, as is the date that
+	/// unpaid invoices are due by.
+	///
+	/// When there are multiple `sections`, the invoice ends with a summary of what each `Job`
+	/// costs, and the total of the whole invoice.
+	///
+	/// # Errors
+	///
+	/// * If this is a [`Format::Template`], and there is no `template`.
+	/// * If the `template` could not be rendered.
+	pub fn export(
+		self,
+		template: Option<&Template>,
+		client: &Organization,
+		sections: &[Section<'_>],
+		contact_info: &[Contact],
+		employer: &Organization,
+	) -> DynResult<Vec<u8>>
+	{
+		Ok(match self
+		{
+			Self::Html => html::export(client, sections, contact_info, employer).into_bytes(),
+			Self::Markdown =>
+			{
+				let mut exported = sections
+					.iter()
+					.map(|s| {
+						let mut exported = markdown_header(
+							s,
+							MarkdownFormat::Markdown.export_job(s.job, contact_info, employer, s.timesheets),
+						);
+
+						if s.terms.adjusts()
+						{
+							exported.push_str(&markdown_charges(s.terms, &s.charges()));
+						}
+
+						exported
+					})
+					.collect::<Vec<_>>()
+					.join("\n---\n\n");
+
+				if let Some(total) = super::total(sections)
+				{
+					exported.push_str(&markdown_summary(sections, &total));
+				}
+
+				exported.into_bytes()
+			},
+			Self::Pdf => pdf::export(client, sections, contact_info, employer),
+			Self::Template => template
+				.ok_or_else(|| ConfigError::NotConfigured("template".into(), "invoices".into()))?
+				.render(client, sections, contact_info, employer)?
+				.into_bytes(),
+			Self::Ubl => ubl::export(client, sections, contact_info, employer).into_bytes(),
+		})
+	}
+}
+
+/// A Markdown table of the `charges` of an invoice with some `terms`, since they are not part of
+/// what [`winvoice_export`] exports.
+fn markdown_charges(terms: &Terms, charges: &Charges) -> String
+{
+	let rows: String = [
+		Some(("Subtotal".to_owned(), charges.subtotal.to_string())),
+		terms.discount.map(|d| (format!("Discount ({d})"), format!("-{}", charges.discount))),
+		terms.tax.map(|t| (format!("Tax ({t}%)"), charges.tax.to_string())),
+		Some(("**Total**".to_owned(), format!("**{}**", charges.total))),
+	]
+	.into_iter()
+	.flatten()
+	.map(|(key, value)| format!("| {key} | {value} |\n"))
+	.collect();
+
+	format!("\n| | |\n|:--|--:|\n{rows}")
+}
+
+/// Add the date that the invoice of a `section` is due by (if it is unpaid and there are payment
+/// terms) below the heading of what [`winvoice_export`] `exported`, since it is not part of it.
+fn markdown_header(section: &Section<'_>, exported: String) -> String
+{
+	let due = section.job.invoice.date.filter(|d| d.paid.is_none()).and(section.terms.due);
+	match due.zip(exported.split_once('\n'))
+	{
+		Some((d, (heading, rest))) => format!("{heading}\n\n**Due by:** {}\n{rest}", super::date(d)),
+		None => exported,
+	}
+}
+
+/// A Markdown table of the `total` of each of the `sections` of a combined invoice, and the `total`
+/// of the invoice itself.
+fn markdown_summary(sections: &[Section<'_>], total: &Charges) -> String
+{
+	let rows: String = sections
+		.iter()
+		.map(|s| {
+			let job = s
+				.terms
+				.number
+				.as_ref()
+				.map_or_else(|| fmt::id_num(s.job.id), |n| format!("{} ({n})", fmt::id_num(s.job.id)));
+			format!("| {job} | {} |\n", s.charges().total)
+		})
+		.collect();
+
+	format!("\n## Summary\n\n| Job | Amount |\n|:--|--:|\n{rows}| **Total** | **{}** |\n", total.total)
+}
+
+#[cfg(test)]
+mod tests
+{
+	use core::time::Duration;
+
+	use pretty_assertions::assert_eq;
+	use winvoice_schema::{
+		chrono::{TimeZone, Utc},
+		Currency,
+		Invoice,
+		InvoiceDate,
+		Job,
+		Location,
+		Money,
+		Organization,
+	};
+
+	use crate::export::{Section, Terms};
+
+	#[test]
+	fn markdown_header()
+	{
+		let due = Utc.ymd(2022, 2, 1).and_hms(12, 0, 0);
+		let mut job = Job {
+			client: Organization {
+				id: 0,
+				location: Location { id: 0, name: "Earth".into(), outer: None },
+				name: "Foo".into(),
+			},
+			date_close: None,
+			date_open: Utc.ymd(2022, 1, 1).and_hms(0, 0, 0),
+			id: 0,
+			increment: Duration::from_secs(15 * 60),
+			invoice: Invoice {
+				date: Some(InvoiceDate { issued: Utc.ymd(2022, 1, 2).and_hms(12, 0, 0), paid: None }),
+				hourly_rate: Money::new(20_00, 2, Currency::Usd),
+			},
+			notes: String::new(),
+			objectives: "Write code".into(),
+		};
+
+		let exported = "# Job №1\n\nWrite code\n";
+		let terms = Terms { due: Some(due), ..Default::default() };
+		let header = |job: &Job, terms: &Terms| {
+			super::markdown_header(&Section { job, terms, timesheets: &[] }, exported.into())
+		};
+
+		assert_eq!(
+			header(&job, &terms),
+			format!("# Job №1\n\n**Due by:** {}\n\nWrite code\n", crate::export::date(due))
+		);
+		assert_eq!(header(&job, &Terms::default()), exported);
+
+		job.invoice.date = job.invoice.date.map(|d| InvoiceDate { paid: Some(due), ..d });
+		assert_eq!(header(&job, &terms), exported);
+	}
+}
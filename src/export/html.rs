@@ -0,0 +1,389 @@
+use core::fmt::Display;
+
+use winvoice_schema::{Contact, ContactKind, Organization, Timesheet};
+
+use super::{date, Charges, Section};
+use crate::{fmt, utils::Billable};
+
+/// The stylesheet which is embedded in every document, so that it can be printed without any
+/// other files.
+const STYLE: &str = r#"
+@page { margin: 2cm; }
+* { box-sizing: border-box; }
+body { color: #222; font: 11pt/1.4 "Helvetica Neue", Helvetica, Arial, sans-serif; margin: 0 auto; max-width: 21cm; padding: 1cm; }
+@media print { body { max-width: none; padding: 0; } }
+a { color: inherit; }
+dd { margin: 0; }
+dl { display: grid; gap: .25em 1em; grid-template-columns: max-content auto; margin: 0; }
+dt { font-weight: bold; }
+h1 { font-size: 2em; margin: 0; }
+h2 { border-bottom: 1px solid #ccc; font-size: 1.2em; margin: 1.5em 0 .5em; }
+h3 { font-size: 1em; margin: 1em 0 .25em; }
+header { align-items: baseline; display: flex; justify-content: space-between; }
+table { border-collapse: collapse; width: 100%; }
+tfoot td { border-bottom: none; font-weight: bold; }
+th, td { border-bottom: 1px solid #ddd; padding: .4em; text-align: left; vertical-align: top; }
+thead { display: table-header-group; }
+tr { break-inside: avoid; }
+.expenses { margin: .25em 0 0; padding-left: 1.2em; white-space: normal; }
+.name { font-size: 1.1em; font-weight: bold; margin: 0; }
+.number { text-align: right; white-space: nowrap; }
+.parties { display: flex; gap: 2em; }
+.parties > div { flex: 1; }
+.text { white-space: pre-wrap; }
+"#;
+
+/// Display a `Contact`'s information as an entry in a description list.
+fn contact(c: &Contact) -> String
+{
+	let value = match &c.kind
+	{
+		ContactKind::Address(location) => escape(location),
+		ContactKind::Email(email) => format!(r#"<a href="mailto:{0}">{0}</a>"#, escape(email)),
+		ContactKind::Other(other) => escape(other),
+		ContactKind::Phone(phone) => format!(r#"<a href="tel:{0}">{0}</a>"#, escape(phone)),
+	};
+
+	format!("<dt>{}</dt><dd>{value}</dd>", escape(&c.label))
+}
+
+/// Escape the characters of `t` which have special meaning in HTML (and XML).
+pub(super) fn escape<T>(t: T) -> String
+where
+	T: Display,
+{
+	t.to_string().chars().fold(String::new(), |mut escaped, c| {
+		match c
+		{
+			'"' => escaped.push_str("&quot;"),
+			'&' => escaped.push_str("&amp;"),
+			'\'' => escaped.push_str("&#39;"),
+			'<' => escaped.push_str("&lt;"),
+			'>' => escaped.push_str("&gt;"),
+			c => escaped.push(c),
+		};
+
+		escaped
+	})
+}
+
+/// Export the `sections` of an invoice to the `client` as a self-contained HTML document.
+///
+/// See [`Format::export`](super::Format::export) for more information.
+pub(super) fn export(
+	client: &Organization,
+	sections: &[Section<'_>],
+	contact_info: &[Contact],
+	employer: &Organization,
+) -> String
+{
+	let title = super::title(sections);
+	let (invoice_dates, summary) = match (sections, super::total(sections))
+	{
+		([s], _) => (self::invoice_dates(s), String::new()),
+		(_, Some(total)) => (String::new(), self::summary(sections, &total)),
+		_ => Default::default(),
+	};
+
+	format!(
+		r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>{title}</title>
+<style>{STYLE}</style>
+</head>
+<body>
+<header>
+<h1>{title}</h1>
+<dl>{invoice_dates}</dl>
+</header>
+<section class="parties">
+<div>
+<h2>From</h2>
+<p class="name">{employer_name}</p>
+<p>{employer_location}</p>
+<dl>{contact_info}</dl>
+</div>
+<div>
+<h2>Bill To</h2>
+<p class="name">{client_name}</p>
+<p>{client_location}</p>
+</div>
+</section>
+{sections}{summary}</body>
+</html>
+"#,
+		client_location = escape(&client.location),
+		client_name = escape(&client.name),
+		contact_info = contact_info.iter().map(contact).collect::<String>(),
+		employer_location = escape(&employer.location),
+		employer_name = escape(&employer.name),
+		sections = sections.iter().map(|s| section(s, sections.len() > 1)).collect::<String>(),
+	)
+}
+
+/// Display the date that the invoice of a `section` was issued, and when it was paid (or is due),
+/// as entries in a description list.
+fn invoice_dates(section: &Section<'_>) -> String
+{
+	section.job.invoice.date.map_or_else(String::new, |d| {
+		let paid = d.paid.map_or_else(
+			|| section.terms.due.map_or_else(|| "<dd>Due</dd>".into(), |u| format!("<dd>Due by {}</dd>", date(u))),
+			|p| format!("<dd>Paid {}</dd>", date(p)),
+		);
+		format!("<dt>Issued</dt><dd>{}</dd><dt>Status</dt>{paid}", date(d.issued))
+	})
+}
+
+/// Display a `section` of an invoice, which is `combined` with others if it lists its own
+/// reference and invoice dates.
+fn section(section: &Section<'_>, combined: bool) -> String
+{
+	let Section { job, terms, timesheets } = section;
+	let invoice = match combined
+	{
+		false => String::new(),
+		true => format!(
+			"{}{}\n",
+			terms.number.as_ref().map_or_else(String::new, |n| format!("<dt>Invoice</dt><dd>{}</dd>", escape(n))),
+			invoice_dates(section),
+		),
+	};
+
+	let date_close = job.date_close.map_or_else(String::new, |d| format!("<dt>Closed</dt><dd>{}</dd>", date(d)));
+	let notes = match job.notes.is_empty()
+	{
+		false => format!(r#"<h3>Notes</h3><p class="text">{}</p>"#, escape(&job.notes)),
+		true => String::new(),
+	};
+
+	let charges = section.charges();
+	let totals = match terms.adjusts()
+	{
+		false => Vec::new(),
+		true => [
+			Some(("Subtotal".to_owned(), charges.subtotal.to_string())),
+			terms.discount.map(|d| (format!("Discount ({d})"), format!("-{}", charges.discount))),
+			terms.tax.map(|t| (format!("Tax ({t}%)"), charges.tax.to_string())),
+		]
+		.into_iter()
+		.flatten()
+		.map(|(key, value)| {
+			format!(r#"<tr><td colspan="5">{}</td><td class="number">{}</td></tr>"#, escape(key), escape(value))
+		})
+		.collect(),
+	};
+
+	format!(
+		r#"<section>
+<h2>Job {job_id}</h2>
+<dl>
+{invoice}<dt>Opened</dt><dd>{date_open}</dd>{date_close}
+<dt>Hourly Rate</dt><dd>{hourly_rate}</dd>
+<dt>Increment</dt><dd>{increment}</dd>
+</dl>
+<h3>Objectives</h3>
+<p class="text">{objectives}</p>
+{notes}
+</section>
+<section>
+<h2>Timesheets</h2>
+<table>
+<thead>
+<tr><th>Employee</th><th>Began</th><th>Ended</th><th class="number">Hours</th><th>Work Notes</th><th class="number">Amount</th></tr>
+</thead>
+<tbody>
+{timesheets}
+</tbody>
+<tfoot>
+{totals}<tr><td colspan="5">Total</td><td class="number">{total}</td></tr>
+</tfoot>
+</table>
+</section>
+"#,
+		date_open = date(job.date_open),
+		hourly_rate = escape(job.invoice.hourly_rate),
+		increment = humantime::format_duration(job.increment),
+		job_id = fmt::id_num(job.id),
+		objectives = escape(&job.objectives),
+		timesheets = timesheets.iter().map(timesheet).collect::<Vec<_>>().join("\n"),
+		total = escape(charges.total),
+		totals = totals.iter().map(|row| format!("{row}\n")).collect::<String>(),
+	)
+}
+
+/// Display the `total` of each of the `sections` of a combined invoice, and the `total` of the
+/// invoice itself.
+fn summary(sections: &[Section<'_>], total: &Charges) -> String
+{
+	format!(
+		r#"<section>
+<h2>Summary</h2>
+<table>
+<thead>
+<tr><th>Job</th><th class="number">Amount</th></tr>
+</thead>
+<tbody>
+{rows}
+</tbody>
+<tfoot>
+<tr><td>Total</td><td class="number">{total}</td></tr>
+</tfoot>
+</table>
+</section>
+"#,
+		rows = sections
+			.iter()
+			.map(|s| {
+				let job = s
+					.terms
+					.number
+					.as_ref()
+					.map_or_else(|| fmt::id_num(s.job.id), |n| format!("{} ({})", fmt::id_num(s.job.id), escape(n)));
+				format!(r#"<tr><td>{job}</td><td class="number">{}</td></tr>"#, escape(s.charges().total))
+			})
+			.collect::<Vec<_>>()
+			.join("\n"),
+		total = escape(total.total),
+	)
+}
+
+/// Display a `Timesheet` as a row of a table.
+fn timesheet(t: &Timesheet) -> String
+{
+	let expenses = match t.expenses.is_empty()
+	{
+		false => format!(
+			r#"<ul class="expenses">{}</ul>"#,
+			t.expenses
+				.iter()
+				.map(|x| format!("<li>{} ({}): {}</li>", escape(&x.category), escape(x.cost), escape(&x.description)))
+				.collect::<String>(),
+		),
+		true => String::new(),
+	};
+
+	format!(
+		r#"<tr><td>{}</td><td>{}</td><td>{}</td><td class="number">{:.2}</td><td class="text">{}{expenses}</td><td class="number">{}</td></tr>"#,
+		escape(&t.employee.name),
+		date(t.time_begin),
+		t.time_end.map(date).unwrap_or_default(),
+		t.hours(),
+		escape(&t.work_notes),
+		escape(t.total()),
+	)
+}
+
+#[cfg(test)]
+mod tests
+{
+	use core::time::Duration;
+
+	use money2::Decimal;
+	use winvoice_schema::{
+		chrono::{TimeZone, Utc},
+		Contact,
+		ContactKind,
+		Currency,
+		Employee,
+		Expense,
+		Invoice,
+		InvoiceDate,
+		Job,
+		Location,
+		Money,
+		Organization,
+		Timesheet,
+	};
+
+	use crate::export::{Discount, Section, Terms};
+
+	#[test]
+	fn export()
+	{
+		let location = Location { id: 0, name: "Earth".into(), outer: None };
+		let employer = Organization { id: 0, location: location.clone(), name: "Foo & Sons".into() };
+		let job = Job {
+			client: Organization { id: 1, location, name: "<Bar>".into() },
+			date_close: Some(Utc.ymd(2022, 1, 2).and_hms(0, 0, 0)),
+			date_open: Utc.ymd(2022, 1, 1).and_hms(0, 0, 0),
+			id: 3,
+			increment: Duration::from_secs(15 * 60),
+			invoice: Invoice { date: None, hourly_rate: Money::new(20_00, 2, Currency::Usd) },
+			notes: String::new(),
+			objectives: "Write \"code\"".into(),
+		};
+
+		let timesheet = Timesheet {
+			id: 0,
+			employee: Employee { id: 0, name: "Bob".into(), status: "Contractor".into(), title: "Dev".into() },
+			expenses: vec![Expense {
+				id: 0,
+				category: "Food".into(),
+				cost: Money::new(5_00, 2, Currency::Usd),
+				description: "Lunch".into(),
+				timesheet_id: 0,
+			}],
+			job: job.clone(),
+			time_begin: Utc.ymd(2022, 1, 1).and_hms(9, 0, 0),
+			time_end: Some(Utc.ymd(2022, 1, 1).and_hms(10, 20, 0)),
+			work_notes: "Fixed <bugs>".into(),
+		};
+
+		let contact_info = [Contact { kind: ContactKind::Email("foo@bar.io".into()), label: "Email".into() }];
+		let timesheets = [timesheet.clone(), timesheet];
+		let terms = Terms::default();
+		let section = Section { job: &job, terms: &terms, timesheets: &timesheets };
+		let exported = super::export(&job.client, &[section], &contact_info, &employer);
+
+		assert!(exported.starts_with("<!DOCTYPE html>"));
+		assert!(exported.contains("<title>Invoice №3</title>"));
+		assert!(exported.contains("Foo &amp; Sons"));
+		assert!(exported.contains("&lt;Bar&gt;"));
+		assert!(exported.contains("Write &quot;code&quot;"));
+		assert!(exported.contains("Fixed &lt;bugs&gt;"));
+		assert!(exported.contains(r#"<a href="mailto:foo@bar.io">foo@bar.io</a>"#));
+		assert!(!exported.contains("<h3>Notes</h3>"));
+
+		// 1h20m rounds up to 1.5h at 20.00 USD/h, plus 5.00 USD of expenses, twice.
+		assert!(exported.contains(r#"<td class="number">1.50</td>"#));
+		assert!(exported.contains(r#"<td class="number">35.00 USD</td>"#));
+		assert!(exported.contains(r#"<td colspan="5">Total</td><td class="number">70.00 USD</td>"#));
+		assert!(!exported.contains("Subtotal"));
+		assert!(!exported.contains("<h2>Summary</h2>"));
+
+		let mut other = job.clone();
+		other.id = 4;
+		let other_terms = Terms { number: Some("INV-2022-0002".into()), ..Default::default() };
+		let sections = [section, Section { job: &other, terms: &other_terms, timesheets: &timesheets[..1] }];
+		let exported = super::export(&job.client, &sections, &contact_info, &employer);
+		assert!(exported.contains("<title>Invoice №3, INV-2022-0002</title>"));
+		assert!(exported.contains("<h2>Job №4</h2>"));
+		assert!(exported.contains("<dt>Invoice</dt><dd>INV-2022-0002</dd>"));
+		assert!(exported.contains(r#"<tr><td>№4 (INV-2022-0002)</td><td class="number">35.00 USD</td></tr>"#));
+		assert!(exported.contains(r#"<tr><td>Total</td><td class="number">105.00 USD</td></tr>"#));
+
+		let terms = Terms {
+			discount: Some(Discount::Percent(Decimal::TEN)),
+			tax: Some(Decimal::new(5, 0)),
+			..Default::default()
+		};
+		let section = Section { job: &job, terms: &terms, timesheets: &timesheets };
+		let exported = super::export(&job.client, &[section], &contact_info, &employer);
+		assert!(exported.contains(r#"<td colspan="5">Subtotal</td><td class="number">70.00 USD</td>"#));
+		assert!(exported.contains(r#"<td colspan="5">Discount (10%)</td><td class="number">-7.00 USD</td>"#));
+		assert!(exported.contains(r#"<td colspan="5">Tax (5%)</td><td class="number">3.15 USD</td>"#));
+		assert!(exported.contains(r#"<td colspan="5">Total</td><td class="number">66.15 USD</td>"#));
+
+		let due = Utc.ymd(2022, 2, 1).and_hms(12, 0, 0);
+		let mut job = job;
+		job.invoice.date = Some(InvoiceDate { issued: Utc.ymd(2022, 1, 2).and_hms(12, 0, 0), paid: None });
+
+		let terms = Terms { due: Some(due), number: Some("INV-2022-0001".into()), ..Default::default() };
+		let section = Section { job: &job, terms: &terms, timesheets: &[] };
+		let exported = super::export(&job.client, &[section], &contact_info, &employer);
+		assert!(exported.contains("<title>Invoice INV-2022-0001</title>"));
+		assert!(exported.contains(&format!("<dd>Due by {}</dd>", crate::export::date(due))));
+	}
+}
@@ -0,0 +1,454 @@
+mod document;
+
+use core::iter;
+
+use document::{Document, Font, MARGIN, PAGE_WIDTH};
+use money2::Decimal;
+use winvoice_schema::{Contact, ContactKind, InvoiceDate, Organization};
+
+use super::{date, Charges, Section, Terms};
+use crate::{fmt, utils::Billable};
+
+/// The size of most text, in points.
+const BODY: f32 = 10.0;
+
+/// The size of section headings, in points.
+const HEADING: f32 = 12.0;
+
+/// The leftmost position of any text.
+const LEFT: f32 = MARGIN;
+
+/// The rightmost position of any text.
+const RIGHT: f32 = PAGE_WIDTH - MARGIN;
+
+/// The size of text in the table of line items, in points.
+const SMALL: f32 = 9.0;
+
+/// The size of the title, in points.
+const TITLE: f32 = 20.0;
+
+/// The positions of the columns in the table of line items.
+mod column
+{
+	/// The left side of the column showing when work began.
+	pub const BEGAN: f32 = super::LEFT;
+
+	/// The left side of the column showing when work ended.
+	pub const ENDED: f32 = BEGAN + 78.0;
+
+	/// The left side of the column showing who did the work.
+	pub const EMPLOYEE: f32 = ENDED + 78.0;
+
+	/// The left side of the column showing notes about the work (and expenses).
+	pub const NOTES: f32 = EMPLOYEE + 74.0;
+
+	/// The right side of the column showing the hours worked.
+	pub const HOURS: f32 = AMOUNT - 92.0;
+
+	/// The right side of the column showing the cost of the work (and expenses).
+	pub const AMOUNT: f32 = super::RIGHT;
+
+	/// The width of the [`EMPLOYEE`] column.
+	pub const EMPLOYEE_WIDTH: f32 = NOTES - EMPLOYEE - 4.0;
+
+	/// The width of the [`NOTES`] column.
+	pub const NOTES_WIDTH: f32 = HOURS - 36.0 - NOTES;
+}
+
+/// Display a `Contact`'s information as a line of text.
+fn contact(c: &Contact) -> String
+{
+	match &c.kind
+	{
+		ContactKind::Address(location) => format!("{}: {location}", c.label),
+		ContactKind::Email(s) | ContactKind::Other(s) | ContactKind::Phone(s) => format!("{}: {s}", c.label),
+	}
+}
+
+/// Export the `sections` of an invoice to the `client` as a paginated PDF.
+///
+/// See [`Format::export`](super::Format::export) for more information.
+pub(super) fn export(
+	client: &Organization,
+	sections: &[Section<'_>],
+	contact_info: &[Contact],
+	employer: &Organization,
+) -> Vec<u8>
+{
+	let mut document = Document::new();
+	let title = super::title(sections);
+
+	document.next_line(TITLE);
+	document.text(LEFT, Font::Bold, TITLE, &title);
+	if let [s] = sections
+	{
+		if let Some(d) = s.job.invoice.date
+		{
+			document.text_right(RIGHT, Font::Regular, BODY, &format!("Issued {}", date(d.issued)));
+			document.next_line(leading(BODY));
+			document.text_right(RIGHT, Font::Bold, BODY, &status(s.terms, d));
+		}
+	}
+
+	/* The parties {{{ */
+	let half = (RIGHT - LEFT) / 2.0;
+	let lines = |heading: &str, o: &Organization, contacts: &[Contact]| {
+		let mut lines = vec![(Font::Bold, heading.to_owned()), (Font::Bold, o.name.clone())];
+		iter::once(o.location.to_string()).chain(contacts.iter().map(contact)).for_each(|l| {
+			lines.extend(Font::Regular.wrap(&l, BODY, half - 12.0).into_iter().map(|l| (Font::Regular, l)));
+		});
+
+		lines
+	};
+
+	let from = lines("From", employer, contact_info);
+	let to = lines("Bill To", client, &[]);
+
+	document.next_line(leading(BODY));
+	(0..from.len().max(to.len())).for_each(|i| {
+		document.next_line(leading(BODY));
+		[(LEFT, from.get(i)), (LEFT + half, to.get(i))].into_iter().for_each(|(x, line)| {
+			if let Some((font, text)) = line
+			{
+				document.text(x, *font, BODY, text);
+			}
+		});
+	});
+	/* }}} */
+
+	sections.iter().for_each(|s| section(&mut document, s, sections.len() > 1));
+	if let Some(total) = super::total(sections)
+	{
+		summary(&mut document, sections, &total);
+	}
+
+	if !document.replaced().is_empty()
+	{
+		eprintln!(
+			"Warning: the fonts of PDF exports cannot show {}, so {} written as `?` in {}. Export as HTML to keep \
+			 them.",
+			fmt::quoted(document.replaced().iter().collect::<String>()),
+			match document.replaced().len()
+			{
+				1 => "it was",
+				_ => "they were",
+			},
+			fmt::quoted(&title),
+		);
+	}
+
+	document.finish(|page, count| format!("{title} — Page {page} of {count}"))
+}
+
+/// Write a `section` of an invoice, which is `combined` with others if it lists its own
+/// invoice number and dates.
+fn section(document: &mut Document, section: &Section<'_>, combined: bool)
+{
+	let Section { job, terms, timesheets } = section;
+
+	/* The job {{{ */
+	heading(document, &format!("Job {}", fmt::id_num(job.id)));
+	let invoice = match combined
+	{
+		false => Vec::new(),
+		true => [
+			terms.number.clone().map(|n| ("Invoice", n)),
+			job.invoice.date.map(|d| ("Issued", date(d.issued))),
+			job.invoice.date.map(|d| ("Status", status(terms, d))),
+		]
+		.into_iter()
+		.flatten()
+		.collect(),
+	};
+
+	invoice
+		.into_iter()
+		.chain(
+			[
+				Some(("Opened", date(job.date_open))),
+				job.date_close.map(|d| ("Closed", date(d))),
+				Some(("Hourly Rate", job.invoice.hourly_rate.to_string())),
+				Some(("Increment", humantime::format_duration(job.increment).to_string())),
+			]
+			.into_iter()
+			.flatten(),
+		)
+		.for_each(|(key, value)| {
+			document.next_line(leading(BODY));
+			document.text(LEFT, Font::Bold, BODY, key);
+			document.text(LEFT + 80.0, Font::Regular, BODY, &value);
+		});
+
+	[("Objectives", &job.objectives), ("Notes", &job.notes)].into_iter().filter(|(_, text)| !text.is_empty()).for_each(
+		|(key, text)| {
+			document.next_line(leading(BODY) * 1.5);
+			document.text(LEFT, Font::Bold, BODY, key);
+			Font::Regular.wrap(text, BODY, RIGHT - LEFT).iter().for_each(|line| {
+				document.next_line(leading(BODY));
+				document.text(LEFT, Font::Regular, BODY, line);
+			});
+		},
+	);
+	/* }}} */
+
+	/* The line items {{{ */
+	heading(document, "Timesheets");
+	table_header(document);
+	timesheets.iter().for_each(|t| {
+		let employee = Font::Regular.wrap(&t.employee.name, SMALL, column::EMPLOYEE_WIDTH);
+		let notes = Font::Regular.wrap(&t.work_notes, SMALL, column::NOTES_WIDTH);
+		let expenses: Vec<_> = t
+			.expenses
+			.iter()
+			.map(|x| {
+				let lines =
+					Font::Regular.wrap(&format!("{}: {}", x.category, x.description), SMALL, column::NOTES_WIDTH - 8.0);
+				(lines, x.cost.to_string())
+			})
+			.collect();
+
+		let rows = employee.len().max(notes.len()).max(1);
+		let height = (rows + expenses.iter().map(|(lines, _)| lines.len()).sum::<usize>()) as f32 * leading(SMALL);
+
+		if document.reserve(height + 6.0)
+		{
+			table_header(document);
+		}
+
+		document.next_line(leading(SMALL));
+		document.text(column::BEGAN, Font::Regular, SMALL, &date(t.time_begin));
+		document.text(column::ENDED, Font::Regular, SMALL, &t.time_end.map(date).unwrap_or_default());
+		document.text_right(column::HOURS, Font::Regular, SMALL, &format!("{:.2}", t.hours()));
+		document.text_right(column::AMOUNT, Font::Regular, SMALL, &t.labor().to_string());
+
+		(0..rows).for_each(|i| {
+			if i > 0
+			{
+				document.next_line(leading(SMALL));
+			}
+
+			[(column::EMPLOYEE, employee.get(i)), (column::NOTES, notes.get(i))].into_iter().for_each(|(x, line)| {
+				if let Some(text) = line
+				{
+					document.text(x, Font::Regular, SMALL, text);
+				}
+			});
+		});
+
+		expenses.iter().for_each(|(lines, cost)| {
+			lines.iter().enumerate().for_each(|(i, line)| {
+				document.next_line(leading(SMALL));
+				if i == 0
+				{
+					document.text(column::NOTES, Font::Regular, SMALL, "•");
+					document.text_right(column::AMOUNT, Font::Regular, SMALL, cost);
+				}
+
+				document.text(column::NOTES + 8.0, Font::Regular, SMALL, line);
+			});
+		});
+
+		document.rule(LEFT, RIGHT);
+		document.advance(6.0);
+	});
+	/* }}} */
+
+	/* The totals {{{ */
+	let charges = section.charges();
+	let adjustments = match terms.adjusts()
+	{
+		false => Vec::new(),
+		true => [
+			Some(("Subtotal".to_owned(), charges.subtotal.to_string())),
+			terms.discount.map(|d| (format!("Discount ({d})"), format!("-{}", charges.discount))),
+			terms.tax.map(|t| (format!("Tax ({t}%)"), charges.tax.to_string())),
+		]
+		.into_iter()
+		.flatten()
+		.map(|(key, value)| (Font::Regular, key, value))
+		.collect(),
+	};
+
+	document.reserve(leading(BODY) * (5 + adjustments.len()) as f32);
+	document.next_line(leading(BODY));
+	[
+		(Font::Regular, "Hours".to_owned(), format!("{:.2}", timesheets.iter().map(Billable::hours).sum::<Decimal>())),
+		(Font::Regular, "Labor".to_owned(), super::sum(job, timesheets.iter().map(Billable::labor)).to_string()),
+		(
+			Font::Regular,
+			"Expenses".to_owned(),
+			super::sum(job, timesheets.iter().flat_map(|t| t.expenses.iter().map(|x| x.cost))).to_string(),
+		),
+	]
+	.into_iter()
+	.chain(adjustments)
+	.chain(iter::once((Font::Bold, "Total".to_owned(), charges.total.to_string())))
+	.for_each(|(font, key, value)| {
+		document.next_line(leading(BODY));
+		document.text_right(column::HOURS, font, BODY, &key);
+		document.text_right(column::AMOUNT, font, BODY, &value);
+	});
+	/* }}} */
+}
+
+/// The status of an invoice with some `terms` which was issued on some `date`.
+fn status(terms: &Terms, date: InvoiceDate) -> String
+{
+	date.paid.map_or_else(
+		|| terms.due.map_or_else(|| "Due".into(), |u| format!("Due by {}", self::date(u))),
+		|p| format!("Paid {}", self::date(p)),
+	)
+}
+
+/// Write the `total` of each of the `sections` of a combined invoice, and the `total` of the
+/// invoice itself.
+fn summary(document: &mut Document, sections: &[Section<'_>], total: &Charges)
+{
+	heading(document, "Summary");
+	sections
+		.iter()
+		.map(|s| {
+			let job = s.terms.number.as_ref().map_or_else(
+				|| format!("Job {}", fmt::id_num(s.job.id)),
+				|n| format!("Job {} ({n})", fmt::id_num(s.job.id)),
+			);
+			(Font::Regular, job, s.charges().total.to_string())
+		})
+		.chain(iter::once((Font::Bold, "Total".to_owned(), total.total.to_string())))
+		.for_each(|(font, key, value)| {
+			document.reserve(leading(BODY));
+			document.next_line(leading(BODY));
+			document.text(LEFT, font, BODY, &key);
+			document.text_right(column::AMOUNT, font, BODY, &value);
+		});
+}
+
+/// Write a section `heading`, leaving some space before it.
+fn heading(document: &mut Document, heading: &str)
+{
+	document.reserve(leading(HEADING) * 4.5);
+	document.next_line(leading(HEADING) * 1.5);
+	document.text(LEFT, Font::Bold, HEADING, heading);
+	document.rule(LEFT, RIGHT);
+	document.advance(4.0);
+}
+
+/// The distance between the baselines of lines of text at some `size`.
+fn leading(size: f32) -> f32
+{
+	size * 1.4
+}
+
+/// Write the names of the columns of the table of line items.
+fn table_header(document: &mut Document)
+{
+	document.next_line(leading(SMALL));
+	[(column::BEGAN, "Began"), (column::ENDED, "Ended"), (column::EMPLOYEE, "Employee"), (column::NOTES, "Work Notes")]
+		.into_iter()
+		.for_each(|(x, text)| document.text(x, Font::Bold, SMALL, text));
+
+	document.text_right(column::HOURS, Font::Bold, SMALL, "Hours");
+	document.text_right(column::AMOUNT, Font::Bold, SMALL, "Amount");
+	document.rule(LEFT, RIGHT);
+	document.advance(6.0);
+}
+
+#[cfg(test)]
+mod tests
+{
+	use core::time::Duration;
+
+	use winvoice_schema::{
+		chrono::{TimeZone, Utc},
+		Contact,
+		ContactKind,
+		Currency,
+		Employee,
+		Expense,
+		Invoice,
+		Job,
+		Location,
+		Money,
+		Organization,
+		Timesheet,
+	};
+
+	use crate::export::{Discount, Section, Terms};
+
+	#[test]
+	fn export()
+	{
+		let location = Location { id: 0, name: "Earth".into(), outer: None };
+		let employer = Organization { id: 0, location: location.clone(), name: "Foo (and Sons)".into() };
+		let job = Job {
+			client: Organization { id: 1, location, name: "Bar".into() },
+			date_close: Some(Utc.ymd(2022, 1, 2).and_hms(0, 0, 0)),
+			date_open: Utc.ymd(2022, 1, 1).and_hms(0, 0, 0),
+			id: 3,
+			increment: Duration::from_secs(15 * 60),
+			invoice: Invoice { date: None, hourly_rate: Money::new(20_00, 2, Currency::Usd) },
+			notes: String::new(),
+			objectives: "Write code".into(),
+		};
+
+		let timesheet = Timesheet {
+			id: 0,
+			employee: Employee { id: 0, name: "Bob".into(), status: "Contractor".into(), title: "Dev".into() },
+			expenses: vec![Expense {
+				id: 0,
+				category: "Food".into(),
+				cost: Money::new(5_00, 2, Currency::Usd),
+				description: "Lunch".into(),
+				timesheet_id: 0,
+			}],
+			job: job.clone(),
+			time_begin: Utc.ymd(2022, 1, 1).and_hms(9, 0, 0),
+			time_end: Some(Utc.ymd(2022, 1, 1).and_hms(10, 20, 0)),
+			work_notes: "Fixed bugs".into(),
+		};
+
+		let contact_info = [Contact { kind: ContactKind::Email("foo@bar.io".into()), label: "Email".into() }];
+		let timesheets = vec![timesheet; 40];
+		let terms = Terms::default();
+		let section = Section { job: &job, terms: &terms, timesheets: &timesheets };
+		let exported = super::export(&job.client, &[section], &contact_info, &employer);
+		let text = String::from_utf8_lossy(&exported);
+
+		assert!(exported.starts_with(b"%PDF-1.4"));
+		assert!(text.contains("(Invoice No.3) Tj"));
+		assert!(text.contains(r"(Foo \(and Sons\)) Tj"));
+		assert!(text.contains("(Email: foo@bar.io) Tj"));
+		assert!(text.contains("(Food: Lunch) Tj"));
+		let count = text.split("/Count ").nth(1).and_then(|s| s.split_whitespace().next()).unwrap();
+		assert!(count.parse::<usize>().unwrap() > 1);
+		assert!(text.contains(&format!(r"(Invoice No.3 \227 Page {count} of {count}) Tj")));
+
+		// 40 × (1.5h at 20.00 USD/h, plus 5.00 USD of expenses)
+		assert!(text.contains("(60.00) Tj"));
+		assert!(text.contains("(1200.00 USD) Tj"));
+		assert!(text.contains("(200.00 USD) Tj"));
+		assert!(text.contains("(1400.00 USD) Tj"));
+		assert!(!text.contains("(Subtotal) Tj"));
+		assert!(!text.contains("(Summary) Tj"));
+
+		let mut other = job.clone();
+		other.id = 4;
+		let other_terms = Terms { number: Some("INV-2022-0002".into()), ..Default::default() };
+		let sections = [section, Section { job: &other, terms: &other_terms, timesheets: &timesheets[..1] }];
+		let exported = super::export(&job.client, &sections, &contact_info, &employer);
+		let text = String::from_utf8_lossy(&exported);
+		assert!(text.contains("(Invoice No.3, INV-2022-0002) Tj"));
+		assert!(text.contains(r"(Job No.4 \(INV-2022-0002\)) Tj"));
+		assert!(text.contains("(1435.00 USD) Tj"));
+
+		let terms =
+			Terms { discount: Some(Discount::Fixed(Money::new(10_000, 2, Currency::Usd))), ..Default::default() };
+		let section = Section { job: &job, terms: &terms, timesheets: &timesheets };
+		let exported = super::export(&job.client, &[section], &contact_info, &employer);
+		let text = String::from_utf8_lossy(&exported);
+		assert!(text.contains("(Subtotal) Tj"));
+		assert!(text.contains(r"(Discount \(100.00 USD\)) Tj"));
+		assert!(text.contains("(-100.00 USD) Tj"));
+		assert!(text.contains("(1300.00 USD) Tj"));
+	}
+}
@@ -0,0 +1,387 @@
+use core::fmt::Write;
+use std::collections::BTreeSet;
+
+/// The height of a page, in points (US Letter).
+pub const PAGE_HEIGHT: f32 = 792.0;
+
+/// The width of a page, in points (US Letter).
+pub const PAGE_WIDTH: f32 = 612.0;
+
+/// The space left empty around the edge of each page, in points.
+pub const MARGIN: f32 = 54.0;
+
+/// The widths of the printable ASCII characters (starting with `' '`) in [`Font::Regular`], in
+/// thousandths of the font size.
+#[rustfmt::skip]
+const REGULAR_WIDTHS: [u16; 95] = [
+	278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278,
+	556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556,
+	1015, 667, 667, 722, 722, 667, 611, 778, 722, 278, 500, 667, 556, 833, 722, 778,
+	667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 278, 278, 278, 469, 556,
+	333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500, 222, 833, 556, 556,
+	556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,
+];
+
+/// The widths of the printable ASCII characters (starting with `' '`) in [`Font::Bold`], in
+/// thousandths of the font size.
+#[rustfmt::skip]
+const BOLD_WIDTHS: [u16; 95] = [
+	278, 333, 474, 556, 556, 889, 722, 238, 333, 333, 389, 584, 278, 333, 278, 278,
+	556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 333, 333, 584, 584, 584, 611,
+	975, 722, 722, 722, 722, 667, 611, 778, 722, 278, 556, 722, 611, 833, 722, 778,
+	667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 333, 278, 333, 584, 556,
+	333, 556, 611, 556, 611, 556, 333, 611, 611, 278, 278, 556, 278, 889, 611, 611,
+	611, 611, 389, 556, 333, 611, 556, 778, 556, 556, 500, 389, 280, 389, 584,
+];
+
+/// One of the fonts which every PDF reader provides, so that it does not have to be embedded.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Font
+{
+	/// Helvetica.
+	Regular,
+
+	/// Helvetica Bold.
+	Bold,
+}
+
+impl Font
+{
+	/// The name which this font is given in the resources of each page.
+	const fn resource(self) -> &'static str
+	{
+		match self
+		{
+			Self::Bold => "F2",
+			Self::Regular => "F1",
+		}
+	}
+
+	/// The width of some `text` when written in this font at some `size`, in points.
+	pub fn width(self, text: &str, size: f32) -> f32
+	{
+		let widths = match self
+		{
+			Self::Bold => &BOLD_WIDTHS,
+			Self::Regular => &REGULAR_WIDTHS,
+		};
+
+		let thousandths: u32 = encode(text)
+			.into_iter()
+			.map(|b| match b
+			{
+				b' '..=b'~' => u32::from(widths[usize::from(b - b' ')]),
+				0x97 => 1000,
+				_ => 556,
+			})
+			.sum();
+
+		thousandths as f32 * size / 1000.0
+	}
+
+	/// Break some `text` into lines which are no wider than `width` when written in this font at
+	/// some `size`. Explicit line breaks are preserved.
+	pub fn wrap(self, text: &str, size: f32, width: f32) -> Vec<String>
+	{
+		let mut lines = Vec::new();
+		text.lines().for_each(|paragraph| {
+			let mut line = String::new();
+			paragraph.split_whitespace().for_each(|word| {
+				let candidate = match line.is_empty()
+				{
+					false => format!("{line} {word}"),
+					true => word.to_owned(),
+				};
+
+				if self.width(&candidate, size) <= width
+				{
+					line = candidate;
+					return;
+				}
+
+				if !line.is_empty()
+				{
+					lines.push(line.clone());
+				}
+
+				// break any word which is too long to fit on a line by itself.
+				line = word.chars().fold(String::new(), |mut partial, c| {
+					partial.push(c);
+					if self.width(&partial, size) > width && partial.chars().count() > 1
+					{
+						partial.pop();
+						lines.push(partial.clone());
+						partial.clear();
+						partial.push(c);
+					}
+
+					partial
+				});
+			});
+
+			lines.push(line);
+		});
+
+		lines
+	}
+}
+
+/// A PDF which is written one line at a time, from the top of each page to the bottom.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Document
+{
+	/// The content streams of the pages which have been filled.
+	pages: Vec<String>,
+
+	/// The content stream of the page being written.
+	page: String,
+
+	/// The characters which [`Font`]s cannot represent, and were written as `?` instead.
+	replaced: BTreeSet<char>,
+
+	/// The position (from the bottom of the page) of the line being written.
+	y: f32,
+}
+
+impl Document
+{
+	/// Create a new [`Document`] with a single, empty page.
+	pub const fn new() -> Self
+	{
+		Self { pages: Vec::new(), page: String::new(), replaced: BTreeSet::new(), y: PAGE_HEIGHT - MARGIN }
+	}
+
+	/// Move down the page by some `height`.
+	pub fn advance(&mut self, height: f32)
+	{
+		self.y -= height;
+	}
+
+	/// Serialize the [`Document`], writing the `footer` (given the page number and number of pages)
+	/// at the bottom of each page.
+	pub fn finish<F>(mut self, footer: F) -> Vec<u8>
+	where
+		F: Fn(usize, usize) -> String,
+	{
+		/// The number of objects which come before the pages (i.e. the catalog, page tree, and
+		/// fonts).
+		const FIRST_PAGE: usize = 5;
+
+		self.pages.push(self.page);
+
+		let count = self.pages.len();
+		let mut objects = vec![
+			"<< /Type /Catalog /Pages 2 0 R >>".to_owned(),
+			format!(
+				"<< /Type /Pages /Kids [{}] /Count {count} >>",
+				(0..count).map(|i| format!("{} 0 R", FIRST_PAGE + (i * 2))).collect::<Vec<_>>().join(" "),
+			),
+			"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>".to_owned(),
+			"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold /Encoding /WinAnsiEncoding >>".to_owned(),
+		];
+
+		self.pages.into_iter().enumerate().for_each(|(i, mut content)| {
+			let footer = footer(i + 1, count);
+			let size = 9.0;
+			text(
+				&mut content,
+				(PAGE_WIDTH - Font::Regular.width(&footer, size)) / 2.0,
+				MARGIN / 2.0,
+				Font::Regular,
+				size,
+				&footer,
+			);
+
+			objects.push(format!(
+				"<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {PAGE_WIDTH} {PAGE_HEIGHT}] /Resources << /Font << /F1 3 \
+				 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+				FIRST_PAGE + (i * 2) + 1,
+			));
+			objects.push(format!("<< /Length {} >>\nstream\n{content}endstream", content.len()));
+		});
+
+		let mut pdf = b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n".to_vec();
+		let offsets: Vec<_> = objects
+			.into_iter()
+			.enumerate()
+			.map(|(i, object)| {
+				let offset = pdf.len();
+				pdf.extend_from_slice(format!("{} 0 obj\n{object}\nendobj\n", i + 1).as_bytes());
+				offset
+			})
+			.collect();
+
+		let xref = pdf.len();
+		let mut trailer = format!("xref\n0 {}\n0000000000 65535 f \n", offsets.len() + 1);
+		offsets.into_iter().for_each(|offset| {
+			writeln!(trailer, "{offset:010} 00000 n ").expect("writing to a `String` should not fail");
+		});
+
+		write!(trailer, "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref}\n%%EOF\n", count * 2 + FIRST_PAGE)
+			.expect("writing to a `String` should not fail");
+
+		pdf.extend_from_slice(trailer.as_bytes());
+		pdf
+	}
+
+	/// Move to the next line, which is some `height` below the current one, starting a new page if
+	/// there is not enough space. Returns whether a new page was started.
+	pub fn next_line(&mut self, height: f32) -> bool
+	{
+		let new_page = self.reserve(height);
+		self.advance(height);
+		new_page
+	}
+
+	/// Start a new page if there is not enough space left on this one for something of some
+	/// `height`, returning whether a new page was started.
+	pub fn reserve(&mut self, height: f32) -> bool
+	{
+		if self.y - height >= MARGIN || self.page.is_empty()
+		{
+			return false;
+		}
+
+		self.pages.push(core::mem::take(&mut self.page));
+		self.y = PAGE_HEIGHT - MARGIN;
+		true
+	}
+
+	/// Draw a horizontal line under the current line, from `left` to `right`.
+	pub fn rule(&mut self, left: f32, right: f32)
+	{
+		let y = self.y - 4.0;
+		writeln!(self.page, "0.5 w {left:.2} {y:.2} m {right:.2} {y:.2} l S")
+			.expect("writing to a `String` should not fail");
+	}
+
+	/// The characters which [`Font`]s cannot represent, and were written as `?` instead.
+	pub const fn replaced(&self) -> &BTreeSet<char>
+	{
+		&self.replaced
+	}
+
+	/// Write some `text` on the current line, starting at `x`.
+	pub fn text(&mut self, x: f32, font: Font, size: f32, text: &str)
+	{
+		self.replaced.extend(text.chars().filter(|c| encode_char(*c).is_none()));
+		self::text(&mut self.page, x, self.y, font, size, text);
+	}
+
+	/// Write some `text` on the current line, ending at `right`.
+	pub fn text_right(&mut self, right: f32, font: Font, size: f32, text: &str)
+	{
+		self.text(right - font.width(text, size), font, size, text);
+	}
+}
+
+/// Encode some `text` as `WinAnsiEncoding`, replacing characters which it cannot represent with `?`.
+fn encode(text: &str) -> Vec<u8>
+{
+	text.chars().flat_map(|c| encode_char(c).unwrap_or_else(|| vec![b'?'])).collect()
+}
+
+/// Encode a character `c` as `WinAnsiEncoding`, or [`None`] if it cannot be represented.
+fn encode_char(c: char) -> Option<Vec<u8>>
+{
+	match c
+	{
+		'№' => Some(b"No.".to_vec()),
+		'€' => Some(vec![0x80]),
+		'•' => Some(vec![0x95]),
+		'–' => Some(vec![0x96]),
+		'—' => Some(vec![0x97]),
+		'‘' => Some(vec![0x91]),
+		'’' => Some(vec![0x92]),
+		'“' => Some(vec![0x93]),
+		'”' => Some(vec![0x94]),
+		' '..='~' | '\u{A0}'..='\u{FF}' =>
+		{
+			Some(vec![u8::try_from(u32::from(c)).expect("`c` should be in the latin-1 range")])
+		},
+		_ => None,
+	}
+}
+
+/// Write some `text` to the `content` stream of a page at (`x`, `y`).
+fn text(content: &mut String, x: f32, y: f32, font: Font, size: f32, text: &str)
+{
+	let literal = encode(text).into_iter().fold(String::new(), |mut literal, b| {
+		match b
+		{
+			b'(' | b')' | b'\\' => literal.extend(['\\', char::from(b)]),
+			b' '..=b'~' => literal.push(char::from(b)),
+			_ => write!(literal, "\\{b:03o}").expect("writing to a `String` should not fail"),
+		};
+
+		literal
+	});
+
+	writeln!(content, "BT /{} {size} Tf {x:.2} {y:.2} Td ({literal}) Tj ET", font.resource())
+		.expect("writing to a `String` should not fail");
+}
+
+#[cfg(test)]
+mod tests
+{
+	use pretty_assertions::assert_eq;
+
+	use super::{Document, Font, MARGIN, PAGE_HEIGHT};
+
+	#[test]
+	fn finish()
+	{
+		let mut document = Document::new();
+		document.text(MARGIN, Font::Bold, 12.0, "Invoice №3 (€5)");
+		assert!(document.replaced().is_empty());
+
+		let mut pages = 1;
+		(0..100).for_each(|_| {
+			if document.reserve(12.0)
+			{
+				pages += 1;
+			}
+
+			document.advance(12.0);
+			document.text_right(200.0, Font::Regular, 10.0, "Line");
+		});
+
+		assert_eq!(pages, 2);
+
+		let pdf = document.finish(|page, count| format!("Page {page} of {count}"));
+		let text = String::from_utf8_lossy(&pdf[15..]);
+
+		assert!(pdf.starts_with(b"%PDF-1.4\n"));
+		assert!(text.ends_with("%%EOF\n"));
+		assert!(text.contains("/Count 2"));
+		assert!(text.contains(r"(Invoice No.3 \(\2005\)) Tj"));
+		assert!(text.contains("(Page 2 of 2) Tj"));
+
+		// every entry in the cross-reference table should point to the object it describes.
+		let xref = text.rfind("xref\n").unwrap();
+		text[xref..].lines().skip(3).take_while(|l| !l.starts_with("trailer")).enumerate().for_each(|(i, l)| {
+			let offset: usize = l[..10].parse().unwrap();
+			assert!(pdf[offset..].starts_with(format!("{} 0 obj", i + 1).as_bytes()));
+		});
+
+		let y = PAGE_HEIGHT - MARGIN;
+		assert!(text.contains(&format!("BT /F2 12 Tf {MARGIN:.2} {y:.2} Td")));
+	}
+
+	#[test]
+	fn replaced()
+	{
+		let mut document = Document::new();
+		document.text(MARGIN, Font::Regular, 10.0, "Zoë Żółć 山田");
+		assert_eq!(document.replaced().iter().collect::<String>(), "ćłŻ山田");
+		assert!(String::from_utf8_lossy(&document.finish(|_, _| String::new())).contains(r"(Zo\353 ?\363?? ??) Tj"));
+	}
+
+	#[test]
+	fn wrap()
+	{
+		assert_eq!(Font::Regular.width("iiii", 10.0), 8.88);
+		assert_eq!(Font::Regular.wrap("foo bar baz\n\nqux", 10.0, 40.0), ["foo bar", "baz", "", "qux"]);
+		assert_eq!(Font::Bold.wrap("mmmmmmmm", 10.0, 20.0), ["mm", "mm", "mm", "mm"]);
+	}
+}
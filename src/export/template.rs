@@ -0,0 +1,309 @@
+use std::{io, path::Path};
+
+use handlebars::{Handlebars, RenderError};
+use money2::Decimal;
+use serde::Serialize;
+use tokio::fs;
+use winvoice_schema::{
+	chrono::{DateTime, Utc},
+	Contact,
+	Expense,
+	Job,
+	Money,
+	Organization,
+	Timesheet,
+};
+
+use super::{Charges, Section};
+use crate::utils::Billable;
+
+/// The extensions of files which should have their values escaped, since they are HTML.
+const ESCAPED_EXTENSIONS: [&str; 4] = ["htm", "html", "xhtml", "xml"];
+
+/// The extensions which mark a file as a [`Template`], rather than the kind of file it renders.
+const TEMPLATE_EXTENSIONS: [&str; 2] = ["handlebars", "hbs"];
+
+/// The values which are available to a [`Template`] when it is rendered.
+#[derive(Debug, Serialize)]
+struct Context<'job>
+{
+	/// The organization which is being invoiced.
+	client: &'job Organization,
+
+	/// The contact information of the employer.
+	contact_info: &'job [Contact],
+
+	/// The organization which is sending the invoice.
+	employer: &'job Organization,
+
+	/// The fields of the only [`Part`], when the invoice is not combined.
+	#[serde(flatten)]
+	job: Option<Part<'job>>,
+
+	/// Every [`Part`] of the invoice.
+	jobs: Vec<Part<'job>>,
+
+	/// The sum of the [`Charges`] of every [`Part`].
+	total: Option<Charges>,
+}
+
+/// The [`Section`] of an invoice for one job.
+#[derive(Debug, Serialize)]
+struct Part<'job>
+{
+	/// The date that the invoice must be paid by, if there are payment terms.
+	due: Option<DateTime<Utc>>,
+
+	/// Every expense of every timesheet.
+	expenses: Vec<&'job Expense>,
+
+	/// The job being invoiced.
+	job: &'job Job,
+
+	/// The number of the invoice, if it has been assigned one.
+	number: Option<&'job str>,
+
+	/// The timesheets of the job.
+	timesheets: Vec<Line<'job>>,
+
+	/// The sums of each [`Line`].
+	totals: Totals,
+}
+
+impl<'job> From<&Section<'job>> for Part<'job>
+{
+	fn from(section: &Section<'job>) -> Self
+	{
+		let Section { job, terms, timesheets } = *section;
+		Self {
+			due: terms.due,
+			expenses: timesheets.iter().flat_map(|t| &t.expenses).collect(),
+			job,
+			number: terms.number.as_deref(),
+			timesheets: timesheets
+				.iter()
+				.map(|t| Line { hours: t.hours(), labor: t.labor(), timesheet: t, total: t.total() })
+				.collect(),
+			totals: Totals {
+				charges: section.charges(),
+				expenses: super::sum(job, timesheets.iter().flat_map(|t| t.expenses.iter().map(|x| x.cost))),
+				hours: timesheets.iter().map(Billable::hours).sum(),
+				labor: super::sum(job, timesheets.iter().map(Billable::labor)),
+			},
+		}
+	}
+}
+
+/// A `Timesheet`, and what it cost.
+#[derive(Debug, Serialize)]
+struct Line<'job>
+{
+	/// See [`Billable::hours`].
+	hours: Decimal,
+
+	/// See [`Billable::labor`].
+	labor: Money,
+
+	/// The fields of the `Timesheet` itself.
+	#[serde(flatten)]
+	timesheet: &'job Timesheet,
+
+	/// See [`Billable::total`].
+	total: Money,
+}
+
+/// The sums of the [`Line`]s of an invoice.
+#[derive(Debug, Serialize)]
+struct Totals
+{
+	/// The `subtotal`, `discount`, `tax`, and `total`.
+	#[serde(flatten)]
+	charges: Charges,
+
+	/// The cost of every expense.
+	expenses: Money,
+
+	/// The number of hours worked.
+	hours: Decimal,
+
+	/// The cost of the hours worked.
+	labor: Money,
+}
+
+/// A [Handlebars](https://handlebarsjs.com) template which `Job`s can be exported with.
+///
+/// The template can use the `client`, `contact_info`, `due` date, `employer`, `expenses`, `job`,
+/// `number`, `timesheets`, and `totals` of the invoice. Each of the `timesheets` has its `hours`,
+/// `labor`, and `total`, as well as the usual fields. The `totals` are the `expenses`, `hours`,
+/// `labor`, `subtotal`, `discount`, `tax`, and `total`.
+///
+/// Invoices which combine several jobs do not have a `due` date, `expenses`, `job`, `number`,
+/// `timesheets`, or `totals`. Instead, each of the `jobs` has them, and the `total` of the
+/// invoice has the `subtotal`, `discount`, `tax`, and `total` of every job together. The `jobs`
+/// and `total` are also available when there is only one job.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Template
+{
+	/// The extension of the files which the template renders.
+	extension: String,
+
+	/// The contents of the template.
+	source: String,
+}
+
+impl Template
+{
+	/// The extension of files which were rendered using this [`Template`].
+	pub fn extension(&self) -> &str
+	{
+		&self.extension
+	}
+
+	/// Read the [`Template`] at `filepath`.
+	///
+	/// The files it renders have the extension of the `filepath`, ignoring `.hbs` or `.handlebars`
+	/// (e.g. "invoice.html.hbs" renders ".html" files). When there is no other extension, ".txt"
+	/// is used.
+	pub async fn read(filepath: &Path) -> io::Result<Self>
+	{
+		let rendered = match filepath.extension().and_then(|e| e.to_str())
+		{
+			Some(e) if TEMPLATE_EXTENSIONS.contains(&e) => filepath.with_extension(""),
+			_ => filepath.to_owned(),
+		};
+
+		let extension = rendered.extension().and_then(|e| e.to_str()).unwrap_or("txt").to_owned();
+		fs::read_to_string(filepath).await.map(|source| Self { extension, source })
+	}
+
+	/// Render the `sections` of an invoice from the `employer` to the `client`, listing the
+	/// `contact_info` of the `employer`.
+	///
+	/// Values are only escaped when rendering HTML or XML, and it is an error to refer to a value
+	/// which does not exist.
+	pub fn render(
+		&self,
+		client: &Organization,
+		sections: &[Section<'_>],
+		contact_info: &[Contact],
+		employer: &Organization,
+	) -> Result<String, RenderError>
+	{
+		let mut handlebars = Handlebars::new();
+		handlebars.set_strict_mode(true);
+		if !ESCAPED_EXTENSIONS.contains(&self.extension.as_str())
+		{
+			handlebars.register_escape_fn(handlebars::no_escape);
+		}
+
+		let context = Context {
+			client,
+			contact_info,
+			employer,
+			job: match sections
+			{
+				[s] => Some(s.into()),
+				_ => None,
+			},
+			jobs: sections.iter().map(Into::into).collect(),
+			total: super::total(sections).or_else(|| sections.first().map(Section::charges)),
+		};
+
+		handlebars.render_template(&self.source, &context)
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use core::time::Duration;
+	use std::fs;
+
+	use money2::Decimal;
+	use pretty_assertions::assert_eq;
+	use winvoice_schema::{
+		chrono::{TimeZone, Utc},
+		Currency,
+		Employee,
+		Invoice,
+		Job,
+		Location,
+		Money,
+		Organization,
+		Timesheet,
+	};
+
+	use super::Template;
+	use crate::{
+		export::{Discount, Section, Terms},
+		utils,
+	};
+
+	#[tokio::test]
+	async fn render()
+	{
+		let location = Location { id: 0, name: "Earth".into(), outer: None };
+		let employer = Organization { id: 0, location: location.clone(), name: "Foo & Sons".into() };
+		let job = Job {
+			client: Organization { id: 1, location, name: "Bar".into() },
+			date_close: None,
+			date_open: Utc.ymd(2022, 1, 1).and_hms(0, 0, 0),
+			id: 3,
+			increment: Duration::from_secs(15 * 60),
+			invoice: Invoice { date: None, hourly_rate: Money::new(20_00, 2, Currency::Usd) },
+			notes: String::new(),
+			objectives: "Write code".into(),
+		};
+
+		let timesheets = [Timesheet {
+			id: 7,
+			employee: Employee { id: 0, name: "Bob".into(), status: "Contractor".into(), title: "Dev".into() },
+			expenses: Vec::new(),
+			job: job.clone(),
+			time_begin: Utc.ymd(2022, 1, 1).and_hms(9, 0, 0),
+			time_end: Some(Utc.ymd(2022, 1, 1).and_hms(10, 20, 0)),
+			work_notes: "Fixed bugs".into(),
+		}];
+
+		let mut filepath = utils::temp_file::<Template>("render");
+		filepath.set_file_name("invoice.tex.hbs");
+		fs::write(
+			&filepath,
+			"{{employer.name}} #{{job.id}}\n{{#each timesheets}}{{id}}: {{work_notes}} \
+			 ({{hours}}h){{/each}}\n{{totals.subtotal.amount}} - {{totals.discount.amount}} + {{totals.tax.amount}} = \
+			 {{totals.total.amount}}",
+		)
+		.unwrap();
+
+		let template = Template::read(&filepath).await.unwrap();
+		let terms = Terms { discount: Some(Discount::Percent(Decimal::TEN)), ..Default::default() };
+		assert_eq!(template.extension(), "tex");
+		let section = Section { job: &job, terms: &terms, timesheets: &timesheets };
+		assert_eq!(
+			template.render(&job.client, &[section], &[], &employer).unwrap(),
+			"Foo & Sons #3\n7: Fixed bugs (1.50h)\n30.00 - 3.00 + 0 = 27.00"
+		);
+
+		fs::write(&filepath, "{{client.name}}: {{#each jobs}}#{{job.id}} {{/each}}= {{total.total.amount}}").unwrap();
+		let template = Template::read(&filepath).await.unwrap();
+		let default_terms = Terms::default();
+		let sections = [section, Section { job: &job, terms: &default_terms, timesheets: &timesheets }];
+		assert_eq!(template.render(&job.client, &sections, &[], &employer).unwrap(), "Bar: #3 #3 = 57.00");
+
+		fs::write(&filepath, "{{job.id}}").unwrap();
+		let template = Template::read(&filepath).await.unwrap();
+		assert!(template.render(&job.client, &sections, &[], &employer).is_err());
+
+		filepath.set_file_name("invoice.html");
+		fs::write(&filepath, "{{employer.name}}").unwrap();
+
+		let template = Template::read(&filepath).await.unwrap();
+		let section = Section { job: &job, terms: &default_terms, timesheets: &[] };
+		assert_eq!(template.extension(), "html");
+		assert_eq!(template.render(&job.client, &[section], &[], &employer).unwrap(), "Foo &amp; Sons");
+
+		fs::write(&filepath, "{{employer.nam}}").unwrap();
+		let template = Template::read(&filepath).await.unwrap();
+		assert!(template.render(&job.client, &[section], &[], &employer).is_err());
+	}
+}
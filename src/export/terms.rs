@@ -0,0 +1,289 @@
+use core::{
+	fmt::{Display, Formatter, Result as FmtResult},
+	ops::Add,
+	str::FromStr,
+};
+
+use money2::{Decimal, Error as MoneyError, Exchange, ExchangeRates};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use winvoice_schema::{
+	chrono::{DateTime, Utc},
+	Currency,
+	Job,
+	Money,
+};
+
+use crate::fmt;
+
+/// An [`Error`](std::error::Error) for parsing a [`Discount`].
+#[derive(Debug, Error)]
+pub enum Error
+{
+	#[allow(missing_docs)]
+	#[error("A discount cannot be negative")]
+	Negative,
+
+	#[allow(missing_docs)]
+	#[error("A discount cannot be more than 100%")]
+	OverOneHundred,
+
+	#[allow(missing_docs)]
+	#[error(transparent)]
+	Parse(#[from] MoneyError),
+}
+
+/// A reduction of the amount which is owed for an invoice.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(into = "String", try_from = "String")]
+pub enum Discount
+{
+	/// A fixed amount, e.g. "50.00 USD".
+	Fixed(Money),
+
+	/// A percentage of the subtotal, e.g. "10%".
+	Percent(Decimal),
+}
+
+impl Discount
+{
+	/// Whether this [`Discount`] does not reduce the amount owed.
+	pub const fn is_zero(&self) -> bool
+	{
+		match self
+		{
+			Self::Fixed(m) => m.amount.is_zero(),
+			Self::Percent(p) => p.is_zero(),
+		}
+	}
+}
+
+impl Display for Discount
+{
+	fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult
+	{
+		match self
+		{
+			Self::Fixed(m) => m.fmt(f),
+			Self::Percent(p) => write!(f, "{p}%"),
+		}
+	}
+}
+
+impl Exchange for Discount
+{
+	fn exchange(self, currency: Currency, rates: &ExchangeRates) -> Self
+	{
+		match self
+		{
+			Self::Fixed(m) => Self::Fixed(m.exchange(currency, rates)),
+			Self::Percent(_) => self,
+		}
+	}
+
+	fn exchange_mut(&mut self, currency: Currency, rates: &ExchangeRates)
+	{
+		if let Self::Fixed(m) = self
+		{
+			m.exchange_mut(currency, rates);
+		}
+	}
+}
+
+impl From<Discount> for String
+{
+	fn from(discount: Discount) -> Self
+	{
+		discount.to_string()
+	}
+}
+
+impl FromStr for Discount
+{
+	type Err = Error;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err>
+	{
+		let discount = s.trim().strip_suffix('%').map_or_else(
+			|| s.parse().map(Self::Fixed),
+			|p| p.trim().parse().map(Self::Percent).map_err(MoneyError::from),
+		)?;
+
+		match discount
+		{
+			Self::Fixed(Money { amount: a, .. }) | Self::Percent(a) if a < Decimal::ZERO => Err(Error::Negative),
+			Self::Percent(p) if p > Decimal::ONE_HUNDRED => Err(Error::OverOneHundred),
+			_ => Ok(discount),
+		}
+	}
+}
+
+impl TryFrom<String> for Discount
+{
+	type Error = Error;
+
+	fn try_from(s: String) -> Result<Self, Self::Error>
+	{
+		s.parse()
+	}
+}
+
+/// The amounts which appear at the bottom of an invoice.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+pub struct Charges
+{
+	/// How much the `subtotal` was reduced by.
+	pub discount: Money,
+
+	/// The cost of the hours worked, plus expenses.
+	pub subtotal: Money,
+
+	/// The tax on the `subtotal` after the `discount`.
+	pub tax: Money,
+
+	/// The amount owed.
+	pub total: Money,
+}
+
+impl Add for Charges
+{
+	type Output = Self;
+
+	/// Combine the [`Charges`] of two invoices, which are in the same currency.
+	fn add(self, rhs: Self) -> Self::Output
+	{
+		let add = |lhs: Money, rhs: Money| Money { amount: lhs.amount + rhs.amount, ..lhs };
+		Self {
+			discount: add(self.discount, rhs.discount),
+			subtotal: add(self.subtotal, rhs.subtotal),
+			tax: add(self.tax, rhs.tax),
+			total: add(self.total, rhs.total),
+		}
+	}
+}
+
+/// The terms under which an invoice is issued, besides the `hourly_rate` of its `Job`.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Terms
+{
+	/// The [`Discount`] on the invoice, if any.
+	pub discount: Option<Discount>,
+
+	/// The date that the invoice must be paid by, if there are payment terms.
+	pub due: Option<DateTime<Utc>>,
+
+	/// The [number](crate::numbering) of the invoice, if it has been assigned one.
+	pub number: Option<String>,
+
+	/// The percentage of tax on the invoice (e.g. 8.25 for 8.25%), if any.
+	pub tax: Option<Decimal>,
+}
+
+impl Terms
+{
+	/// Whether these [`Terms`] change the [`Charges`] on an invoice, so that they should be
+	/// listed separately.
+	pub const fn adjusts(&self) -> bool
+	{
+		self.discount.is_some() || self.tax.is_some()
+	}
+
+	/// How the invoice of the `job` is referred to, which is its [`number`](Terms::number) or else
+	/// the `id` of the `job`.
+	pub fn reference(&self, job: &Job) -> String
+	{
+		self.number.clone().unwrap_or_else(|| fmt::id_num(job.id))
+	}
+
+	/// The title of the invoice of the `job`.
+	pub fn title(&self, job: &Job) -> String
+	{
+		format!("Invoice {}", self.reference(job))
+	}
+
+	/// The [`Charges`] of an invoice with some `subtotal`.
+	///
+	/// Fixed discounts must already be [exchanged](Exchange) into the currency of the `subtotal`
+	/// (see [`exchange::terms`](crate::exchange::terms)), and can reduce it no further than zero.
+	/// Discounts and taxes are rounded to the nearest hundredth.
+	pub fn charges(&self, subtotal: Money) -> Charges
+	{
+		let money = |amount| Money { amount, ..subtotal };
+		let discount = match self.discount
+		{
+			Some(Discount::Fixed(m)) =>
+			{
+				debug_assert_eq!(m.currency, subtotal.currency, "fixed discounts must be exchanged first");
+				m.amount.min(subtotal.amount)
+			},
+			Some(Discount::Percent(p)) => (subtotal.amount * p / Decimal::ONE_HUNDRED).round_dp(2),
+			None => Decimal::ZERO,
+		};
+
+		let discounted = subtotal.amount - discount;
+		let tax = self.tax.map_or(Decimal::ZERO, |t| (discounted * t / Decimal::ONE_HUNDRED).round_dp(2));
+
+		Charges { discount: money(discount), subtotal, tax: money(tax), total: money(discounted + tax) }
+	}
+}
+
+impl Exchange for Terms
+{
+	fn exchange(self, currency: Currency, rates: &ExchangeRates) -> Self
+	{
+		Self { discount: self.discount.map(|d| d.exchange(currency, rates)), ..self }
+	}
+
+	fn exchange_mut(&mut self, currency: Currency, rates: &ExchangeRates)
+	{
+		if let Some(d) = self.discount.as_mut()
+		{
+			d.exchange_mut(currency, rates);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use money2::Decimal;
+	use pretty_assertions::assert_eq;
+	use winvoice_schema::{Currency, Money};
+
+	use super::{Discount, Error, Terms};
+
+	#[test]
+	fn charges()
+	{
+		let subtotal = Money::new(20_000, 2, Currency::Usd);
+		let usd = |amount| Money::new(amount, 2, Currency::Usd);
+
+		let mut terms = Terms::default();
+		assert!(!terms.adjusts());
+		assert_eq!(terms.charges(subtotal).total, subtotal);
+
+		terms.discount = Some("12.5%".parse().unwrap());
+		terms.tax = Some(Decimal::new(825, 2));
+		let charges = terms.charges(subtotal);
+		assert_eq!(charges.discount, usd(25_00));
+		assert_eq!(charges.tax, usd(14_44));
+		assert_eq!(charges.total, usd(18_944));
+
+		terms.discount = Some("250.00 USD".parse().unwrap());
+		let charges = terms.charges(subtotal);
+		assert_eq!(charges.discount, subtotal);
+		assert_eq!(charges.total, usd(0));
+
+		let combined = terms.charges(subtotal) + Terms::default().charges(usd(5_00));
+		assert_eq!(combined.subtotal, usd(20_500));
+		assert_eq!(combined.total, usd(5_00));
+
+		assert_eq!(" 10 %".parse::<Discount>().unwrap(), Discount::Percent(Decimal::TEN));
+		assert_eq!(Discount::Fixed(usd(5_00)).to_string(), "5.00 USD");
+		assert!("ten percent".parse::<Discount>().is_err());
+		assert!(matches!("-5.00 USD".parse::<Discount>(), Err(Error::Negative)));
+		assert!(matches!("-1%".parse::<Discount>(), Err(Error::Negative)));
+		assert!(matches!("100.5%".parse::<Discount>(), Err(Error::OverOneHundred)));
+		assert_eq!("100%".parse::<Discount>().unwrap(), Discount::Percent(Decimal::ONE_HUNDRED));
+	}
+}
@@ -0,0 +1,218 @@
+use core::iter;
+
+use clap::ValueEnum;
+use money2::Decimal;
+use serde::Serialize;
+use winvoice_schema::{
+	chrono::{DateTime, Local, SecondsFormat, Utc},
+	Id,
+	Timesheet,
+};
+
+use crate::{utils::Billable, DynResult};
+
+/// A format which `Timesheet`s can be exported to, for clients who want the time data rather than
+/// an invoice.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, ValueEnum)]
+pub enum TimesheetFormat
+{
+	/// Comma-separated values, with one row per `Timesheet` (and `Expense`).
+	Csv,
+}
+
+/// A row of exported `Timesheet`s.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+struct Row<'timesheet>
+{
+	/// The id of the `Timesheet`, which is the same for a `Timesheet` and its `Expense`s.
+	timesheet: Id,
+
+	/// Whether this row is for a "timesheet" or an "expense".
+	kind: &'static str,
+
+	/// The id of the `Job` which the work was done for.
+	job: Id,
+
+	/// The name of the client of the `Job`.
+	client: &'timesheet str,
+
+	/// The name of the `Employee` who did the work.
+	employee: &'timesheet str,
+
+	/// When the work began, in RFC 3339 (see [`timestamp`]).
+	begin: String,
+
+	/// When the work ended (if it has), in RFC 3339 (see [`timestamp`]).
+	end: String,
+
+	/// The hours worked, rounded up to the `increment` of the `Job`.
+	hours: Option<Decimal>,
+
+	/// The hourly rate of the `Job`.
+	rate: Option<Decimal>,
+
+	/// What the work (or expense) cost.
+	amount: Decimal,
+
+	/// The currency of the `rate` and `amount`.
+	currency: String,
+
+	/// The work notes of the `Timesheet`, or the category and description of the `Expense`.
+	notes: String,
+}
+
+impl TimesheetFormat
+{
+	/// The extension of files in this [`TimesheetFormat`].
+	pub const fn extension(self) -> &'static str
+	{
+		match self
+		{
+			Self::Csv => "csv",
+		}
+	}
+
+	/// Export the `timesheets`, along with a row for each of their `expenses` when specified.
+	///
+	/// # Errors
+	///
+	/// If the `timesheets` could not be written.
+	pub fn export(self, timesheets: &[Timesheet], expenses: bool) -> DynResult<Vec<u8>>
+	{
+		let rows = timesheets.iter().flat_map(|t| {
+			let labor = t.labor();
+			let row = Row {
+				timesheet: t.id,
+				kind: "timesheet",
+				job: t.job.id,
+				client: &t.job.client.name,
+				employee: &t.employee.name,
+				begin: timestamp(t.time_begin),
+				end: t.time_end.map(timestamp).unwrap_or_default(),
+				hours: Some(t.hours()),
+				rate: Some(t.job.invoice.hourly_rate.amount),
+				amount: labor.amount,
+				currency: labor.currency.to_string(),
+				notes: t.work_notes.clone(),
+			};
+
+			let expense_rows: Vec<_> = t
+				.expenses
+				.iter()
+				.filter(|_| expenses)
+				.map(|x| Row {
+					kind: "expense",
+					hours: None,
+					rate: None,
+					amount: x.cost.amount,
+					currency: x.cost.currency.to_string(),
+					notes: format!("{}: {}", x.category, x.description),
+					..row.clone()
+				})
+				.collect();
+
+			iter::once(row).chain(expense_rows)
+		});
+
+		Ok(match self
+		{
+			Self::Csv =>
+			{
+				let mut writer = csv::Writer::from_writer(Vec::new());
+				rows.into_iter().try_for_each(|r| writer.serialize(r))?;
+				writer.into_inner()?
+			},
+		})
+	}
+}
+
+/// Display a date in RFC 3339, in the [`Local`] timezone and with its offset (e.g.
+/// "2022-01-01T14:00:00+01:00"), so that the accounting systems of clients in other timezones (and
+/// `winvoice import`) read it as the same instant.
+fn timestamp(d: DateTime<Utc>) -> String
+{
+	d.with_timezone(&Local).to_rfc3339_opts(SecondsFormat::Secs, false)
+}
+
+#[cfg(test)]
+mod tests
+{
+	use core::time::Duration;
+
+	use pretty_assertions::assert_eq;
+	use winvoice_schema::{
+		chrono::{Local, TimeZone, Utc},
+		Currency,
+		Employee,
+		Expense,
+		Invoice,
+		Job,
+		Location,
+		Money,
+		Organization,
+		Timesheet,
+	};
+
+	use super::TimesheetFormat;
+	use crate::import;
+
+	#[test]
+	fn export()
+	{
+		let job = Job {
+			client: Organization {
+				id: 1,
+				location: Location { id: 0, name: "Earth".into(), outer: None },
+				name: "Bar".into(),
+			},
+			date_close: None,
+			date_open: Utc.ymd(2022, 1, 1).and_hms(0, 0, 0),
+			id: 3,
+			increment: Duration::from_secs(15 * 60),
+			invoice: Invoice { date: None, hourly_rate: Money::new(20_00, 2, Currency::Usd) },
+			notes: String::new(),
+			objectives: "Write code".into(),
+		};
+
+		let local = |hour, minute| Local.ymd(2022, 1, 1).and_hms(hour, minute, 0).with_timezone(&Utc);
+		let timesheets = [Timesheet {
+			id: 7,
+			employee: Employee { id: 0, name: "Bob".into(), status: "Contractor".into(), title: "Dev".into() },
+			expenses: vec![Expense {
+				id: 0,
+				category: "Food".into(),
+				cost: Money::new(5_00, 2, Currency::Usd),
+				description: "Lunch, with \"client\"".into(),
+				timesheet_id: 7,
+			}],
+			job,
+			time_begin: local(9, 0),
+			time_end: Some(local(10, 20)),
+			work_notes: "Fixed bugs".into(),
+		}];
+
+		let rfc3339 = |hour, minute| Local.ymd(2022, 1, 1).and_hms(hour, minute, 0).format("%Y-%m-%dT%H:%M:%S%:z");
+		let (begin, end) = (rfc3339(9, 0), rfc3339(10, 20));
+
+		let header = "timesheet,kind,job,client,employee,begin,end,hours,rate,amount,currency,notes\n";
+		let timesheet = format!("7,timesheet,3,Bar,Bob,{begin},{end},1.50,20.00,30.00,USD,Fixed bugs\n");
+		assert_eq!(
+			String::from_utf8(TimesheetFormat::Csv.export(&timesheets, false).unwrap()).unwrap(),
+			format!("{header}{timesheet}"),
+		);
+
+		let exported = TimesheetFormat::Csv.export(&timesheets, true).unwrap();
+		assert_eq!(
+			String::from_utf8(exported.clone()).unwrap(),
+			format!(
+				"{header}{timesheet}7,expense,3,Bar,Bob,{begin},{end},,,5.00,USD,\"Food: Lunch, with \
+				 \"\"client\"\"\"\n"
+			),
+		);
+
+		let (entries, invalid) = import::csv(exported.as_slice());
+		assert_eq!(invalid, []);
+		assert_eq!(entries.len(), 1);
+		assert_eq!((entries[0].time_begin, entries[0].time_end), (local(9, 0), Some(local(10, 20))));
+	}
+}
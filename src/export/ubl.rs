@@ -0,0 +1,419 @@
+use core::iter;
+use std::collections::BTreeMap;
+
+use money2::Decimal;
+use winvoice_schema::{
+	chrono::{DateTime, Local, Utc},
+	Contact,
+	Currency,
+	Money,
+	Organization,
+	Timesheet,
+};
+
+use super::{html::escape, Section};
+use crate::{fmt, utils::Billable};
+
+/// The namespaces of the elements in a UBL 2.1 invoice.
+const NAMESPACES: &str = concat!(
+	r#"xmlns="urn:oasis:names:specification:ubl:schema:xsd:Invoice-2" "#,
+	r#"xmlns:cac="urn:oasis:names:specification:ubl:schema:xsd:CommonAggregateComponents-2" "#,
+	r#"xmlns:cbc="urn:oasis:names:specification:ubl:schema:xsd:CommonBasicComponents-2""#,
+);
+
+/// An element of some `amount` of money, which lists its currency as an attribute.
+fn amount(tag: &str, amount: Money) -> String
+{
+	format!(r#"<cbc:{tag} currencyID="{}">{:.2}</cbc:{tag}>"#, amount.currency, amount.amount)
+}
+
+/// Display a `date` in the [`Local`] timezone, as UBL expects.
+fn date(d: DateTime<Utc>) -> String
+{
+	d.with_timezone(&Local).format("%Y-%m-%d").to_string()
+}
+
+/// An element with some `tag` which contains some `content`.
+fn element(tag: &str, content: impl AsRef<str>) -> String
+{
+	format!("<{tag}>{}</{tag}>", content.as_ref())
+}
+
+/// Export the `sections` of an invoice from the `employer` to the `client` as a UBL 2.1 invoice.
+///
+/// See [`Format::export`](super::Format::export) for more information.
+pub(super) fn export(
+	client: &Organization,
+	sections: &[Section<'_>],
+	contact_info: &[Contact],
+	employer: &Organization,
+) -> String
+{
+	let currency = sections.first().map_or_else(Currency::default, |s| s.job.invoice.hourly_rate.currency);
+	let zero = Money { amount: Decimal::ZERO, currency };
+	let charges = sections.iter().map(Section::charges).reduce(|lhs, rhs| lhs + rhs);
+
+	let id = sections.iter().map(|s| s.terms.reference(s.job)).collect::<Vec<_>>().join(", ");
+	let issued = sections.iter().filter_map(|s| s.job.invoice.date.map(|d| d.issued)).max().unwrap_or_else(Utc::now);
+	let due =
+		sections.iter().filter(|s| s.job.invoice.date.and_then(|d| d.paid).is_none()).filter_map(|s| s.terms.due).min();
+
+	let notes: String = sections.iter().map(|s| element("cbc:Note", escape(&s.job.objectives))).collect();
+	let period = element(
+		"cac:InvoicePeriod",
+		[
+			sections.iter().map(|s| s.job.date_open).min().map(|d| element("cbc:StartDate", date(d))),
+			sections.iter().filter_map(|s| s.job.date_close).max().map(|d| element("cbc:EndDate", date(d))),
+		]
+		.into_iter()
+		.flatten()
+		.collect::<String>(),
+	);
+
+	let allowances: String = sections
+		.iter()
+		.filter(|s| s.terms.discount.is_some_and(|d| !d.is_zero()))
+		.map(|s| {
+			let charges = s.charges();
+			element(
+				"cac:AllowanceCharge",
+				format!(
+					"{}{}{}{}{}",
+					element("cbc:ChargeIndicator", "false"),
+					element("cbc:AllowanceChargeReason", escape(format!("Discount on Job {}", fmt::id_num(s.job.id)))),
+					amount("Amount", charges.discount),
+					amount("BaseAmount", charges.subtotal),
+					tax_category("cac:TaxCategory", s.terms.tax),
+				),
+			)
+		})
+		.collect();
+
+	let subtotals = sections.iter().fold(BTreeMap::<_, (Money, Money)>::new(), |mut subtotals, s| {
+		let charges = s.charges();
+		let (taxable, tax) = subtotals.entry(s.terms.tax).or_insert((zero, zero));
+		taxable.amount += charges.subtotal.amount - charges.discount.amount;
+		tax.amount += charges.tax.amount;
+		subtotals
+	});
+
+	let tax_total = element(
+		"cac:TaxTotal",
+		format!(
+			"{}{}",
+			amount("TaxAmount", charges.map_or(zero, |c| c.tax)),
+			subtotals
+				.into_iter()
+				.map(|(rate, (taxable, tax))| {
+					element(
+						"cac:TaxSubtotal",
+						format!(
+							"{}{}{}",
+							amount("TaxableAmount", taxable),
+							amount("TaxAmount", tax),
+							tax_category("cac:TaxCategory", rate),
+						),
+					)
+				})
+				.collect::<String>(),
+		),
+	);
+
+	let monetary_total = element(
+		"cac:LegalMonetaryTotal",
+		charges.map_or_else(String::new, |c| {
+			[
+				amount("LineExtensionAmount", c.subtotal),
+				amount("TaxExclusiveAmount", Money { amount: c.subtotal.amount - c.discount.amount, ..c.subtotal }),
+				amount("TaxInclusiveAmount", c.total),
+				amount("AllowanceTotalAmount", c.discount),
+				amount("PayableAmount", c.total),
+			]
+			.concat()
+		}),
+	);
+
+	let lines: String = sections
+		.iter()
+		.flat_map(|s| s.timesheets.iter().map(move |t| (s, t)))
+		.flat_map(|(s, t)| timesheet_lines(s, t))
+		.enumerate()
+		.map(|(i, line)| element("cac:InvoiceLine", format!("{}{line}", element("cbc:ID", (i + 1).to_string()))))
+		.collect();
+
+	format!(
+		r#"<?xml version="1.0" encoding="UTF-8"?>
+<Invoice {NAMESPACES}>
+{version}
+{customization}
+{id}
+{issued}
+{due}{type_code}
+{notes}
+{currency}
+{period}
+<cac:AccountingSupplierParty>{supplier}</cac:AccountingSupplierParty>
+<cac:AccountingCustomerParty>{customer}</cac:AccountingCustomerParty>
+{allowances}{tax_total}
+{monetary_total}
+{lines}
+</Invoice>
+"#,
+		currency = element("cbc:DocumentCurrencyCode", currency.to_string()),
+		customer = party(client, &[]),
+		customization = element("cbc:CustomizationID", "urn:cen.eu:en16931:2017"),
+		due = due.map_or_else(String::new, |d| format!("{}\n", element("cbc:DueDate", date(d)))),
+		id = element("cbc:ID", escape(id)),
+		issued = element("cbc:IssueDate", date(issued)),
+		supplier = party(employer, contact_info),
+		type_code = element("cbc:InvoiceTypeCode", "380"),
+		version = element("cbc:UBLVersionID", "2.1"),
+	)
+}
+
+/// Describe an `organization` which is party to an invoice, along with its `contact_info`.
+fn party(organization: &Organization, contact_info: &[Contact]) -> String
+{
+	let mut locations: Vec<_> = iter::successors(Some(&organization.location), |l| l.outer.as_deref()).collect();
+	let country = locations.pop().map(|l| element("cac:Country", element("cbc:Name", escape(&l.name))));
+	let address = locations
+		.into_iter()
+		.map(|l| element("cac:AddressLine", element("cbc:Line", escape(&l.name))))
+		.chain(country)
+		.collect::<String>();
+
+	let contact = [
+		contact_info.iter().find_map(|c| c.kind.phone()).map(|p| element("cbc:Telephone", escape(p))),
+		contact_info.iter().find_map(|c| c.kind.email()).map(|e| element("cbc:ElectronicMail", escape(e))),
+	]
+	.into_iter()
+	.flatten()
+	.collect::<String>();
+
+	let name = escape(&organization.name);
+	element(
+		"cac:Party",
+		format!(
+			"{}{}{}{}",
+			element("cac:PartyName", element("cbc:Name", &name)),
+			element("cac:PostalAddress", address),
+			element("cac:PartyLegalEntity", element("cbc:RegistrationName", &name)),
+			match contact.is_empty()
+			{
+				false => element("cac:Contact", contact),
+				true => String::new(),
+			},
+		),
+	)
+}
+
+/// Describe the category of tax which has some `rate`, as an element with some `tag`.
+///
+/// Invoices without a tax rate are not subject to tax.
+fn tax_category(tag: &str, rate: Option<Decimal>) -> String
+{
+	let scheme = element("cac:TaxScheme", element("cbc:ID", "VAT"));
+	element(
+		tag,
+		rate.map_or_else(
+			|| format!("{}{scheme}", element("cbc:ID", "O")),
+			|r| format!("{}{}{scheme}", element("cbc:ID", "S"), element("cbc:Percent", r.normalize().to_string())),
+		),
+	)
+}
+
+/// The contents of the invoice lines for the labor and expenses of a `timesheet` in some
+/// `section`, without their IDs.
+fn timesheet_lines(section: &Section<'_>, timesheet: &Timesheet) -> Vec<String>
+{
+	let category = tax_category("cac:ClassifiedTaxCategory", section.terms.tax);
+	let period = element(
+		"cac:InvoicePeriod",
+		iter::once(element("cbc:StartDate", date(timesheet.time_begin)))
+			.chain(timesheet.time_end.map(|d| element("cbc:EndDate", date(d))))
+			.collect::<String>(),
+	);
+
+	let labor = format!(
+		r#"<cbc:InvoicedQuantity unitCode="HUR">{hours:.2}</cbc:InvoicedQuantity>{amount}{period}{item}{price}"#,
+		amount = amount("LineExtensionAmount", timesheet.labor()),
+		hours = timesheet.hours(),
+		item = element(
+			"cac:Item",
+			format!(
+				"{}{}{category}",
+				element("cbc:Description", escape(&timesheet.work_notes)),
+				element(
+					"cbc:Name",
+					escape(format!("Job {} by {}", fmt::id_num(section.job.id), timesheet.employee.name))
+				),
+			),
+		),
+		price = element("cac:Price", amount("PriceAmount", section.job.invoice.hourly_rate)),
+	);
+
+	iter::once(labor)
+		.chain(timesheet.expenses.iter().map(|x| {
+			format!(
+				r#"<cbc:InvoicedQuantity unitCode="C62">1</cbc:InvoicedQuantity>{}{period}{}{}"#,
+				amount("LineExtensionAmount", x.cost),
+				element(
+					"cac:Item",
+					format!(
+						"{}{}{category}",
+						element("cbc:Description", escape(&x.description)),
+						element("cbc:Name", escape(&x.category)),
+					),
+				),
+				element("cac:Price", amount("PriceAmount", x.cost)),
+			)
+		}))
+		.collect()
+}
+
+#[cfg(test)]
+mod tests
+{
+	use core::time::Duration;
+	use std::{
+		io::{ErrorKind, Write},
+		path::Path,
+		process::{Command, Stdio},
+	};
+
+	use money2::Decimal;
+	use winvoice_schema::{
+		chrono::{TimeZone, Utc},
+		Contact,
+		ContactKind,
+		Currency,
+		Employee,
+		Expense,
+		Invoice,
+		InvoiceDate,
+		Job,
+		Location,
+		Money,
+		Organization,
+		Timesheet,
+	};
+
+	use crate::export::{Discount, Section, Terms};
+
+	/// Assert that the `xml` is a valid `Invoice` according to the UBL 2.1 schemas in
+	/// `src/export/ubl/xsd`, using `xmllint`.
+	///
+	/// The validation is skipped (with a message) if the schemas have not been downloaded, or
+	/// `xmllint` is not installed. See `src/export/ubl/xsd/README.md`.
+	fn assert_valid(xml: &str)
+	{
+		let schema = concat!(env!("CARGO_MANIFEST_DIR"), "/src/export/ubl/xsd/maindoc/UBL-Invoice-2.1.xsd");
+		if !Path::new(schema).is_file()
+		{
+			eprintln!("Skipping UBL schema validation: {schema} is missing (see src/export/ubl/xsd/README.md)");
+			return;
+		}
+
+		let spawned = Command::new("xmllint")
+			.args(["--noout", "--nonet", "--schema", schema, "-"])
+			.stdin(Stdio::piped())
+			.stderr(Stdio::piped())
+			.spawn();
+
+		let mut xmllint = match spawned
+		{
+			Ok(x) => x,
+			Err(e) if e.kind() == ErrorKind::NotFound =>
+			{
+				eprintln!("Skipping UBL schema validation: `xmllint` is not installed");
+				return;
+			},
+			Err(e) => panic!("`xmllint` could not be run: {e}"),
+		};
+
+		xmllint.stdin.take().unwrap().write_all(xml.as_bytes()).unwrap();
+		let output = xmllint.wait_with_output().unwrap();
+		assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+	}
+
+	#[test]
+	fn export()
+	{
+		let location = Location {
+			id: 1,
+			name: "Paris".into(),
+			outer: Some(Location { id: 0, name: "France".into(), outer: None }.into()),
+		};
+		let employer = Organization { id: 0, location: location.clone(), name: "Foo & Sons".into() };
+		let job = Job {
+			client: Organization { id: 1, location, name: "Bar".into() },
+			date_close: Some(Utc.ymd(2022, 1, 2).and_hms(0, 0, 0)),
+			date_open: Utc.ymd(2022, 1, 1).and_hms(0, 0, 0),
+			id: 3,
+			increment: Duration::from_secs(15 * 60),
+			invoice: Invoice {
+				date: Some(InvoiceDate { issued: Utc.ymd(2022, 1, 3).and_hms(12, 0, 0), paid: None }),
+				hourly_rate: Money::new(20_00, 2, Currency::Eur),
+			},
+			notes: String::new(),
+			objectives: "Write <code>".into(),
+		};
+
+		let timesheets = [Timesheet {
+			id: 0,
+			employee: Employee { id: 0, name: "Bob".into(), status: "Contractor".into(), title: "Dev".into() },
+			expenses: vec![Expense {
+				id: 0,
+				category: "Food".into(),
+				cost: Money::new(5_00, 2, Currency::Eur),
+				description: "Lunch".into(),
+				timesheet_id: 0,
+			}],
+			job: job.clone(),
+			time_begin: Utc.ymd(2022, 1, 1).and_hms(9, 0, 0),
+			time_end: Some(Utc.ymd(2022, 1, 1).and_hms(10, 20, 0)),
+			work_notes: "Fixed bugs".into(),
+		}];
+
+		let contact_info = [Contact { kind: ContactKind::Email("foo@bar.io".into()), label: "Email".into() }];
+		let terms = Terms {
+			discount: Some(Discount::Percent(Decimal::TEN)),
+			number: Some("INV-2022-0001".into()),
+			tax: Some(Decimal::new(20, 0)),
+			..Default::default()
+		};
+
+		let section = Section { job: &job, terms: &terms, timesheets: &timesheets };
+		let exported = super::export(&job.client, &[section], &contact_info, &employer);
+
+		assert!(exported.starts_with(r#"<?xml version="1.0" encoding="UTF-8"?>"#));
+		assert_valid(&exported);
+
+		assert!(exported.contains("<cbc:ID>INV-2022-0001</cbc:ID>"));
+		assert!(exported.contains("<cbc:IssueDate>2022-01-03</cbc:IssueDate>"));
+		assert!(exported.contains("<cbc:Note>Write &lt;code&gt;</cbc:Note>"));
+		assert!(exported.contains("<cbc:DocumentCurrencyCode>EUR</cbc:DocumentCurrencyCode>"));
+		assert!(exported.contains("<cac:PartyName><cbc:Name>Foo &amp; Sons</cbc:Name></cac:PartyName>"));
+		assert!(exported.contains(
+			"<cac:PostalAddress><cac:AddressLine><cbc:Line>Paris</cbc:Line></cac:AddressLine><cac:Country><cbc:\
+			 Name>France</cbc:Name></cac:Country></cac:PostalAddress>"
+		));
+		assert!(exported.contains("<cac:Contact><cbc:ElectronicMail>foo@bar.io</cbc:ElectronicMail></cac:Contact>"));
+
+		// 1.5h at 20.00 EUR/h plus 5.00 EUR of expenses, less 10%, plus 20% tax.
+		assert!(exported.contains(r#"<cbc:InvoicedQuantity unitCode="HUR">1.50</cbc:InvoicedQuantity>"#));
+		assert!(exported.contains(r#"<cbc:Amount currencyID="EUR">3.50</cbc:Amount>"#));
+		assert!(exported.contains(r#"<cbc:TaxableAmount currencyID="EUR">31.50</cbc:TaxableAmount>"#));
+		assert!(exported.contains(r#"<cbc:TaxAmount currencyID="EUR">6.30</cbc:TaxAmount>"#));
+		assert!(exported.contains(r#"<cbc:LineExtensionAmount currencyID="EUR">35.00</cbc:LineExtensionAmount>"#));
+		assert!(exported.contains(r#"<cbc:PayableAmount currencyID="EUR">37.80</cbc:PayableAmount>"#));
+		assert!(exported.contains("<cbc:ID>S</cbc:ID><cbc:Percent>20</cbc:Percent>"));
+		assert_eq!(exported.matches("<cac:InvoiceLine>").count(), 2);
+
+		let exported = super::export(&job.client, &[section, section], &[], &employer);
+		assert_valid(&exported);
+		assert!(exported.contains("<cbc:ID>INV-2022-0001, INV-2022-0001</cbc:ID>"));
+		assert!(exported.contains(r#"<cbc:PayableAmount currencyID="EUR">75.60</cbc:PayableAmount>"#));
+		assert!(exported.contains("<cac:InvoiceLine><cbc:ID>4</cbc:ID>"));
+	}
+}
@@ -1,7 +1,10 @@
 //! Tools to format data.
 
+mod output_format;
+
 use core::{any, fmt::Display};
 
+pub(crate) use output_format::OutputFormat;
 use winvoice_schema::Id;
 
 /// Return "№{id}" (without quotes).
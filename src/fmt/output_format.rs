@@ -0,0 +1,250 @@
+use core::fmt::Write;
+
+use clap::ValueEnum;
+use serde::Serialize;
+use serde_json::Value;
+use serde_yaml as yaml;
+
+use crate::DynResult;
+
+/// A machine-readable (or terminal-friendly) representation of some serializable values.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, ValueEnum)]
+pub enum OutputFormat
+{
+	/// Comma-separated values, with nested fields flattened into dot-separated columns (e.g.
+	/// `client.location.name`).
+	Csv,
+
+	/// A JSON array.
+	Json,
+
+	/// An aligned table, with nested fields flattened the same as `csv`.
+	Table,
+
+	/// A YAML sequence.
+	Yaml,
+}
+
+impl OutputFormat
+{
+	/// Render the `values` in this format.
+	///
+	/// # Errors
+	///
+	/// When the `values` cannot be serialized.
+	pub fn render<T>(self, values: &[T]) -> DynResult<String>
+	where
+		T: Serialize,
+	{
+		Ok(match self
+		{
+			Self::Csv =>
+			{
+				let (header, rows) = flattened(values)?;
+				let mut writer = csv::Writer::from_writer(Vec::new());
+				writer.write_record(&header)?;
+				rows.iter().try_for_each(|r| writer.write_record(r))?;
+				String::from_utf8(writer.into_inner()?)?
+			},
+			Self::Json => serde_json::to_string_pretty(values)?,
+			Self::Table =>
+			{
+				let (header, rows) = flattened(values)?;
+				table(&header, &rows)
+			},
+			Self::Yaml => yaml::to_string(values)?,
+		})
+	}
+}
+
+/// Convert each of the `values` into a row of columns, where each nested field is flattened into
+/// its own column. Returns the names of the columns (which every row has, even if it was missing the
+/// field), and then the rows.
+fn flattened<T>(values: &[T]) -> serde_json::Result<(Vec<String>, Vec<Vec<String>>)>
+where
+	T: Serialize,
+{
+	/// Push every leaf of `value` onto `columns`, prefixing each key with the `path` to it.
+	fn flatten(path: String, value: Value, columns: &mut Vec<(String, String)>)
+	{
+		let join = |key: &dyn ToString| match path.is_empty()
+		{
+			false => format!("{path}.{}", key.to_string()),
+			true => key.to_string(),
+		};
+
+		match value
+		{
+			Value::Array(array) => array.into_iter().enumerate().for_each(|(i, v)| flatten(join(&i), v, columns)),
+			Value::Bool(b) => columns.push((path, b.to_string())),
+			Value::Null => columns.push((path, String::new())),
+			Value::Number(n) => columns.push((path, n.to_string())),
+			Value::Object(object) => object.into_iter().for_each(|(k, v)| flatten(join(&k), v, columns)),
+			Value::String(s) => columns.push((path, s)),
+		}
+	}
+
+	let mut header = Vec::<String>::new();
+	let flattened = values
+		.iter()
+		.map(|v| {
+			let mut columns = Vec::new();
+			flatten(String::new(), serde_json::to_value(v)?, &mut columns);
+			for (name, _) in &columns
+			{
+				if header.contains(name)
+				{
+					continue;
+				}
+
+				// NOTE: columns which only some rows have (e.g. the fields of an `Option` which
+				//       was `null` in an earlier row) go after their closest relatives, so the
+				//       order of the columns does not depend on the order of the rows.
+				let index = name
+					.match_indices('.')
+					.rev()
+					.map(|(i, _)| &name[..i])
+					.find_map(|parent| header.iter().rposition(|n| n == parent || n.starts_with(&format!("{parent}."))))
+					.map_or(header.len(), |i| i + 1);
+
+				header.insert(index, name.clone());
+			}
+
+			Ok(columns)
+		})
+		.collect::<serde_json::Result<Vec<_>>>()?;
+
+	// NOTE: a `null` nested value (e.g. `Option<Location>`) should not get its own column when
+	//       other rows have columns for its fields.
+	let nested: Vec<_> =
+		header.iter().filter_map(|name| name.rsplit_once('.').map(|(parent, _)| parent.to_owned())).collect();
+	header.retain(|name| !nested.iter().any(|n| n == name || n.starts_with(&format!("{name}."))));
+
+	let rows = flattened
+		.into_iter()
+		.map(|mut columns| {
+			header
+				.iter()
+				.map(|name| {
+					columns.iter().position(|(n, _)| n == name).map(|i| columns.swap_remove(i).1).unwrap_or_default()
+				})
+				.collect()
+		})
+		.collect();
+
+	Ok((header, rows))
+}
+
+/// Align the `header` and `rows` into a table.
+fn table(header: &[String], rows: &[Vec<String>]) -> String
+{
+	/// The separator between columns.
+	const SEPARATOR: &str = "  ";
+
+	let escaped: Vec<Vec<String>> =
+		rows.iter().map(|r| r.iter().map(|c| c.escape_debug().to_string()).collect()).collect();
+	let widths: Vec<usize> = header
+		.iter()
+		.enumerate()
+		.map(|(i, name)| escaped.iter().map(|r| r[i].chars().count()).chain([name.chars().count()]).max().unwrap_or(0))
+		.collect();
+
+	let mut output = String::new();
+	let mut write_row = |row: &mut dyn Iterator<Item = &String>| {
+		let line = row.zip(&widths).fold(String::new(), |mut line, (cell, width)| {
+			write!(line, "{cell:width$}{SEPARATOR}").expect("writing to a `String` should not fail");
+			line
+		});
+
+		output.push_str(line.trim_end());
+		output.push('\n');
+	};
+
+	write_row(&mut header.iter());
+	write_row(&mut widths.iter().map(|w| "-".repeat(*w)).collect::<Vec<_>>().iter());
+	escaped.iter().for_each(|r| write_row(&mut r.iter()));
+
+	output
+}
+
+#[cfg(test)]
+mod tests
+{
+	use pretty_assertions::assert_eq;
+	use serde::Serialize;
+
+	use super::OutputFormat;
+
+	#[derive(Serialize)]
+	struct Outer
+	{
+		id: u8,
+		inner: Option<Inner>,
+		notes: &'static str,
+	}
+
+	#[derive(Serialize)]
+	struct Inner
+	{
+		name: &'static str,
+		tags: Vec<&'static str>,
+	}
+
+	fn values() -> [Outer; 2]
+	{
+		[Outer { id: 1, inner: Some(Inner { name: "foo", tags: vec!["a", "b"] }), notes: "one\ntwo" }, Outer {
+			id: 20,
+			inner: None,
+			notes: "three, four",
+		}]
+	}
+
+	#[test]
+	fn csv()
+	{
+		assert_eq!(
+			OutputFormat::Csv.render(&values()).unwrap(),
+			"id,inner.name,inner.tags.0,inner.tags.1,notes
+1,foo,a,b,\"one\ntwo\"
+20,,,,\"three, four\"
+"
+		);
+	}
+
+	#[test]
+	fn csv_null_first()
+	{
+		let mut values = values();
+		values.reverse();
+		values[1].inner.as_mut().unwrap().tags.push("c");
+
+		assert_eq!(
+			OutputFormat::Csv.render(&values).unwrap(),
+			"id,inner.name,inner.tags.0,inner.tags.1,inner.tags.2,notes
+20,,,,,\"three, four\"
+1,foo,a,b,c,\"one\ntwo\"
+"
+		);
+	}
+
+	#[test]
+	fn json()
+	{
+		let rendered = OutputFormat::Json.render(&values()).unwrap();
+		let parsed: serde_json::Value = serde_json::from_str(&rendered).unwrap();
+		assert_eq!(parsed, serde_json::to_value(values()).unwrap());
+	}
+
+	#[test]
+	fn table()
+	{
+		assert_eq!(
+			OutputFormat::Table.render(&values()).unwrap(),
+			"id  inner.name  inner.tags.0  inner.tags.1  notes
+--  ----------  ------------  ------------  -----------
+1   foo         a             b             one\\ntwo
+20                                          three, four
+"
+		);
+	}
+}
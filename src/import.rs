@@ -0,0 +1,110 @@
+//! Formats which `Timesheet`s can be imported from, such as the spreadsheets of other programs.
+//!
+//! Each format is parsed into [`Entry`]s, which refer to the `Employee` and `Job` of the
+//! `Timesheet` they describe by `id` or name, so that they can be resolved before anything is
+//! created. Programs which refer to work by their own names (e.g. tags) are translated using a
+//! [`Mapping`].
+
+mod csv;
+mod mapping;
+mod timewarrior;
+mod toggl;
+
+pub use csv::csv;
+pub use mapping::{Mapping, Mappings};
+use thiserror::Error;
+pub use timewarrior::timewarrior;
+pub use toggl::toggl;
+use winvoice_schema::chrono::{DateTime, NaiveDateTime, Utc};
+
+use crate::utils;
+
+/// The formats of dates and times in the [`Local`](winvoice_schema::chrono::Local) timezone which
+/// can be imported, besides the RFC 3339-like format which is used elsewhere (e.g.
+/// "2022-01-01T14:00:00").
+const DATE_TIME_FORMATS: [&str; 2] = ["%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M"];
+
+/// A `Timesheet` which is to be imported.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Entry
+{
+	/// The `id` or `name` of the `Employee` who did the work.
+	pub employee: String,
+
+	/// The `id` or `objectives` of the `Job` which the work was done for.
+	pub job: String,
+
+	/// Where the entry came from in the imported file (e.g. its line number).
+	pub line: u64,
+
+	/// When the work began.
+	pub time_begin: DateTime<Utc>,
+
+	/// When the work ended, if it has.
+	pub time_end: Option<DateTime<Utc>>,
+
+	/// Notes about the work which was done.
+	pub work_notes: String,
+}
+
+/// An [`Error`](std::error::Error) indicating that some part of an imported file is not valid.
+#[derive(Clone, Debug, Eq, Error, Hash, Ord, PartialEq, PartialOrd)]
+#[error("Line {line}: {message}")]
+pub struct Invalid
+{
+	/// Where the problem is in the imported file.
+	pub line: u64,
+
+	/// What the problem is.
+	pub message: String,
+}
+
+/// An [`Error`](std::error::Error) indicating that nothing was imported, because some of the
+/// imported file was [`Invalid`]. Each problem is listed on its own line.
+#[derive(Clone, Debug, Eq, Error, Hash, Ord, PartialEq, PartialOrd)]
+#[error(
+	"Nothing was imported, because {} problem(s) were found:{}",
+	.0.len(),
+	.0.iter().map(|i| format!("\n{i}")).collect::<String>()
+)]
+pub struct Rejected(pub Vec<Invalid>);
+
+/// Parse a date and time, which is either in RFC 3339 with an offset (e.g.
+/// "2022-01-01T14:00:00+01:00", as [exported](crate::export::TimesheetFormat)), or else in the
+/// [`Local`](winvoice_schema::chrono::Local) timezone.
+fn date_time(s: &str) -> Option<DateTime<Utc>>
+{
+	let s = s.trim();
+	DateTime::parse_from_rfc3339(s).map_or_else(
+		|_| {
+			s.parse::<NaiveDateTime>()
+				.ok()
+				.or_else(|| DATE_TIME_FORMATS.iter().find_map(|f| NaiveDateTime::parse_from_str(s, f).ok()))
+				.map(utils::naive_local_datetime_to_utc)
+		},
+		|d| Some(d.with_timezone(&Utc)),
+	)
+}
+
+#[cfg(test)]
+mod tests
+{
+	use pretty_assertions::assert_eq;
+
+	use super::{Invalid, Rejected};
+
+	#[test]
+	fn rejected()
+	{
+		let rejected = Rejected(vec![Invalid { line: 2, message: "There is no Job named \"foo\"".into() }, Invalid {
+			line: 5,
+			message: "\"tomorrow\" is not a date".into(),
+		}]);
+
+		assert_eq!(
+			rejected.to_string(),
+			"Nothing was imported, because 2 problem(s) were found:\nLine 2: There is no Job named \"foo\"\nLine 5: \
+			 \"tomorrow\" is not a date"
+		);
+	}
+}
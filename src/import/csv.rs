@@ -0,0 +1,178 @@
+use std::io::Read;
+
+use serde::Deserialize;
+
+use super::{Entry, Invalid};
+
+/// A row of an imported CSV file.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+struct Row
+{
+	/// See [`Entry::time_begin`].
+	#[serde(alias = "time_begin")]
+	begin: String,
+
+	/// See [`Entry::employee`].
+	employee: String,
+
+	/// See [`Entry::time_end`].
+	#[serde(alias = "time_end", default)]
+	end: String,
+
+	/// See [`Entry::job`].
+	job: String,
+
+	/// Whether the row is for a "timesheet" or an "expense", as exported by
+	/// [`TimesheetFormat::Csv`](crate::export::TimesheetFormat::Csv).
+	#[serde(default)]
+	kind: Option<String>,
+
+	/// See [`Entry::work_notes`].
+	#[serde(alias = "notes", default)]
+	work_notes: String,
+}
+
+impl Row
+{
+	/// Validate this [`Row`], which is on some `line`.
+	///
+	/// Returns [`None`] if the row should be skipped.
+	fn validate(self, line: u64) -> Option<Result<Entry, Invalid>>
+	{
+		let invalid = |message: String| Invalid { line, message };
+		if self.kind.as_deref() == Some("expense")
+		{
+			return None;
+		}
+
+		let entry = [("employee", &self.employee), ("job", &self.job)]
+			.into_iter()
+			.find(|(_, value)| value.trim().is_empty())
+			.map_or(Ok(()), |(column, _)| Err(invalid(format!("The {column} column is empty"))))
+			.and_then(|_| {
+				let parse =
+					|s: &str| super::date_time(s).ok_or_else(|| invalid(format!("{s:?} is not a date and time")));
+				let time_begin = parse(&self.begin)?;
+				let time_end = match self.end.trim().is_empty()
+				{
+					false => Some(parse(&self.end)?),
+					true => None,
+				};
+
+				match time_end.is_some_and(|e| e < time_begin)
+				{
+					false => Ok(Entry {
+						employee: self.employee.trim().into(),
+						job: self.job.trim().into(),
+						line,
+						time_begin,
+						time_end,
+						work_notes: self.work_notes,
+					}),
+					true => Err(invalid("The end is before the beginning".into())),
+				}
+			});
+
+		Some(entry)
+	}
+}
+
+/// Parse the [`Entry`]s of a CSV file with a header row, which has one row per `Timesheet`.
+///
+/// The columns are the `employee` (an `id` or name), the `job` (an `id` or its `objectives`), and
+/// when the work `begin`s and `end`s, along with its `work_notes` (or `notes`). The `end` and
+/// `work_notes` are optional, and other columns are ignored. Rows which are for an "expense" (in
+/// the `kind` column) are skipped, so that exported `Timesheet`s can be imported again.
+///
+/// Returns every [`Entry`] which could be parsed, and what was [`Invalid`] about the rest.
+pub fn csv(reader: impl Read) -> (Vec<Entry>, Vec<Invalid>)
+{
+	let mut reader = ::csv::Reader::from_reader(reader);
+	let headers = match reader.headers()
+	{
+		Ok(h) => h.clone(),
+		Err(e) => return (Vec::new(), vec![Invalid { line: 1, message: e.to_string() }]),
+	};
+
+	reader.records().fold((Vec::new(), Vec::new()), |(mut entries, mut invalid), record| {
+		let parsed = record.map_err(|e| (e.position().map(::csv::Position::line), e.to_string())).and_then(|r| {
+			let line = r.position().map(::csv::Position::line);
+			r.deserialize::<Row>(Some(&headers)).map(|row| (line, row)).map_err(|e| (line, e.to_string()))
+		});
+
+		match parsed
+		{
+			Ok((line, row)) => match row.validate(line.unwrap_or_default())
+			{
+				Some(Ok(entry)) => entries.push(entry),
+				Some(Err(e)) => invalid.push(e),
+				None => (),
+			},
+			Err((line, message)) => invalid.push(Invalid { line: line.unwrap_or_default(), message }),
+		};
+
+		(entries, invalid)
+	})
+}
+
+#[cfg(test)]
+mod tests
+{
+	use pretty_assertions::assert_eq;
+	use winvoice_schema::chrono::{Local, TimeZone, Utc};
+
+	use super::{Entry, Invalid};
+
+	#[test]
+	fn csv()
+	{
+		let local = |hour, minute| Local.ymd(2022, 1, 1).and_hms(hour, minute, 0).with_timezone(&Utc);
+		let file = "\
+timesheet,kind,job,client,employee,begin,end,hours,rate,amount,currency,notes
+7,timesheet,3,Bar,Bob,2022-01-01 09:00,2022-01-01 10:20,1.50,20.00,30.00,USD,Fixed bugs
+7,expense,3,Bar,Bob,2022-01-01 09:00,2022-01-01 10:20,,,5.00,USD,Food: Lunch
+8,timesheet,3,Bar,Bob,2022-01-01T11:00:00,,,20.00,0.00,USD,
+12,timesheet,3,Bar,Bob,2022-01-01T13:00:00+02:00,2022-01-01T11:30:00Z,0.50,20.00,10.00,USD,Meeting
+9,timesheet,,Bar,Bob,2022-01-01 12:00,,,20.00,0.00,USD,
+10,timesheet,3,Bar,Bob,noon,,,20.00,0.00,USD,
+11,timesheet,3,Bar,Bob,2022-01-01 12:00,2022-01-01 11:00,,20.00,0.00,USD,
+";
+
+		let (entries, invalid) = super::csv(file.as_bytes());
+		assert_eq!(entries, [
+			Entry {
+				employee: "Bob".into(),
+				job: "3".into(),
+				line: 2,
+				time_begin: local(9, 0),
+				time_end: Some(local(10, 20)),
+				work_notes: "Fixed bugs".into(),
+			},
+			Entry {
+				employee: "Bob".into(),
+				job: "3".into(),
+				line: 4,
+				time_begin: local(11, 0),
+				time_end: None,
+				work_notes: String::new(),
+			},
+			Entry {
+				employee: "Bob".into(),
+				job: "3".into(),
+				line: 5,
+				time_begin: Utc.ymd(2022, 1, 1).and_hms(11, 0, 0),
+				time_end: Some(Utc.ymd(2022, 1, 1).and_hms(11, 30, 0)),
+				work_notes: "Meeting".into(),
+			},
+		]);
+		assert_eq!(invalid, [
+			Invalid { line: 6, message: "The job column is empty".into() },
+			Invalid { line: 7, message: "\"noon\" is not a date and time".into() },
+			Invalid { line: 8, message: "The end is before the beginning".into() },
+		]);
+
+		let (entries, invalid) = super::csv("employee,job\nBob".as_bytes());
+		assert!(entries.is_empty());
+		assert_eq!(invalid.len(), 1);
+	}
+}
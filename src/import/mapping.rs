@@ -0,0 +1,111 @@
+use core::result::Result as StdResult;
+use std::{
+	collections::{BTreeMap, BTreeSet},
+	io,
+	path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{input::Result, utils};
+
+/// How the names which other programs use (e.g. the tags of Timewarrior, or the projects and users
+/// of Toggl) refer to the `Employee`s and `Job`s of a store.
+///
+/// Each name is mapped to the `id` or name of an `Employee`, or the `id` or `objectives` of a
+/// `Job`.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default)]
+pub struct Mapping
+{
+	/// The names of users, and the `Employee`s which they refer to.
+	pub employees: BTreeMap<String, String>,
+
+	/// The names of tags or projects, and the `Job`s which they refer to.
+	pub jobs: BTreeMap<String, String>,
+}
+
+impl Mapping
+{
+	/// The `Job` which some `names` (e.g. the tags of an entry) refer to.
+	///
+	/// Names which are not mapped are ignored, but it is an error if none of the `names` are
+	/// mapped, or if they are mapped to different `Job`s.
+	pub fn job<'name>(&self, names: impl IntoIterator<Item = &'name str>) -> StdResult<&str, String>
+	{
+		let names: Vec<_> = names.into_iter().filter(|n| !n.is_empty()).collect();
+		let jobs: BTreeSet<_> = names.iter().filter_map(|n| self.jobs.get(*n)).collect();
+
+		let mut iter = jobs.iter();
+		match (iter.next(), iter.next())
+		{
+			(Some(job), None) => Ok(job.as_str()),
+			(None, _) => Err(format!("None of {names:?} are mapped to a Job")),
+			(Some(_), Some(_)) => Err(format!("{names:?} are mapped to more than one Job: {jobs:?}")),
+		}
+	}
+}
+
+/// The [`Mapping`] of each store.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Mappings(BTreeMap<String, Mapping>);
+
+impl Mappings
+{
+	/// The [`Mapping`] of the `store`.
+	pub fn get(&self, store: &str) -> Mapping
+	{
+		self.0.get(store).cloned().unwrap_or_default()
+	}
+
+	/// Read the [`Mappings`] from the file at `filepath`, or the [default file](path) if there is
+	/// no `filepath`.
+	pub fn read(filepath: Option<&Path>) -> Result<Self>
+	{
+		filepath.map_or_else(|| path().map_err(Into::into).and_then(|p| Self::read_from(&p)), Self::read_from)
+	}
+
+	/// Read the [`Mappings`] from the file at `filepath`, which are empty if there is no file.
+	fn read_from(filepath: &Path) -> Result<Self>
+	{
+		utils::read_data(filepath)
+	}
+}
+
+/// The path to the [`Mappings`] file which is used by default.
+///
+/// # Errors
+///
+/// * When [`utils::data_path`] does.
+fn path() -> io::Result<PathBuf>
+{
+	utils::data_path("import-mappings.yaml")
+}
+
+#[cfg(test)]
+mod tests
+{
+	use std::fs;
+
+	use pretty_assertions::assert_eq;
+
+	use super::Mappings;
+	use crate::utils;
+
+	#[test]
+	fn job()
+	{
+		let filepath = utils::temp_file::<Mappings>("job");
+		fs::write(&filepath, "default:\n  jobs:\n    acme: 3\n    website: Redesign the website\n    www: 3\n")
+			.unwrap();
+
+		let mappings = Mappings::read(Some(&filepath)).unwrap();
+		let mapping = mappings.get("default");
+		assert_eq!(mapping.job(["meeting", "acme"]), Ok("3"));
+		assert_eq!(mapping.job(["acme", "www"]), Ok("3"));
+		assert_eq!(mapping.job(["website", ""]), Ok("Redesign the website"));
+		assert!(mapping.job(["acme", "website"]).is_err());
+		assert!(mapping.job(["meeting"]).is_err());
+		assert_eq!(mappings.get("other"), Default::default());
+	}
+}
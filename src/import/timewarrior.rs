@@ -0,0 +1,128 @@
+use std::io::Read;
+
+use serde::Deserialize;
+use winvoice_schema::chrono::{DateTime, NaiveDateTime, Utc};
+
+use super::{Entry, Invalid, Mapping};
+
+/// The format of the dates and times which Timewarrior exports, which are always in UTC.
+const DATE_TIME_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+
+/// An interval of an exported Timewarrior database.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+struct Interval
+{
+	/// See [`Entry::work_notes`].
+	#[serde(default)]
+	annotation: String,
+
+	/// See [`Entry::time_end`].
+	end: Option<String>,
+
+	/// See [`Entry::time_begin`].
+	start: String,
+
+	/// The tags of the interval, which are [mapped](Mapping::job) to a `Job`.
+	#[serde(default)]
+	tags: Vec<String>,
+}
+
+impl Interval
+{
+	/// Validate this [`Interval`], which is on some `line`, and was recorded by some `employee`.
+	fn validate(self, line: u64, employee: &str, mapping: &Mapping) -> Result<Entry, Invalid>
+	{
+		let invalid = |message: String| Invalid { line, message };
+		let parse = |s: &str| {
+			NaiveDateTime::parse_from_str(s, DATE_TIME_FORMAT)
+				.map(|d| DateTime::<Utc>::from_utc(d, Utc))
+				.map_err(|_| invalid(format!("{s:?} is not a date and time")))
+		};
+
+		let job = mapping.job(self.tags.iter().map(String::as_str)).map_err(invalid)?;
+		Ok(Entry {
+			employee: employee.into(),
+			job: job.into(),
+			line,
+			time_begin: parse(&self.start)?,
+			time_end: self.end.as_deref().map(parse).transpose()?,
+			work_notes: self.annotation,
+		})
+	}
+}
+
+/// Parse the [`Entry`]s of a Timewarrior database exported by `timew export`, all of which were
+/// recorded by some `employee` (an `id` or name).
+///
+/// The `Job` of each interval is the one that its tags are [mapped](Mapping::job) to, and its
+/// annotation becomes the `work_notes`. Since `timew export` writes each interval on its own line
+/// after the opening `[`, intervals are reported as though that is where they are.
+///
+/// Returns every [`Entry`] which could be parsed, and what was [`Invalid`] about the rest.
+pub fn timewarrior(reader: impl Read, employee: &str, mapping: &Mapping) -> (Vec<Entry>, Vec<Invalid>)
+{
+	let intervals: Vec<Interval> = match serde_json::from_reader(reader)
+	{
+		Ok(i) => i,
+		Err(e) => return (Vec::new(), vec![Invalid { line: e.line() as u64, message: e.to_string() }]),
+	};
+
+	intervals.into_iter().zip(2..).fold((Vec::new(), Vec::new()), |(mut entries, mut invalid), (interval, line)| {
+		match interval.validate(line, employee, mapping)
+		{
+			Ok(entry) => entries.push(entry),
+			Err(e) => invalid.push(e),
+		};
+
+		(entries, invalid)
+	})
+}
+
+#[cfg(test)]
+mod tests
+{
+	use pretty_assertions::assert_eq;
+	use winvoice_schema::chrono::{TimeZone, Utc};
+
+	use super::{Entry, Invalid, Mapping};
+
+	#[test]
+	fn timewarrior()
+	{
+		let mapping = Mapping { jobs: [("acme".into(), "3".into())].into(), ..Default::default() };
+		let file = r#"[
+{"id":4,"start":"20220101T090000Z","end":"20220101T102000Z","tags":["acme","bugs"],"annotation":"Fixed bugs"},
+{"id":3,"start":"20220101T110000Z","tags":["acme"]},
+{"id":2,"start":"20220101T120000Z","end":"20220101T130000Z","tags":["lunch"]},
+{"id":1,"start":"noon","tags":["acme"]}
+]"#;
+
+		let (entries, invalid) = super::timewarrior(file.as_bytes(), "Bob", &mapping);
+		assert_eq!(entries, [
+			Entry {
+				employee: "Bob".into(),
+				job: "3".into(),
+				line: 2,
+				time_begin: Utc.ymd(2022, 1, 1).and_hms(9, 0, 0),
+				time_end: Some(Utc.ymd(2022, 1, 1).and_hms(10, 20, 0)),
+				work_notes: "Fixed bugs".into(),
+			},
+			Entry {
+				employee: "Bob".into(),
+				job: "3".into(),
+				line: 3,
+				time_begin: Utc.ymd(2022, 1, 1).and_hms(11, 0, 0),
+				time_end: None,
+				work_notes: String::new(),
+			},
+		]);
+		assert_eq!(invalid, [
+			Invalid { line: 4, message: r#"None of ["lunch"] are mapped to a Job"#.into() },
+			Invalid { line: 5, message: r#""noon" is not a date and time"#.into() },
+		]);
+
+		let (entries, invalid) = super::timewarrior("[{".as_bytes(), "Bob", &mapping);
+		assert!(entries.is_empty());
+		assert_eq!(invalid.len(), 1);
+	}
+}
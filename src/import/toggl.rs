@@ -0,0 +1,174 @@
+use std::io::Read;
+
+use serde::Deserialize;
+
+use super::{Entry, Invalid, Mapping};
+
+/// A row of a detailed report exported from Toggl as CSV.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+struct Row
+{
+	/// See [`Entry::work_notes`].
+	#[serde(rename = "Description", default)]
+	description: String,
+
+	/// The local date that the work ended.
+	#[serde(rename = "End date")]
+	end_date: String,
+
+	/// The local time that the work ended.
+	#[serde(rename = "End time")]
+	end_time: String,
+
+	/// The project of the entry, which is [mapped](Mapping::job) to a `Job` along with its `tags`.
+	#[serde(rename = "Project", default)]
+	project: String,
+
+	/// The local date that the work began.
+	#[serde(rename = "Start date")]
+	start_date: String,
+
+	/// The local time that the work began.
+	#[serde(rename = "Start time")]
+	start_time: String,
+
+	/// The comma-separated tags of the entry.
+	#[serde(rename = "Tags", default)]
+	tags: String,
+
+	/// The name of the user who did the work, which is mapped to an `Employee`.
+	#[serde(rename = "User", default)]
+	user: String,
+}
+
+impl Row
+{
+	/// Validate this [`Row`], which is on some `line`.
+	fn validate(self, line: u64, employee: Option<&str>, mapping: &Mapping) -> Result<Entry, Invalid>
+	{
+		let invalid = |message: String| Invalid { line, message };
+		let parse = |date: &str, time: &str| {
+			let s = format!("{date} {time}");
+			super::date_time(&s).ok_or_else(|| invalid(format!("{s:?} is not a date and time")))
+		};
+
+		let employee =
+			mapping.employees.get(&self.user).map(String::as_str).or(employee).ok_or_else(|| {
+				invalid(format!("{:?} is not mapped to an Employee, and there is no default", self.user))
+			})?;
+
+		let job = mapping
+			.job(Some(self.project.as_str()).into_iter().chain(self.tags.split(',').map(str::trim)))
+			.map_err(invalid)?;
+
+		let time_begin = parse(&self.start_date, &self.start_time)?;
+		let time_end = parse(&self.end_date, &self.end_time)?;
+		match time_end < time_begin
+		{
+			false => Ok(Entry {
+				employee: employee.into(),
+				job: job.into(),
+				line,
+				time_begin,
+				time_end: Some(time_end),
+				work_notes: self.description,
+			}),
+			true => Err(invalid("The end is before the beginning".into())),
+		}
+	}
+}
+
+/// Parse the [`Entry`]s of a detailed report which was exported from Toggl as CSV.
+///
+/// The `Job` of each entry is the one that its project and tags are [mapped](Mapping::job) to, and
+/// its description becomes the `work_notes`. The `Employee` is the one that its user is mapped to,
+/// or the default `employee` (an `id` or name) if there is one.
+///
+/// Returns every [`Entry`] which could be parsed, and what was [`Invalid`] about the rest.
+pub fn toggl(reader: impl Read, employee: Option<&str>, mapping: &Mapping) -> (Vec<Entry>, Vec<Invalid>)
+{
+	let mut reader = ::csv::Reader::from_reader(reader);
+	let headers = match reader.headers()
+	{
+		Ok(h) => h.clone(),
+		Err(e) => return (Vec::new(), vec![Invalid { line: 1, message: e.to_string() }]),
+	};
+
+	reader.records().fold((Vec::new(), Vec::new()), |(mut entries, mut invalid), record| {
+		let line = match &record
+		{
+			Ok(r) => r.position(),
+			Err(e) => e.position(),
+		}
+		.map(::csv::Position::line)
+		.unwrap_or_default();
+
+		let validated = record
+			.and_then(|r| r.deserialize::<Row>(Some(&headers)))
+			.map_err(|e| Invalid { line, message: e.to_string() })
+			.and_then(|row| row.validate(line, employee, mapping));
+
+		match validated
+		{
+			Ok(entry) => entries.push(entry),
+			Err(e) => invalid.push(e),
+		};
+
+		(entries, invalid)
+	})
+}
+
+#[cfg(test)]
+mod tests
+{
+	use pretty_assertions::assert_eq;
+	use winvoice_schema::chrono::{Local, TimeZone, Utc};
+
+	use super::{Entry, Invalid, Mapping};
+
+	#[test]
+	fn toggl()
+	{
+		let local = |hour, minute| Local.ymd(2022, 1, 1).and_hms(hour, minute, 0).with_timezone(&Utc);
+		let mapping = Mapping {
+			employees: [("Bob Smith".into(), "Bob".into())].into(),
+			jobs: [("Website".into(), "3".into()), ("acme".into(), "4".into())].into(),
+		};
+
+		let file = "\
+User,Email,Client,Project,Task,Description,Billable,Start date,Start time,End date,End time,Duration,Tags,Amount (USD)
+Bob Smith,bob@example.com,Acme,Website,,Fixed bugs,Yes,2022-01-01,09:00:00,2022-01-01,10:20:00,01:20:00,,26.67
+Alice,alice@example.com,Acme,,,Call,Yes,2022-01-01,11:00:00,2022-01-01,11:30:00,00:30:00,\"meeting, acme\",10.00
+Bob Smith,bob@example.com,Acme,Website,,Lunch,No,2022-01-01,12:00:00,2022-01-01,11:00:00,00:00:00,acme,0.00
+";
+
+		let (entries, invalid) = super::toggl(file.as_bytes(), Some("1"), &mapping);
+		assert_eq!(entries, [
+			Entry {
+				employee: "Bob".into(),
+				job: "3".into(),
+				line: 2,
+				time_begin: local(9, 0),
+				time_end: Some(local(10, 20)),
+				work_notes: "Fixed bugs".into(),
+			},
+			Entry {
+				employee: "1".into(),
+				job: "4".into(),
+				line: 3,
+				time_begin: local(11, 0),
+				time_end: Some(local(11, 30)),
+				work_notes: "Call".into(),
+			},
+		]);
+		assert_eq!(invalid.len(), 1);
+		assert_eq!(invalid[0].line, 4);
+
+		let (entries, invalid) = super::toggl(file.as_bytes(), None, &mapping);
+		assert_eq!(entries.len(), 1);
+		assert_eq!(invalid[0], Invalid {
+			line: 3,
+			message: "\"Alice\" is not mapped to an Employee, and there is no default".into()
+		});
+	}
+}
@@ -1,14 +1,15 @@
 mod error;
 pub mod expense;
+pub mod expression;
+mod prompter;
 
-use core::{
-	fmt::{Debug, Display},
-	str::FromStr,
-};
+use core::{fmt::Display, str::FromStr};
 use std::io;
 
-use dialoguer::{Confirm, Editor, Input, MultiSelect, Select};
 pub use error::{Error, Result};
+#[cfg(test)]
+pub use prompter::Answer;
+pub use prompter::{AutoAccept, Prompter, Scripted, Terminal};
 use serde::{de::DeserializeOwned, Serialize};
 use serde_yaml as yaml;
 use sqlx::{Database, Executor, Pool};
@@ -21,34 +22,34 @@ use crate::{fmt, DynResult};
 const MATCH_PROMPT: &str =
 	"See the documentation of this query at https://github.com/Iron-E/winvoice/wiki/Query-Syntax#";
 
-/// `prompt` the user with a yes/no question.
+/// `prompt` the user with a yes/no question (suggesting "no").
 ///
 /// # Returns
 ///
 /// * [`Ok(true)`] if the user answers "yes".
 /// * [`Ok(false)`] if the user answers "no".
 /// * [`Err`] if there was an error gathering input.
-pub fn confirm<T>(prompt: T) -> io::Result<bool>
+pub fn confirm<T>(prompter: &dyn Prompter, prompt: T) -> io::Result<bool>
 where
-	T: Into<String>,
+	T: AsRef<str>,
 {
-	Confirm::new().with_prompt(prompt).interact()
+	prompter.confirm(prompt.as_ref(), false)
 }
 
 /// If a `prompt` is [`confirm`]ed, return `some` value.
-pub fn confirm_then_some<Prompt, Some>(prompt: Prompt, some: Some) -> Option<Some>
+///
+/// # Errors
+///
+/// * When [`confirm`] does.
+pub fn confirm_then_some<Prompt, Some>(prompter: &dyn Prompter, prompt: Prompt, some: Some) -> io::Result<Option<Some>>
 where
-	Prompt: Into<String>,
+	Prompt: AsRef<str>,
 {
-	confirm(prompt).unwrap_or(false).then_some(some)
+	confirm(prompter, prompt).map(|confirmed| confirmed.then_some(some))
 }
 
-/// Gather input from the user's text editor, defined by the:
-///
-/// 1. "VISUAL" environment variable.
-/// 2. "EDITOR" environment variable.
-/// 3. Platform default (Notepad on Windows, Vi on Unix).
-pub fn edit<Entity, Prompt>(entity: &Entity, prompt: Prompt) -> Result<Entity>
+/// Gather input by having the `prompter` [edit](Prompter::edit) the `entity` as YAML.
+pub fn edit<Entity, Prompt>(prompter: &dyn Prompter, entity: &Entity, prompt: Prompt) -> Result<Entity>
 where
 	Entity: DeserializeOwned + Serialize,
 	Prompt: AsRef<str>,
@@ -56,31 +57,31 @@ where
 	let to_edit = yaml::to_string(&entity)
 		.map(|serialized| format!("# {}\n\n{serialized}", prompt.as_ref().replace('\n', "\n# "),))?;
 
-	let maybe_edited = Editor::new().extension(".yaml").edit(&to_edit)?;
+	let maybe_edited = prompter.edit(&to_edit, ".yaml")?;
 
 	maybe_edited.ok_or(Error::NotEdited).and_then(|edit| yaml::from_str(&edit).map_err(Error::from))
 }
 
 /// [Edit](edit) an `entity`, and then [restore](winvoice_schema::RestorableSerde) it.
-pub fn edit_and_restore<Entity, Prompt>(entity: &Entity, prompt: Prompt) -> Result<Entity>
+pub fn edit_and_restore<Entity, Prompt>(prompter: &dyn Prompter, entity: &Entity, prompt: Prompt) -> Result<Entity>
 where
 	Entity: DeserializeOwned + RestorableSerde + Serialize,
 	Prompt: AsRef<str>,
 {
-	let mut edited = edit(entity, prompt)?;
+	let mut edited = edit(prompter, entity, prompt)?;
 	edited.try_restore(entity)?;
 	Ok(edited)
 }
 
 /// [Edit](edit) `Entity::default`, returning that `default` if [no edits](Error::NotEdited) were
 /// made.
-pub fn edit_default<Entity, Prompt>(prompt: Prompt) -> Result<Entity>
+pub fn edit_default<Entity, Prompt>(prompter: &dyn Prompter, prompt: Prompt) -> Result<Entity>
 where
 	Entity: Default + DeserializeOwned + Serialize,
 	Prompt: AsRef<str>,
 {
 	let default = Entity::default();
-	edit(&default, prompt).or_else(|e| match e
+	edit(prompter, &default, prompt).or_else(|e| match e
 	{
 		Error::NotEdited => Ok(default),
 		_ => Err(e),
@@ -89,51 +90,98 @@ where
 
 /// [Retrieve](Retrievable::retrieve) all [entities](Retrievable::Entity) that match a
 /// user-provided query.
-pub async fn retrieve<Retr, Db, Prompt>(connection: &Pool<Db>, prompt: Prompt) -> DynResult<Vec<Retr::Entity>>
+pub async fn retrieve<Retr, Db, Prompt>(
+	prompter: &dyn Prompter,
+	connection: &Pool<Db>,
+	prompt: Prompt,
+) -> DynResult<Vec<Retr::Entity>>
 where
 	Db: Database,
 	Prompt: Display,
 	Retr: Retrievable<Db = Db>,
-	Retr::Match: Default + DeserializeOwned + Serialize,
+	Retr::Match: Clone + Default + DeserializeOwned + Serialize,
+	for<'connection> &'connection mut Db::Connection: Executor<'connection, Database = Db>,
+{
+	retrieve_with_condition::<Retr, _, _>(prompter, connection, prompt).await.map(|(_, retrieved)| retrieved)
+}
+
+/// [Retrieve](Retrievable::retrieve) all [entities](Retrievable::Entity) that match a
+/// user-provided query, and return that query along with them.
+pub async fn retrieve_with_condition<Retr, Db, Prompt>(
+	prompter: &dyn Prompter,
+	connection: &Pool<Db>,
+	prompt: Prompt,
+) -> DynResult<(Retr::Match, Vec<Retr::Entity>)>
+where
+	Db: Database,
+	Prompt: Display,
+	Retr: Retrievable<Db = Db>,
+	Retr::Match: Clone + Default + DeserializeOwned + Serialize,
 	for<'connection> &'connection mut Db::Connection: Executor<'connection, Database = Db>,
 {
 	loop
 	{
-		let match_condition: Retr::Match = edit_default(format!("{prompt}\n{}locations", MATCH_PROMPT))?;
+		let match_condition: Retr::Match = edit_default(prompter, format!("{prompt}\n{}locations", MATCH_PROMPT))?;
 
-		let results = Retr::retrieve(connection, match_condition).await?;
+		let results = Retr::retrieve(connection, match_condition.clone()).await?;
 
-		if results.is_empty() && confirm("That query did not return any results, would you like to try again?")?
+		if results.is_empty() &&
+			confirm(prompter, "That query did not return any results, would you like to try again?")?
 		{
 			continue;
 		}
 
-		return Ok(results);
+		return Ok((match_condition, results));
 	}
 }
 
 /// `prompt` users to select elements from `entities`, returning them.
-pub fn select<Entity, Prompt>(entities: Vec<Entity>, prompt: Prompt) -> io::Result<Vec<Entity>>
+pub fn select<Entity, Prompt>(prompter: &dyn Prompter, entities: Vec<Entity>, prompt: Prompt) -> io::Result<Vec<Entity>>
+where
+	Entity: Display,
+	Prompt: AsRef<str>,
+{
+	let indices = select_indices(prompter, &entities, prompt)?;
+	Ok(entities.into_iter().enumerate().filter(|(i, _)| indices.binary_search(i).is_ok()).map(|(_, e)| e).collect())
+}
+
+/// `prompt` users to select elements from `entities` which will be destroyed (e.g. deleted),
+/// returning them. See [`Prompter::select_destructive`] for what it means to be `narrowed`.
+pub fn select_destructive<Entity, Prompt>(
+	prompter: &dyn Prompter,
+	entities: Vec<Entity>,
+	narrowed: bool,
+	prompt: Prompt,
+) -> io::Result<Vec<Entity>>
 where
-	Entity: Clone + Display,
-	Prompt: Into<String>,
+	Entity: Display,
+	Prompt: AsRef<str>,
 {
-	let indices = select_indices(&entities, prompt)?;
+	if entities.is_empty()
+	{
+		return Ok(entities);
+	}
+
+	let indices = prompter.select_destructive(&to_strings(&entities), prompt.as_ref(), narrowed)?;
 	Ok(entities.into_iter().enumerate().filter(|(i, _)| indices.binary_search(i).is_ok()).map(|(_, e)| e).collect())
 }
 
 /// `prompt` users to select elements from `entities`, and then return the index where they appear.
-pub fn select_indices<Entity, Prompt>(entities: &[Entity], prompt: Prompt) -> io::Result<Vec<usize>>
+pub fn select_indices<Entity, Prompt>(
+	prompter: &dyn Prompter,
+	entities: &[Entity],
+	prompt: Prompt,
+) -> io::Result<Vec<usize>>
 where
-	Entity: Clone + Display,
-	Prompt: Into<String>,
+	Entity: Display,
+	Prompt: AsRef<str>,
 {
 	if entities.is_empty()
 	{
 		return Ok(Vec::new());
 	}
 
-	MultiSelect::new().items(entities).with_prompt(prompt).interact()
+	prompter.select(&to_strings(entities), prompt.as_ref())
 }
 
 /// `prompt` users to select one element from `entities`, returning it.
@@ -141,55 +189,45 @@ where
 /// # Errors
 ///
 /// * When [`select_one_index`] does.
-pub fn select_one<Entity, Prompt>(mut entities: Vec<Entity>, prompt: Prompt) -> Result<Entity>
+pub fn select_one<Entity, Prompt>(prompter: &dyn Prompter, mut entities: Vec<Entity>, prompt: Prompt) -> Result<Entity>
 where
-	Entity: Clone + Display,
-	Prompt: Into<String>,
+	Entity: Display,
+	Prompt: AsRef<str>,
 {
-	select_one_index(&entities, prompt).map(|i| entities.remove(i))
+	select_one_index(prompter, &entities, prompt, 0).map(|i| entities.remove(i))
 }
 
-/// `prompt` users to select one element from `entities`, returning the index where it is found.
+/// `prompt` users to select one element from `entities` (suggesting the one at the `default`
+/// index), returning the index where it is found.
 ///
 /// # Errors
 ///
 /// * When `entities` is empty.
-/// * When [`Select::interact`] does.
-pub fn select_one_index<Entity, Prompt>(entities: &[Entity], prompt: Prompt) -> Result<usize>
+/// * When [`Prompter::select_one`] does.
+pub fn select_one_index<Entity, Prompt>(
+	prompter: &dyn Prompter,
+	entities: &[Entity],
+	prompt: Prompt,
+	default: usize,
+) -> Result<usize>
 where
-	Entity: Clone + Display,
-	Prompt: Into<String>,
+	Entity: Display,
+	Prompt: AsRef<str>,
 {
 	if entities.is_empty()
 	{
 		return Err(Error::NoData(crate::fmt::type_name::<Entity>().into()));
 	}
 
-	// {{{
-	let mut s = Select::new();
-	s.items(entities).with_prompt(prompt);
-	// }}}
-
-	let selector = s;
-	loop
-	{
-		match selector.interact()
-		{
-			Err(e) if e.kind() == io::ErrorKind::Other && e.to_string().contains("Quit not allowed") =>
-			{
-				println!("Please select something, or press Ctrl+C to quit");
-			},
-			result => return result.map_err(Error::from),
-		};
-	}
+	prompter.select_one(&to_strings(entities), prompt.as_ref(), default).map_err(Error::from)
 }
 
 /// [`select_one`] from:
 ///
 /// * If `match_condition` is [`None`], values the user was `prompt`ed to [`retrieve`].
 /// * If `match_condition` is [`Some`], values matching the condition.
-#[cfg_attr(test, allow(unreachable_code))]
 pub async fn select_one_retrieved<Retr, Db, Prompt>(
+	prompter: &dyn Prompter,
 	connection: &Pool<Db>,
 	match_condition: Option<Retr::Match>,
 	prompt: Prompt,
@@ -199,20 +237,16 @@ where
 	Prompt: Display,
 	Retr: Retrievable<Db = Db>,
 	Retr::Entity: Clone + Display,
-	Retr::Match: Default + DeserializeOwned + Serialize,
+	Retr::Match: Clone + Default + DeserializeOwned + Serialize,
 	for<'connection> &'connection mut Db::Connection: Executor<'connection, Database = Db>,
 {
-	#[cfg_attr(not(test), allow(unused_mut))]
-	let mut retrieved = match match_condition
+	let retrieved = match match_condition
 	{
 		Some(condition) => Retr::retrieve(connection, condition).await?,
-		None => retrieve::<Retr, _, _>(connection, prompt).await?,
+		None => retrieve::<Retr, _, _>(prompter, connection, prompt).await?,
 	};
 
-	#[cfg(test)]
-	return Ok(retrieved.remove(0));
-
-	let selected = select_one(retrieved, format!("Select a {}", fmt::type_name::<Retr::Entity>()))?;
+	let selected = select_one(prompter, retrieved, format!("Select a {}", fmt::type_name::<Retr::Entity>()))?;
 
 	Ok(selected)
 }
@@ -221,8 +255,8 @@ where
 ///
 /// * If `match_condition` is [`None`], values the user was `prompt`ed to [`retrieve`].
 /// * If `match_condition` is [`Some`], values matching the condition.
-#[cfg_attr(test, allow(unreachable_code))]
 pub async fn select_retrieved<Retr, Db, Prompt>(
+	prompter: &dyn Prompter,
 	connection: &Pool<Db>,
 	match_condition: Option<Retr::Match>,
 	prompt: Prompt,
@@ -232,35 +266,37 @@ where
 	Prompt: Display,
 	Retr: Retrievable<Db = Db>,
 	Retr::Entity: Clone + Display,
-	Retr::Match: Default + DeserializeOwned + Serialize,
+	Retr::Match: Clone + Default + DeserializeOwned + Serialize,
 	for<'connection> &'connection mut Db::Connection: Executor<'connection, Database = Db>,
 {
 	let retrieved = match match_condition
 	{
 		Some(condition) => Retr::retrieve(connection, condition).await?,
-		None => retrieve::<Retr, _, _>(connection, prompt).await?,
+		None => retrieve::<Retr, _, _>(prompter, connection, prompt).await?,
 	};
 
-	#[cfg(test)]
-	return Ok(retrieved);
-
-	let selected = select(retrieved, format!("Select the {}s", fmt::type_name::<Retr::Entity>()))?;
+	let selected = select(prompter, retrieved, format!("Select the {}s", fmt::type_name::<Retr::Entity>()))?;
 	Ok(selected)
 }
 
 /// `prompt` the user to enter text, and return what they entered.
-pub fn text<Text, Prompt>(default_text: Option<Text>, prompt: Prompt) -> io::Result<Text>
+pub fn text<Text, Prompt>(prompter: &dyn Prompter, default_text: Option<Text>, prompt: Prompt) -> io::Result<Text>
 where
-	Prompt: Into<String>,
-	Text: Clone + FromStr + Display,
-	Text::Err: Display + Debug,
+	Prompt: AsRef<str>,
+	Text: FromStr + Display,
+	Text::Err: Display,
 {
-	let mut input = Input::new();
+	let default = default_text.map(|t| t.to_string());
+	let entered = prompter
+		.text(default.as_deref(), prompt.as_ref(), &|s| s.parse::<Text>().map(drop).map_err(|e| e.to_string()))?;
 
-	if let Some(text) = default_text
-	{
-		input.default(text);
-	}
+	entered.parse().map_err(|e: Text::Err| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))
+}
 
-	input.with_prompt(prompt).interact_text()
+/// Convert each of the `entities` [`to_string`](ToString::to_string).
+fn to_strings<Entity>(entities: &[Entity]) -> Vec<String>
+where
+	Entity: Display,
+{
+	entities.iter().map(ToString::to_string).collect()
 }
@@ -8,6 +8,8 @@ use thiserror::Error;
 use winvoice_config::Error as ConfigError;
 use winvoice_schema::RestoreError;
 
+use super::expression::Error as ExpressionError;
+
 /// An [`Error`](std::error::Error) for getting input from STDIO.
 #[derive(Debug, Error)]
 pub enum Error
@@ -16,6 +18,10 @@ pub enum Error
 	#[error(transparent)]
 	Config(#[from] ConfigError),
 
+	#[allow(missing_docs)]
+	#[error(transparent)]
+	Expression(#[from] ExpressionError),
+
 	#[allow(missing_docs)]
 	#[error(transparent)]
 	Io(#[from] io::Error),
@@ -32,6 +38,18 @@ pub enum Error
 	#[error(transparent)]
 	Restore(#[from] RestoreError),
 
+	#[allow(missing_docs)]
+	#[error(transparent)]
+	TomlDe(#[from] toml::de::Error),
+
+	#[allow(missing_docs)]
+	#[error(transparent)]
+	TomlSer(#[from] toml::ser::Error),
+
+	#[allow(missing_docs)]
+	#[error("There is no saved query named {0:?} for {1}s")]
+	UnknownQuery(String, String),
+
 	#[allow(missing_docs)]
 	#[error(transparent)]
 	Yaml(#[from] yaml::Error),
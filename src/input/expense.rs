@@ -9,14 +9,15 @@ use action::Action;
 use strum::IntoEnumIterator;
 use winvoice_schema::Money;
 
-use super::Result;
+use super::{Prompter, Result};
 
 /// Show a menu for adding `expenses`.
-fn add_menu(expenses: &mut Vec<(String, Money, String)>) -> io::Result<()>
+fn add_menu(prompter: &dyn Prompter, expenses: &mut Vec<(String, Money, String)>) -> io::Result<()>
 {
-	let category = super::text(None, "What type of Expense is this?")?;
-	let description = super::text(None, format!("Describe the {category} Expense"))?;
+	let category = super::text(prompter, None, "What type of Expense is this?")?;
+	let description = super::text(prompter, None, format!("Describe the {category} Expense"))?;
 	let cost = super::text(
+		prompter,
 		None,
 		format!("What is the cost of the {category} Expense? e.g. {}", Money::new(20_00, 2, Default::default()),),
 	)?;
@@ -31,35 +32,39 @@ fn add_menu(expenses: &mut Vec<(String, Money, String)>) -> io::Result<()>
 /// # Errors
 ///
 /// * When [`select_one`](super::select_one), [`add_menu`], [`delete_menu`], or [`edit_menu`] does.
-pub fn menu() -> Result<Vec<(String, Money, String)>>
+pub fn menu(prompter: &dyn Prompter) -> Result<Vec<(String, Money, String)>>
 {
 	let all_actions: Vec<_> = Action::iter().collect();
+	let continue_index = all_actions.iter().position(|a| *a == Action::Continue).unwrap_or_default();
 	let mut expenses = Vec::new();
 
 	loop
 	{
 		let action = super::select_one_index(
+			prompter,
 			&all_actions,
 			"\nThis is the menu for entering Expenses\nWhat would you like to do?",
+			continue_index,
 		)
 		.map(|i| all_actions[i])?;
 
 		match action
 		{
-			Action::Add => add_menu(&mut expenses)?,
+			Action::Add => add_menu(prompter, &mut expenses)?,
 			Action::Continue => return Ok(expenses),
-			Action::Delete => delete_menu(&mut expenses)?,
-			Action::Edit => edit_menu(&mut expenses)?,
+			Action::Delete => delete_menu(prompter, &mut expenses)?,
+			Action::Edit => edit_menu(prompter, &mut expenses)?,
 		};
 	}
 }
 
 /// Show a menu for deleting `expenses`.
-fn delete_menu(expenses: &mut Vec<(String, Money, String)>) -> Result<()>
+fn delete_menu(prompter: &dyn Prompter, expenses: &mut Vec<(String, Money, String)>) -> Result<()>
 {
 	if !expenses.is_empty()
 	{
 		let to_remove_indices = super::select_indices(
+			prompter,
 			&expenses.iter().map(tuple_to_string).collect::<Vec<_>>(),
 			"Select Expenses to remove",
 		)?;
@@ -75,22 +80,24 @@ fn delete_menu(expenses: &mut Vec<(String, Money, String)>) -> Result<()>
 }
 
 /// Show a menu for editing `expenses`.
-fn edit_menu(expenses: &mut Vec<(String, Money, String)>) -> Result<()>
+fn edit_menu(prompter: &dyn Prompter, expenses: &mut Vec<(String, Money, String)>) -> Result<()>
 {
 	if !expenses.is_empty()
 	{
 		const PROMPT: &str = "Make any desired changes to the ";
 
 		let edit_index = super::select_one_index(
+			prompter,
 			&expenses.iter().map(tuple_to_string).collect::<Vec<_>>(),
 			"Select an Expense to edit",
+			0,
 		)?;
 
 		let (mut category, mut cost, mut description) = expenses.remove(edit_index);
 
-		category = super::text(Some(category), format!("{PROMPT} category"))?;
-		cost = super::text(Some(cost), format!("{PROMPT} cost"))?;
-		description = super::text(Some(description), format!("{PROMPT} description"))?;
+		category = super::text(prompter, Some(category), format!("{PROMPT} category"))?;
+		cost = super::text(prompter, Some(cost), format!("{PROMPT} cost"))?;
+		description = super::text(prompter, Some(description), format!("{PROMPT} description"))?;
 
 		expenses.push((category, cost, description));
 	}
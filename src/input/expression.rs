@@ -0,0 +1,427 @@
+//! This module contains the [`parse`] function, which converts inline match expressions (e.g.
+//! `client.name ~ "Acme" and date_close = null`) into match conditions.
+//!
+//! An expression is any number of comparisons between a field and a value, which may be combined
+//! using `and`, `or`, `not`, and parentheses:
+//!
+//! | Operator | Meaning                        |
+//! |:--------:|:-------------------------------|
+//! | `=`      | equal to (or `none`, if `null`) |
+//! | `!=`     | not equal to                   |
+//! | `~`      | contains                       |
+//! | `=~`     | matches the regular expression |
+//! | `<`      | less than                      |
+//! | `<=`     | less than or equal to          |
+//! | `>`      | greater than                   |
+//! | `>=`     | greater than or equal to       |
+//!
+//! Fields are the same as those of the YAML match conditions, with nested fields separated by
+//! `.` (e.g. `client.location.name`). Values which contain spaces or operators must be
+//! `"quoted"`, and `not field = null` matches any `field` which has a value. Because each field of a match condition
+//! can only be matched once, `or` and `not` may only be used on conditions which refer to the same field.
+
+mod error;
+mod parser;
+mod token;
+
+use core::ops::Range;
+
+pub use error::Error;
+use parser::{Node, Parser};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_yaml::{self as yaml, Mapping, Value};
+use token::Operator;
+
+use crate::fmt;
+
+/// A condition on a single field.
+struct Condition
+{
+	/// The path to the field (e.g. `["client", "name"]`).
+	field: Vec<String>,
+
+	/// Where in the expression the condition is, in bytes.
+	span: Range<usize>,
+
+	/// The condition, as it would appear in a YAML match condition.
+	value: Value,
+
+	/// The same condition as the `value`, except every value is a string, exactly as it was written
+	/// (e.g. `equal_to: "007"` rather than `equal_to: 7`).
+	literal: Value,
+}
+
+impl Condition
+{
+	/// Combine this condition with the `other` one using the `tag` (e.g. `and`).
+	fn combine(self, tag: &str, other: Self) -> Self
+	{
+		Self {
+			field: self.field,
+			span: self.span.start.min(other.span.start)..self.span.end.max(other.span.end),
+			value: combine(tag, self.value, other.value),
+			literal: combine(tag, self.literal, other.literal),
+		}
+	}
+
+	/// Check that the `value` of this condition can match the field of `T` which it refers to,
+	/// using its `literal` instead if only that can (e.g. because the field is a string).
+	///
+	/// # Errors
+	///
+	/// * When neither the `value` nor the `literal` can match the field.
+	fn typed<T>(mut self, expression: &str) -> Result<Self, Error>
+	where
+		T: DeserializeOwned,
+	{
+		let describes = |value: &Value| {
+			let mut root = Mapping::new();
+			insert(&mut root, &self.field, value.clone());
+			yaml::from_value::<T>(Value::Mapping(root))
+		};
+
+		if let Err(e) = describes(&self.value)
+		{
+			match self.literal != self.value && describes(&self.literal).is_ok()
+			{
+				false =>
+				{
+					return Err(Error::new(
+						expression,
+						Some(self.span),
+						format!("`{}` cannot be matched this way: {e}", self.field.join(".")),
+					))
+				},
+				true => self.value = self.literal.clone(),
+			}
+		}
+
+		Ok(self)
+	}
+}
+
+/// Combine the `lhs` and `rhs` conditions using the `tag` (e.g. `and`), flattening nested
+/// combinations of the same `tag`.
+fn combine(tag: &str, lhs: Value, rhs: Value) -> Value
+{
+	match lhs
+	{
+		Value::Mapping(mut m) if m.len() == 1 && matches!(m.get(&tag.into()), Some(Value::Sequence(_))) =>
+		{
+			if let Some(Value::Sequence(s)) = m.get_mut(&tag.into())
+			{
+				s.push(rhs);
+			}
+
+			Value::Mapping(m)
+		},
+		_ => tagged(tag, Value::Sequence(vec![lhs, rhs])),
+	}
+}
+
+/// Convert a comparison with the `operator` and `value` into a YAML match condition.
+fn compare(operator: Operator, value: Value) -> Value
+{
+	if value.is_null()
+	{
+		return Value::String("none".into());
+	}
+
+	match operator
+	{
+		Operator::Contains => tagged("contains", value),
+		Operator::EqualTo => tagged("equal_to", value),
+		Operator::GreaterThan => tagged("greater_than", value),
+		Operator::GreaterThanOrEqualTo =>
+		{
+			combine("or", compare(Operator::GreaterThan, value.clone()), compare(Operator::EqualTo, value))
+		},
+		Operator::LessThan => tagged("less_than", value),
+		Operator::LessThanOrEqualTo =>
+		{
+			combine("or", compare(Operator::LessThan, value.clone()), compare(Operator::EqualTo, value))
+		},
+		Operator::NotEqualTo => tagged("not", compare(Operator::EqualTo, value)),
+		Operator::Regex => tagged("regex", value),
+	}
+}
+
+/// Insert the `value` of a condition on the `field` into the `root` of a YAML match condition.
+fn insert(root: &mut Mapping, field: &[String], value: Value)
+{
+	let (last, parents) = field.split_last().expect("fields should have at least one name");
+	let parent = parents.iter().fold(root, |mapping, name| {
+		let key = Value::String(name.clone());
+		if !mapping.contains_key(&key)
+		{
+			mapping.insert(key.clone(), Value::Mapping(Mapping::new()));
+		}
+
+		match mapping.get_mut(&key)
+		{
+			Some(Value::Mapping(m)) => m,
+			_ => unreachable!("conditions on parents of other conditions' fields should have been rejected"),
+		}
+	});
+
+	parent.insert(Value::String(last.clone()), value);
+}
+
+/// Convert the `node` into [`Condition`]s, each of which refers to a different field, and all of
+/// which must match.
+fn lower(expression: &str, node: Node) -> Result<Vec<Condition>, Error>
+{
+	/// Ensure there is exactly one of the `conditions`, or return an `Err` about the `span` which
+	/// has the `message`.
+	fn single(
+		expression: &str,
+		mut conditions: Vec<Condition>,
+		span: &Range<usize>,
+		message: &str,
+	) -> Result<Condition, Error>
+	{
+		match conditions.len()
+		{
+			1 => Ok(conditions.remove(0)),
+			_ => Err(Error::new(expression, Some(span.clone()), message)),
+		}
+	}
+
+	match node
+	{
+		Node::And(nodes) => nodes.into_iter().try_fold(Vec::<Condition>::new(), |mut conditions, n| {
+			for c in lower(expression, n)?
+			{
+				match conditions.iter().position(|existing| existing.field == c.field)
+				{
+					Some(i) =>
+					{
+						let existing = conditions.remove(i);
+						conditions.insert(i, existing.combine("and", c));
+					},
+					None => conditions.push(c),
+				}
+			}
+
+			Ok(conditions)
+		}),
+
+		Node::Comparison { field, literal, operator, span, value } =>
+		{
+			Ok(vec![Condition { field, span, value: compare(operator, value), literal: compare(operator, literal) }])
+		},
+
+		Node::Not(node, span) =>
+		{
+			let mut condition = single(
+				expression,
+				lower(expression, *node)?,
+				&span,
+				"`not` can only be used on conditions which refer to the same field",
+			)?;
+
+			condition.value = negate(condition.value);
+			condition.literal = negate(condition.literal);
+			Ok(vec![condition])
+		},
+
+		Node::Or(nodes, span) =>
+		{
+			/// The message for when `or` is misused.
+			const MESSAGE: &str = r#"`or` can only be used on conditions which refer to the same field (e.g. `name = "a" or name = "b"`)"#;
+
+			let mut conditions =
+				nodes.into_iter().map(|n| lower(expression, n).and_then(|c| single(expression, c, &span, MESSAGE)));
+
+			let first = conditions.next().expect("`or` should have at least two operands")?;
+			conditions
+				.try_fold(first, |lhs, rhs| {
+					let rhs = rhs?;
+					match lhs.field == rhs.field
+					{
+						false => Err(Error::new(expression, Some(span.clone()), MESSAGE)),
+						true => Ok(lhs.combine("or", rhs)),
+					}
+				})
+				.map(|c| vec![c])
+		},
+	}
+}
+
+/// Negate the `value` of a condition, such that `not` being `none` means being `some`thing.
+fn negate(value: Value) -> Value
+{
+	match value
+	{
+		Value::String(s) if s == "none" => tagged("some", "any".into()),
+		_ => tagged("not", value),
+	}
+}
+
+/// Parse the `expression` into a match condition (e.g. [`MatchJob`](winvoice_match::MatchJob)).
+///
+/// # Errors
+///
+/// * When the `expression` is not syntactically valid.
+/// * When the `expression` refers to fields which `T` does not have, or compares fields to values of the wrong type.
+///
+/// Unquoted values are read as YAML (e.g. `3` is a number), unless the field they are compared to
+/// is a string (e.g. `name = 007` is the same as `name = "007"`).
+pub fn parse<T>(expression: &str) -> Result<T, Error>
+where
+	T: DeserializeOwned + Serialize,
+{
+	let node = Parser::parse(expression)?;
+	let conditions = lower(expression, node)?;
+
+	if let Some((parent, child)) = conditions.iter().find_map(|parent| {
+		conditions
+			.iter()
+			.find(|c| c.field.len() > parent.field.len() && c.field.starts_with(&parent.field))
+			.map(|c| (parent, c))
+	})
+	{
+		return Err(Error::new(
+			expression,
+			Some(child.span.clone()),
+			format!("this conflicts with the condition on `{}`", parent.field.join(".")),
+		));
+	}
+
+	let conditions = conditions.into_iter().map(|c| c.typed::<T>(expression)).collect::<Result<Vec<_>, _>>()?;
+	let fields: Vec<_> = conditions.iter().map(|c| (c.field.clone(), c.span.clone())).collect();
+	let mut root = Mapping::new();
+	conditions.into_iter().for_each(|c| insert(&mut root, &c.field, c.value));
+
+	let type_name = fmt::type_name::<T>();
+	let parsed: T = yaml::from_value(Value::Mapping(root))
+		.map_err(|e| Error::new(expression, None, format!("it does not describe a valid {type_name}: {e}")))?;
+
+	// NOTE: unknown fields are ignored when deserializing, so to report typos, check that every field is
+	//       still present after a round-trip.
+	let serialized = yaml::to_value(&parsed).map_err(|e| Error::new(expression, None, e.to_string()))?;
+	if let Some((field, span)) = fields.into_iter().find(|(field, _)| {
+		field
+			.iter()
+			.try_fold(&serialized, |value, name| match value
+			{
+				Value::Mapping(m) => m.get(&Value::String(name.clone())),
+				_ => None,
+			})
+			.is_none()
+	})
+	{
+		return Err(Error::new(expression, Some(span), format!("`{}` is not a field of {type_name}", field.join("."))));
+	}
+
+	Ok(parsed)
+}
+
+/// Create a YAML mapping of `tag` to the `value` (e.g. `not: value`).
+fn tagged(tag: &str, value: Value) -> Value
+{
+	let mut mapping = Mapping::new();
+	mapping.insert(tag.into(), value);
+	Value::Mapping(mapping)
+}
+
+#[cfg(test)]
+mod tests
+{
+	use pretty_assertions::assert_eq;
+	use winvoice_match::{Match, MatchEmployee, MatchJob, MatchOption, MatchOrganization, MatchStr, MatchTimesheet};
+	use winvoice_schema::chrono::NaiveDate;
+
+	use super::parse;
+
+	#[test]
+	fn errors()
+	{
+		/// Assert that parsing the `expression` fails, and that the error points at the `caret`.
+		fn assert_err(expression: &str, caret: &str)
+		{
+			let message = parse::<MatchJob>(expression).unwrap_err().to_string();
+			assert_eq!(message.lines().nth(2), Some(caret), "{message}");
+		}
+
+		assert_err(r#"client.name ~ "Acme" and"#, r#"                            ^"#);
+		assert_err(r#"client.name "Acme""#, r#"                ^^^^^^"#);
+		assert_err(r#"client..name = "Acme""#, r#"    ^^^^^^^^^^^^"#);
+		assert_err(r#"(id = 3"#, r#"    ^"#);
+		assert_err(r#"id = 3)"#, r#"          ^"#);
+		assert_err(r#"notes ! "foo""#, r#"          ^"#);
+		assert_err(r#"notes = "foo"#, r#"            ^^^^"#);
+		assert_err(r#"date_close > null"#, r#"    ^^^^^^^^^^^^^^^^^"#);
+		assert_err(r#"id = 3 or notes ~ "foo""#, r#"           ^^"#);
+		assert_err(r#"not (id = 3 and notes ~ "foo")"#, r#"    ^^^"#);
+		assert_err(r#"client = 3 and client.name ~ "a""#, r#"                   ^^^^^^^^^^^^^^^^^"#);
+		assert_err(r#"client.nam ~ "a""#, r#"    ^^^^^^^^^^^^^^^^"#);
+
+		assert_err(r#"notes ~ "foo" and id = "three""#, r#"                      ^^^^^^^^^^^^"#);
+		assert_err(r#"not id = null"#, r#"        ^^^^^^^^^"#);
+
+		let message = parse::<MatchJob>(r#"id = "three""#).unwrap_err().to_string();
+		assert!(message.contains("`id` cannot be matched this way"), "{message}");
+	}
+
+	#[test]
+	fn job()
+	{
+		assert_eq!(parse::<MatchJob>(r#"client.name ~ "Acme" and date_close = null"#).unwrap(), MatchJob {
+			client: MatchOrganization { name: MatchStr::Contains("Acme".into()), ..Default::default() },
+			date_close: MatchOption::None,
+			..Default::default()
+		});
+
+		assert_eq!(parse::<MatchJob>("(id = 1 or id = 2 or id > 10) and not objectives =~ ^Test").unwrap(), MatchJob {
+			id: Match::Or(vec![1.into(), 2.into(), Match::GreaterThan(10)]),
+			objectives: MatchStr::Not(MatchStr::Regex("^Test".into()).into()),
+			..Default::default()
+		});
+
+		assert_eq!(parse::<MatchJob>("not date_close = null").unwrap(), MatchJob {
+			date_close: MatchOption::Some(Match::Any),
+			..Default::default()
+		});
+	}
+
+	#[test]
+	fn literal()
+	{
+		assert_eq!(
+			parse::<MatchTimesheet>("id = 7 and (employee.name = 007 or employee.name = true)").unwrap(),
+			MatchTimesheet {
+				employee: MatchEmployee {
+					name: MatchStr::Or(vec!["007".to_owned().into(), "true".to_owned().into()]),
+					..Default::default()
+				},
+				id: 7.into(),
+				..Default::default()
+			}
+		);
+	}
+
+	#[test]
+	fn timesheet()
+	{
+		let (begin, end) =
+			(NaiveDate::from_ymd(2022, 1, 1).and_hms(0, 0, 0), NaiveDate::from_ymd(2022, 2, 1).and_hms(0, 0, 0));
+		assert_eq!(
+			parse::<MatchTimesheet>(
+				r#"time_begin >= 2022-01-01T00:00:00 AND time_begin < 2022-02-01T00:00:00 and employee.name != "Bob""#
+			)
+			.unwrap(),
+			MatchTimesheet {
+				employee: MatchEmployee {
+					name: MatchStr::Not(MatchStr::EqualTo("Bob".into()).into()),
+					..Default::default()
+				},
+				time_begin: Match::And(vec![
+					Match::Or(vec![Match::GreaterThan(begin), begin.into()]),
+					Match::LessThan(end),
+				]),
+				..Default::default()
+			}
+		);
+	}
+}
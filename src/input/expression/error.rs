@@ -0,0 +1,42 @@
+use core::ops::Range;
+
+use thiserror::Error;
+
+/// An [`Error`](std::error::Error) for when an [expression](super::parse) could not be parsed, or
+/// does not describe a valid match condition.
+#[derive(Clone, Debug, Eq, Error, Hash, PartialEq)]
+#[error("Invalid match expression: {message}\n    {expression}{}", self.caret())]
+pub struct Error
+{
+	/// The expression which could not be parsed.
+	expression: String,
+
+	/// A description of the problem.
+	message: String,
+
+	/// The byte range of the `expression` which is the cause of the problem, if there is one.
+	span: Option<Range<usize>>,
+}
+
+impl Error
+{
+	/// Create a new [`Error`] about the `span` of some `expression`.
+	pub fn new<E, M>(expression: E, span: Option<Range<usize>>, message: M) -> Self
+	where
+		E: Into<String>,
+		M: Into<String>,
+	{
+		Self { expression: expression.into(), message: message.into(), span }
+	}
+
+	/// A line which points at the `span` of the `expression` using carets (`^`), or nothing if
+	/// there is no `span`.
+	fn caret(&self) -> String
+	{
+		self.span.as_ref().map_or_else(String::new, |span| {
+			let offset = self.expression.get(..span.start).map_or(span.start, |s| s.chars().count());
+			let width = self.expression.get(span.clone()).map_or(1, |s| s.chars().count().max(1));
+			format!("\n    {}{}", " ".repeat(offset), "^".repeat(width))
+		})
+	}
+}
@@ -0,0 +1,204 @@
+use core::{iter::Peekable, ops::Range};
+use std::vec::IntoIter;
+
+use serde_yaml::{self as yaml, Value};
+
+use super::{
+	token::{self, Kind, Operator, Token},
+	Error,
+};
+use crate::fmt;
+
+/// The message for when an [`Operator`] was expected.
+const EXPECTED_OPERATOR: &str = "expected one of `=`, `!=`, `~`, `=~`, `<`, `<=`, `>`, or `>=`";
+
+/// A parsed expression.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Node
+{
+	/// Every [`Node`] must match.
+	And(Vec<Self>),
+
+	/// The `field` must compare to the `value` using the `operator`.
+	Comparison
+	{
+		/// The path to the field being compared (e.g. `["client", "name"]`).
+		field: Vec<String>,
+
+		/// How the `field` is compared to the `value`.
+		operator: Operator,
+
+		/// Where in the expression this comparison is, in bytes.
+		span: Range<usize>,
+
+		/// The value the `field` is compared to.
+		value: Value,
+
+		/// The `value` exactly as it was written, as a string (e.g. `"007"` rather than `7`).
+		literal: Value,
+	},
+
+	/// The [`Node`] must not match. The span of the `not` keyword is included.
+	Not(Box<Self>, Range<usize>),
+
+	/// Any [`Node`] must match. The span of the first `or` keyword is included.
+	Or(Vec<Self>, Range<usize>),
+}
+
+/// A recursive-descent parser for expressions.
+///
+/// ```text
+/// or         = and ("or" and)*
+/// and        = not ("and" not)*
+/// not        = "not" not | primary
+/// primary    = "(" or ")" | comparison
+/// comparison = field operator value
+/// ```
+pub struct Parser<'expr>
+{
+	/// The expression being parsed.
+	expression: &'expr str,
+
+	/// The [`Token`]s of the `expression` which have not been parsed yet.
+	tokens: Peekable<IntoIter<Token>>,
+}
+
+impl<'expr> Parser<'expr>
+{
+	/// Parse the `expression` into a [`Node`].
+	pub fn parse(expression: &'expr str) -> Result<Node, Error>
+	{
+		let tokens = token::tokenize(expression)?;
+		let mut parser = Self { expression, tokens: tokens.into_iter().peekable() };
+		let node = parser.or()?;
+
+		match parser.tokens.next()
+		{
+			Some(t) => Err(parser.error(t.span, "expected `and`, `or`, or the end of the expression")),
+			None => Ok(node),
+		}
+	}
+
+	/// `and = not ("and" not)*`
+	fn and(&mut self) -> Result<Node, Error>
+	{
+		let mut nodes = vec![self.not()?];
+		while self.tokens.next_if(|t| t.kind == Kind::And).is_some()
+		{
+			nodes.push(self.not()?);
+		}
+
+		Ok(match nodes.len()
+		{
+			1 => nodes.remove(0),
+			_ => Node::And(nodes),
+		})
+	}
+
+	/// `comparison = field operator value`, where the `field` has already been parsed.
+	fn comparison(&mut self, field: String, field_span: Range<usize>) -> Result<Node, Error>
+	{
+		let path: Vec<_> = field.split('.').map(str::to_owned).collect();
+		if path.iter().any(String::is_empty)
+		{
+			return Err(self.error(field_span, format!("{} is not a valid field name", fmt::quoted(&field))));
+		}
+
+		let operator = match self.tokens.next()
+		{
+			Some(Token { kind: Kind::Operator(o), .. }) => o,
+			Some(t) => return Err(self.error(t.span, EXPECTED_OPERATOR)),
+			None => return Err(self.error(self.end(), EXPECTED_OPERATOR)),
+		};
+
+		let (value, literal, value_span) = match self.tokens.next()
+		{
+			Some(Token { kind: Kind::Quoted(s), span }) => (Value::String(s.clone()), Value::String(s), span),
+			Some(Token { kind: Kind::Word(w), span }) => (unquoted(w.clone()), Value::String(w), span),
+			Some(t) => return Err(self.error(t.span, "expected a value")),
+			None => return Err(self.error(self.end(), "expected a value")),
+		};
+
+		let span = field_span.start..value_span.end;
+		if value.is_null() && operator != Operator::EqualTo
+		{
+			return Err(self.error(span, "`null` can only be compared using `=`"));
+		}
+
+		Ok(Node::Comparison { field: path, literal, operator, span, value })
+	}
+
+	/// The span just past the end of the expression.
+	const fn end(&self) -> Range<usize>
+	{
+		self.expression.len()..self.expression.len() + 1
+	}
+
+	/// Create an [`Error`] about the `span` of the expression.
+	fn error<M>(&self, span: Range<usize>, message: M) -> Error
+	where
+		M: Into<String>,
+	{
+		Error::new(self.expression, Some(span), message)
+	}
+
+	/// `not = "not" not | primary`
+	fn not(&mut self) -> Result<Node, Error>
+	{
+		match self.tokens.next_if(|t| t.kind == Kind::Not)
+		{
+			Some(t) => Ok(Node::Not(Box::new(self.not()?), t.span)),
+			None => self.primary(),
+		}
+	}
+
+	/// `or = and ("or" and)*`
+	fn or(&mut self) -> Result<Node, Error>
+	{
+		let mut nodes = vec![self.and()?];
+		let mut or_span = None;
+		while let Some(t) = self.tokens.next_if(|t| t.kind == Kind::Or)
+		{
+			or_span.get_or_insert(t.span);
+			nodes.push(self.and()?);
+		}
+
+		Ok(match or_span
+		{
+			Some(span) => Node::Or(nodes, span),
+			None => nodes.remove(0),
+		})
+	}
+
+	/// `primary = "(" or ")" | comparison`
+	fn primary(&mut self) -> Result<Node, Error>
+	{
+		match self.tokens.next()
+		{
+			Some(Token { kind: Kind::LeftParen, span }) =>
+			{
+				let node = self.or()?;
+				match self.tokens.next()
+				{
+					Some(Token { kind: Kind::RightParen, .. }) => Ok(node),
+					Some(t) => Err(self.error(t.span, "expected `)`")),
+					None => Err(self.error(span, "this `(` is never closed")),
+				}
+			},
+			Some(Token { kind: Kind::Word(field), span }) => self.comparison(field, span),
+			Some(t) => Err(self.error(t.span, "expected a field name, `not`, or `(`")),
+			None => Err(self.error(self.end(), "expected a condition")),
+		}
+	}
+}
+
+/// Interpret an unquoted `word` as a YAML scalar (e.g. a number, boolean, or `null`), or as a
+/// string if it is not one.
+fn unquoted(word: String) -> Value
+{
+	match yaml::from_str(&word)
+	{
+		Ok(Value::Mapping(_) | Value::Sequence(_)) | Err(_) => Value::String(word),
+		Ok(value) => value,
+	}
+}
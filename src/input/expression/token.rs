@@ -0,0 +1,164 @@
+use core::{iter::Peekable, ops::Range, str::CharIndices};
+
+use super::Error;
+
+/// A comparison between a field and a value.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Operator
+{
+	/// `~`
+	Contains,
+
+	/// `=`
+	EqualTo,
+
+	/// `>`
+	GreaterThan,
+
+	/// `>=`
+	GreaterThanOrEqualTo,
+
+	/// `<`
+	LessThan,
+
+	/// `<=`
+	LessThanOrEqualTo,
+
+	/// `!=`
+	NotEqualTo,
+
+	/// `=~`
+	Regex,
+}
+
+/// The kind of a [`Token`].
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Kind
+{
+	/// The `and` keyword.
+	And,
+
+	/// `(`
+	LeftParen,
+
+	/// The `not` keyword.
+	Not,
+
+	/// A comparison [`Operator`].
+	Operator(Operator),
+
+	/// The `or` keyword.
+	Or,
+
+	/// A `"quoted string"`, with its escape sequences (`\"`, `\\`, `\n`, `\t`) resolved.
+	Quoted(String),
+
+	/// `)`
+	RightParen,
+
+	/// A sequence of characters which is not a keyword, e.g. a field name or an unquoted value.
+	Word(String),
+}
+
+/// A piece of an expression.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Token
+{
+	/// What was found.
+	pub kind: Kind,
+
+	/// Where in the expression it was found, in bytes.
+	pub span: Range<usize>,
+}
+
+/// Whether `c` ends a [`Kind::Word`].
+fn ends_word(c: char) -> bool
+{
+	c.is_whitespace() || "()\"=!<>~".contains(c)
+}
+
+/// Split the `expression` into [`Token`]s.
+pub fn tokenize(expression: &str) -> Result<Vec<Token>, Error>
+{
+	/// If the next character is `expected`, skip it and return `Some(then)`.
+	fn next_if_eq<T>(chars: &mut Peekable<CharIndices>, expected: char, then: T) -> Option<T>
+	{
+		chars.next_if(|(_, c)| *c == expected).map(|_| then)
+	}
+
+	let unterminated = |start: usize| Error::new(expression, Some(start..expression.len()), "unterminated string");
+	let mut chars = expression.char_indices().peekable();
+	let mut tokens = Vec::new();
+
+	while let Some((start, c)) = chars.next()
+	{
+		let kind = match c
+		{
+			c if c.is_whitespace() => continue,
+			'(' => Kind::LeftParen,
+			')' => Kind::RightParen,
+			'~' => Kind::Operator(Operator::Contains),
+			'=' => Kind::Operator(next_if_eq(&mut chars, '~', Operator::Regex).unwrap_or(Operator::EqualTo)),
+			'<' =>
+			{
+				Kind::Operator(next_if_eq(&mut chars, '=', Operator::LessThanOrEqualTo).unwrap_or(Operator::LessThan))
+			},
+			'>' => Kind::Operator(
+				next_if_eq(&mut chars, '=', Operator::GreaterThanOrEqualTo).unwrap_or(Operator::GreaterThan),
+			),
+			'!' => next_if_eq(&mut chars, '=', Kind::Operator(Operator::NotEqualTo))
+				.ok_or_else(|| Error::new(expression, Some(start..start + 1), "expected `=` after `!`"))?,
+			'"' =>
+			{
+				let mut quoted = String::new();
+				loop
+				{
+					match chars.next()
+					{
+						Some((_, '"')) => break,
+						Some((_, '\\')) => match chars.next()
+						{
+							Some((_, 'n')) => quoted.push('\n'),
+							Some((_, 't')) => quoted.push('\t'),
+							Some((_, escaped @ ('"' | '\\'))) => quoted.push(escaped),
+							Some((i, _)) =>
+							{
+								return Err(Error::new(
+									expression,
+									Some(i - 1..i + 1),
+									r#"unknown escape sequence (expected one of `\"`, `\\`, `\n`, or `\t`)"#,
+								))
+							},
+							None => return Err(unterminated(start)),
+						},
+						Some((_, c)) => quoted.push(c),
+						None => return Err(unterminated(start)),
+					}
+				}
+
+				Kind::Quoted(quoted)
+			},
+			c =>
+			{
+				let mut word = String::from(c);
+				while let Some((_, c)) = chars.next_if(|(_, c)| !ends_word(*c))
+				{
+					word.push(c);
+				}
+
+				match word.to_lowercase().as_str()
+				{
+					"and" => Kind::And,
+					"not" => Kind::Not,
+					"or" => Kind::Or,
+					_ => Kind::Word(word),
+				}
+			},
+		};
+
+		let end = chars.peek().map_or(expression.len(), |(i, _)| *i);
+		tokens.push(Token { kind, span: start..end });
+	}
+
+	Ok(tokens)
+}
@@ -0,0 +1,59 @@
+//! This module contains the [`Prompter`] trait, which abstracts over how Winvoice asks questions
+//! while running a command, along with its implementations.
+
+mod auto_accept;
+mod scripted;
+mod terminal;
+
+use std::io;
+
+pub use auto_accept::AutoAccept;
+#[cfg(test)]
+pub use scripted::Answer;
+pub use scripted::Scripted;
+pub use terminal::Terminal;
+
+/// A source of answers to the questions that Winvoice asks while running a command.
+///
+/// * [`Terminal`] asks the user.
+/// * [`Scripted`] answers using a list of [`Answer`](scripted::Answer)s that were provided ahead of time.
+/// * [`AutoAccept`] accepts the default answer to every question.
+pub trait Prompter
+{
+	/// `prompt` with a yes/no question, suggesting the `default` answer.
+	fn confirm(&self, prompt: &str, default: bool) -> io::Result<bool>;
+
+	/// Edit some `text`, which is the content of a file with the given `extension` (e.g. ".yaml").
+	///
+	/// # Returns
+	///
+	/// * [`Ok(Some)`] with the edited `text`.
+	/// * [`Ok(None)`] if the `text` was not saved.
+	fn edit(&self, text: &str, extension: &str) -> io::Result<Option<String>>;
+
+	/// `prompt` to select any number of the `items`, returning the indices of the selections in
+	/// ascending order.
+	fn select(&self, items: &[String], prompt: &str) -> io::Result<Vec<usize>>;
+
+	/// [`select`](Prompter::select) any number of the `items` to be destroyed (e.g. deleted).
+	///
+	/// The `items` were `narrowed` if they all matched a condition which was given ahead of time
+	/// (e.g. by `--match`), rather than one that the `Prompter` was asked for.
+	fn select_destructive(&self, items: &[String], prompt: &str, _narrowed: bool) -> io::Result<Vec<usize>>
+	{
+		self.select(items, prompt)
+	}
+
+	/// `prompt` to select exactly one of the `items` (suggesting the one at the `default` index),
+	/// returning the index of the selection.
+	fn select_one(&self, items: &[String], prompt: &str, default: usize) -> io::Result<usize>;
+
+	/// `prompt` to enter text, suggesting the `default`. Text is only accepted if it passes
+	/// `validate`, which describes why any given text is invalid.
+	fn text(
+		&self,
+		default: Option<&str>,
+		prompt: &str,
+		validate: &dyn Fn(&str) -> Result<(), String>,
+	) -> io::Result<String>;
+}
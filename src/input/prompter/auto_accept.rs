@@ -0,0 +1,93 @@
+use std::io;
+
+use super::Prompter;
+
+/// A [`Prompter`] which accepts everything it is offered without asking:
+///
+/// * Yes/no questions are answered with their default.
+/// * Text is left as it was, without edits.
+/// * All of the items are selected when selecting any number of them, unless they are being
+///   [destroyed](Prompter::select_destructive) and the items were not narrowed down ahead of time.
+/// * The default item is selected when selecting exactly one.
+///
+/// Asking for text which has no default is an [`Err`], since there is nothing to accept.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct AutoAccept;
+
+impl Prompter for AutoAccept
+{
+	fn confirm(&self, _: &str, default: bool) -> io::Result<bool>
+	{
+		Ok(default)
+	}
+
+	fn edit(&self, text: &str, _: &str) -> io::Result<Option<String>>
+	{
+		Ok(Some(text.to_owned()))
+	}
+
+	fn select(&self, items: &[String], _: &str) -> io::Result<Vec<usize>>
+	{
+		Ok((0..items.len()).collect())
+	}
+
+	fn select_destructive(&self, items: &[String], prompt: &str, narrowed: bool) -> io::Result<Vec<usize>>
+	{
+		match narrowed
+		{
+			false => Err(io::Error::new(
+				io::ErrorKind::InvalidInput,
+				format!(
+					"Refusing to select all {} items for the prompt {prompt:?} without asking; narrow them down with \
+					 --match, --query, or --where",
+					items.len(),
+				),
+			)),
+			true => self.select(items, prompt),
+		}
+	}
+
+	fn select_one(&self, items: &[String], prompt: &str, default: usize) -> io::Result<usize>
+	{
+		match default < items.len()
+		{
+			false => Err(io::Error::new(
+				io::ErrorKind::InvalidInput,
+				format!("There was nothing to select for the prompt {prompt:?}"),
+			)),
+			true => Ok(default),
+		}
+	}
+
+	fn text(
+		&self,
+		default: Option<&str>,
+		prompt: &str,
+		validate: &dyn Fn(&str) -> Result<(), String>,
+	) -> io::Result<String>
+	{
+		let text = default.ok_or_else(|| {
+			io::Error::new(io::ErrorKind::InvalidInput, format!("There was no default text for the prompt {prompt:?}"))
+		})?;
+
+		validate(text).map(|_| text.to_owned()).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use std::io;
+
+	use pretty_assertions::assert_eq;
+
+	use super::{AutoAccept, Prompter};
+
+	#[test]
+	fn select_destructive()
+	{
+		let items = ["foo".to_owned(), "bar".to_owned()];
+		assert_eq!(AutoAccept.select_destructive(&items, "", true).unwrap(), [0, 1]);
+		assert_eq!(AutoAccept.select_destructive(&items, "", false).unwrap_err().kind(), io::ErrorKind::InvalidInput);
+	}
+}
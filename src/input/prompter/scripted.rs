@@ -0,0 +1,181 @@
+mod answer;
+mod from;
+
+use core::cell::RefCell;
+use std::{collections::VecDeque, fs, io, path::Path};
+
+pub use answer::Answer;
+use serde_yaml as yaml;
+
+use super::Prompter;
+use crate::input::Result;
+
+/// A [`Prompter`] which answers each question using the next of some [`Answer`]s that were
+/// provided ahead of time (e.g. by a script, or a test).
+///
+/// It is an [`Err`] to ask a question which does not match the kind of the next [`Answer`], or to
+/// ask more questions than there are [`Answer`]s.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Scripted
+{
+	/// The [`Answer`]s which have not been given yet.
+	answers: RefCell<VecDeque<Answer>>,
+}
+
+impl Scripted
+{
+	/// Read a list of [`Answer`]s from the YAML file at `filepath`.
+	pub fn read(filepath: &Path) -> Result<Self>
+	{
+		let contents = fs::read_to_string(filepath)?;
+		let answers: Vec<Answer> = yaml::from_str(&contents)?;
+		Ok(answers.into())
+	}
+
+	/// Take the next [`Answer`] for the `prompt`.
+	fn next(&self, prompt: &str) -> io::Result<Answer>
+	{
+		self.answers.borrow_mut().pop_front().ok_or_else(|| {
+			io::Error::new(io::ErrorKind::UnexpectedEof, format!("There was no answer for the prompt {prompt:?}"))
+		})
+	}
+
+	/// Create an [`io::Error`] indicating that the `answer` to the `prompt` was not the `expected`
+	/// kind.
+	fn mismatch(answer: &Answer, prompt: &str, expected: &str) -> io::Error
+	{
+		io::Error::new(
+			io::ErrorKind::InvalidInput,
+			format!("Expected a {expected} answer for the prompt {prompt:?}, but the answer was {answer:?}"),
+		)
+	}
+
+	/// Create an [`io::Error`] indicating that the `index` is not one of the `items`.
+	fn out_of_bounds(index: usize, items: &[String], prompt: &str) -> io::Error
+	{
+		io::Error::new(
+			io::ErrorKind::InvalidInput,
+			format!("Selected index {index} for the prompt {prompt:?}, but there are only {} items", items.len()),
+		)
+	}
+}
+
+impl Prompter for Scripted
+{
+	fn confirm(&self, prompt: &str, _: bool) -> io::Result<bool>
+	{
+		match self.next(prompt)?
+		{
+			Answer::Confirm(b) => Ok(b),
+			answer => Err(Self::mismatch(&answer, prompt, "confirm")),
+		}
+	}
+
+	fn edit(&self, _: &str, extension: &str) -> io::Result<Option<String>>
+	{
+		let prompt = format!("Edit the {extension} file");
+		match self.next(&prompt)?
+		{
+			Answer::Edit(edited) => Ok(edited),
+			answer => Err(Self::mismatch(&answer, &prompt, "edit")),
+		}
+	}
+
+	fn select(&self, items: &[String], prompt: &str) -> io::Result<Vec<usize>>
+	{
+		match self.next(prompt)?
+		{
+			Answer::Select(mut indices) =>
+			{
+				if let Some(i) = indices.iter().find(|i| **i >= items.len())
+				{
+					return Err(Self::out_of_bounds(*i, items, prompt));
+				}
+
+				indices.sort_unstable();
+				indices.dedup();
+				Ok(indices)
+			},
+			answer => Err(Self::mismatch(&answer, prompt, "select")),
+		}
+	}
+
+	fn select_one(&self, items: &[String], prompt: &str, _: usize) -> io::Result<usize>
+	{
+		match self.next(prompt)?
+		{
+			Answer::SelectOne(i) if i >= items.len() => Err(Self::out_of_bounds(i, items, prompt)),
+			Answer::SelectOne(i) => Ok(i),
+			answer => Err(Self::mismatch(&answer, prompt, "select_one")),
+		}
+	}
+
+	fn text(
+		&self,
+		_: Option<&str>,
+		prompt: &str,
+		validate: &dyn Fn(&str) -> core::result::Result<(), String>,
+	) -> io::Result<String>
+	{
+		match self.next(prompt)?
+		{
+			Answer::Text(text) =>
+			{
+				validate(&text).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+				Ok(text)
+			},
+			answer => Err(Self::mismatch(&answer, prompt, "text")),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use std::io;
+
+	use pretty_assertions::assert_eq;
+
+	use super::{Answer, Prompter, Scripted};
+	use crate::utils;
+
+	#[test]
+	fn prompter()
+	{
+		let items = ["foo".to_owned(), "bar".to_owned(), "baz".to_owned()];
+		let scripted = Scripted::from(vec![
+			Answer::Confirm(true),
+			Answer::Edit(None),
+			Answer::Select(vec![2, 0, 2]),
+			Answer::SelectOne(1),
+			Answer::Text("3".into()),
+			Answer::Text("three".into()),
+			Answer::SelectOne(3),
+			Answer::Confirm(false),
+		]);
+
+		let is_number = |s: &str| s.parse::<u8>().map(drop).map_err(|e| e.to_string());
+
+		assert!(scripted.confirm("", false).unwrap());
+		assert_eq!(scripted.edit("", ".yaml").unwrap(), None);
+		assert_eq!(scripted.select(&items, "").unwrap(), [0, 2]);
+		assert_eq!(scripted.select_one(&items, "", 0).unwrap(), 1);
+		assert_eq!(scripted.text(None, "", &is_number).unwrap(), "3");
+		assert_eq!(scripted.text(None, "", &is_number).unwrap_err().kind(), io::ErrorKind::InvalidData);
+		assert_eq!(scripted.select_one(&items, "", 0).unwrap_err().kind(), io::ErrorKind::InvalidInput);
+		assert_eq!(scripted.select(&items, "").unwrap_err().kind(), io::ErrorKind::InvalidInput);
+		assert_eq!(scripted.confirm("", true).unwrap_err().kind(), io::ErrorKind::UnexpectedEof);
+	}
+
+	#[test]
+	fn read()
+	{
+		let filepath = utils::temp_file::<Scripted>("read");
+		utils::write_yaml(&filepath, [Answer::Confirm(true), Answer::Select(vec![1]), Answer::Text("foo".into())]);
+
+		let scripted = Scripted::read(&filepath).unwrap();
+		assert!(scripted.confirm("", false).unwrap());
+		assert_eq!(scripted.select(&["a".into(), "b".into()], "").unwrap(), [1]);
+		assert_eq!(scripted.text(None, "", &|_| Ok(())).unwrap(), "foo");
+	}
+}
@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+
+/// An answer to one of the questions asked of a [`Prompter`](super::super::Prompter), which has
+/// been provided ahead of time.
+///
+/// ```yaml
+/// - confirm: true
+/// - edit: null # do not save any edits
+/// - select: [0, 2]
+/// - select_one: 1
+/// - text: "20.00 USD"
+/// ```
+#[derive(Clone, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Answer
+{
+	/// The answer to a yes/no question.
+	Confirm(bool),
+
+	/// The text that results from editing, or [`None`] if no edits were saved.
+	Edit(Option<String>),
+
+	/// The indices of the items to select (of which there may be any number).
+	Select(Vec<usize>),
+
+	/// The index of the item to select.
+	SelectOne(usize),
+
+	/// Text to enter.
+	Text(String),
+}
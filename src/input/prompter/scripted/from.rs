@@ -0,0 +1,11 @@
+use core::cell::RefCell;
+
+use super::{Answer, Scripted};
+
+impl From<Vec<Answer>> for Scripted
+{
+	fn from(answers: Vec<Answer>) -> Self
+	{
+		Self { answers: RefCell::new(answers.into()) }
+	}
+}
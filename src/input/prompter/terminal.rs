@@ -0,0 +1,66 @@
+use std::io;
+
+use dialoguer::{Confirm, Editor, Input, MultiSelect, Select};
+
+use super::Prompter;
+
+/// A [`Prompter`] which asks the user via the terminal (and their text editor, which is defined
+/// by the "VISUAL" or "EDITOR" environment variables, or the platform default).
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Terminal;
+
+impl Prompter for Terminal
+{
+	fn confirm(&self, prompt: &str, default: bool) -> io::Result<bool>
+	{
+		Confirm::new().default(default).with_prompt(prompt).interact()
+	}
+
+	fn edit(&self, text: &str, extension: &str) -> io::Result<Option<String>>
+	{
+		Editor::new().extension(extension).edit(text)
+	}
+
+	fn select(&self, items: &[String], prompt: &str) -> io::Result<Vec<usize>>
+	{
+		MultiSelect::new().items(items).with_prompt(prompt).interact()
+	}
+
+	fn select_one(&self, items: &[String], prompt: &str, default: usize) -> io::Result<usize>
+	{
+		// {{{
+		let mut s = Select::new();
+		s.items(items).default(default).with_prompt(prompt);
+		// }}}
+
+		let selector = s;
+		loop
+		{
+			match selector.interact()
+			{
+				Err(e) if e.kind() == io::ErrorKind::Other && e.to_string().contains("Quit not allowed") =>
+				{
+					println!("Please select something, or press Ctrl+C to quit");
+				},
+				result => return result,
+			};
+		}
+	}
+
+	fn text(
+		&self,
+		default: Option<&str>,
+		prompt: &str,
+		validate: &dyn Fn(&str) -> Result<(), String>,
+	) -> io::Result<String>
+	{
+		let mut input = Input::<String>::new();
+
+		if let Some(text) = default
+		{
+			input.default(text.to_owned());
+		}
+
+		input.with_prompt(prompt).validate_with(|text: &String| validate(text)).interact_text()
+	}
+}
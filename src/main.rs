@@ -19,7 +19,7 @@
 //! cargo install winvoice --features=<adapters>
 //! ```
 //!
-//! * Any desired storage implementations (e.g. Bincode, PostgreSQL) should be listed in place of `<adapters>`.
+//! * Any desired storage implementations (e.g. PostgreSQL, SQLite) should be listed in place of `<adapters>`.
 //!
 //! > __NOTE__: This application has not yet been uploaded to [crates.io][crates]!
 //!
@@ -36,6 +36,15 @@
 //! of [`Config`](winvoice_config::Config) for more information about the configuration file and
 //! its options.
 //!
+//! A store can keep its data in a SQLite database file instead of PostgreSQL, when Winvoice is
+//! built with the `sqlite` feature. Run `winvoice init -s <store>` once before using it:
+//!
+//! ```toml
+//! [stores.default]
+//! adapter = "sqlite"
+//! url = "sqlite:///home/user/winvoice.db?mode=rwc"
+//! ```
+//!
 //! # Usage
 //!
 //! * For basic information, run `winvoice help` from the command line.
@@ -108,19 +117,38 @@
 	clippy::wildcard_imports
 )]
 
+mod archive;
 mod args;
+mod config;
+mod discounts;
 mod dyn_result;
+mod exchange;
+mod export;
 mod fmt;
+mod import;
 mod input;
+mod migration;
+mod numbering;
+mod payments;
+#[cfg(feature = "sqlite")]
+mod sqlite;
 mod utils;
 
+use std::process::ExitCode;
+
 use args::Args;
 use clap::Parser;
 use dyn_result::DynResult;
 
 /// Interprets arguments `winvoice` (if any) and executes the implied instruction.
+///
+/// Exits with the status of the instruction (e.g. `2` when `winvoice aging` finds overdue
+/// invoices), or `1` for any error.
 #[tokio::main(flavor = "current_thread")]
-async fn main() -> DynResult<()>
+async fn main() -> ExitCode
 {
-	Args::parse().run().await
+	Args::parse().run().await.unwrap_or_else(|e| {
+		eprintln!("Error: {e}");
+		ExitCode::FAILURE
+	})
 }
@@ -0,0 +1,250 @@
+//! Versions of the schema of a store, and the migrations which upgrade a store from one version to
+//! the next.
+//!
+//! The version of a store is recorded in its `winvoice_version` table. Stores which were
+//! initialized before the schema was versioned do not have this table, and are version `0`.
+
+use core::fmt::Write;
+
+use sqlx::{ColumnIndex, Connection, Database, Decode, Executor, Pool, Row, Type};
+use thiserror::Error;
+
+use crate::{config::Adapter, DynResult};
+
+/// The table which records the version of a store.
+const TABLE: &str = "winvoice_version";
+
+/// Every [`Migration`], in the order that they must be applied.
+///
+/// Since `winvoice init` applies these to new stores after the adapter has
+/// initialized them, the adapters are expected to create the schema as of version `0`; every
+/// change to the schema after that must be made by a new [`Migration`]. The [`Migration`] which
+/// creates the tables of a module (e.g. [`payments`](crate::payments)) is declared in that module.
+const MIGRATIONS: [Migration; 5] = [
+	Migration {
+		description: "Record the version of the schema",
+		postgres: &["CREATE TABLE winvoice_version (
+			version BIGINT NOT NULL PRIMARY KEY,
+			applied TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+		)"],
+		sqlite: &["CREATE TABLE winvoice_version (
+			version INTEGER NOT NULL PRIMARY KEY,
+			applied TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+		)"],
+		version: 1,
+	},
+	crate::payments::MIGRATION,
+	crate::numbering::MIGRATIONS[0],
+	crate::numbering::MIGRATIONS[1],
+	crate::discounts::MIGRATION,
+];
+
+/// The version of the schema which this version of Winvoice uses.
+pub const VERSION: i64 = MIGRATIONS[MIGRATIONS.len() - 1].version;
+
+/// An [`Error`](std::error::Error) for the version of a store.
+#[derive(Clone, Copy, Debug, Eq, Error, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Error
+{
+	#[allow(missing_docs)]
+	#[error(
+		"The store is version {0}, but this version of Winvoice only supports up to version {VERSION}; upgrade \
+		 Winvoice to use it"
+	)]
+	Newer(i64),
+
+	#[allow(missing_docs)]
+	#[error(
+		"The store is version {0}, but this version of Winvoice requires version {VERSION}; upgrade the store with \
+		 `winvoice migrate`"
+	)]
+	Older(i64),
+}
+
+/// A change to the schema of a store, which upgrades it to some `version`.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Migration
+{
+	/// What the [`Migration`] changes.
+	pub description: &'static str,
+
+	/// The statements which apply the [`Migration`] to a Postgres store.
+	pub(crate) postgres: &'static [&'static str],
+
+	/// The statements which apply the [`Migration`] to a SQLite store.
+	pub(crate) sqlite: &'static [&'static str],
+
+	/// The version of the schema once the [`Migration`] has been applied.
+	pub version: i64,
+}
+
+impl Migration
+{
+	/// The statements which apply this [`Migration`] to a store using some `adapter`, including
+	/// the one which records its [`version`](Migration::version).
+	pub fn sql(&self, adapter: Adapter) -> Vec<String>
+	{
+		let statements = match adapter
+		{
+			Adapter::Postgres => self.postgres,
+			Adapter::Sqlite => self.sqlite,
+		};
+
+		statements
+			.iter()
+			.map(|s| (*s).to_owned())
+			.chain([format!("INSERT INTO {TABLE} (version) VALUES ({})", self.version)])
+			.collect()
+	}
+}
+
+/// Apply every [`Migration`] which is `pending` to the store which the `connection` is to, using
+/// some `adapter`, in one transaction.
+///
+/// # Errors
+///
+/// If any [`Migration`] could not be applied, in which case none of them are.
+pub async fn apply<Db>(connection: &mut Db::Connection, adapter: Adapter, pending: &[Migration]) -> DynResult<()>
+where
+	Db: Database,
+	for<'connection> &'connection mut Db::Connection: Executor<'connection, Database = Db>,
+{
+	let mut transaction = connection.begin().await?;
+	for migration in pending
+	{
+		for statement in migration.sql(adapter)
+		{
+			(&mut *transaction).execute(statement.as_str()).await?;
+		}
+
+		println!("Migrated to version {}: {}", migration.version, migration.description);
+	}
+
+	transaction.commit().await?;
+	Ok(())
+}
+
+/// Make sure that the store in the `pool`, which uses some `adapter`, is the [`VERSION`] which this
+/// version of Winvoice uses.
+///
+/// # Errors
+///
+/// * [`Error::Newer`], if the store is newer than this version of Winvoice.
+/// * [`Error::Older`], if the store has [`Migration`]s which are [pending].
+/// * If the version could not be retrieved.
+pub async fn check<Db>(pool: &Pool<Db>, adapter: Adapter) -> DynResult<()>
+where
+	Db: Database,
+	i64: Type<Db> + for<'row> Decode<'row, Db>,
+	usize: ColumnIndex<Db::Row>,
+	for<'connection> &'connection mut Db::Connection: Executor<'connection, Database = Db>,
+{
+	let mut connection = pool.acquire().await?;
+	let version = version::<Db>(&mut connection, adapter).await?;
+	match pending(version)?.is_empty()
+	{
+		false => Err(Error::Older(version).into()),
+		true => Ok(()),
+	}
+}
+
+/// The [`Migration`]s which must be applied to a store at some `version`, in order.
+///
+/// # Errors
+///
+/// [`Error::Newer`], if the `version` is newer than this version of Winvoice.
+pub fn pending(version: i64) -> Result<&'static [Migration], Error>
+{
+	match version > VERSION
+	{
+		false => Ok(&MIGRATIONS[MIGRATIONS.partition_point(|m| m.version <= version)..]),
+		true => Err(Error::Newer(version)),
+	}
+}
+
+/// The statements of the `pending` [`Migration`]s for a store using some `adapter`, as a SQL
+/// script.
+pub fn script(adapter: Adapter, pending: &[Migration]) -> String
+{
+	pending.iter().fold(String::new(), |mut script, migration| {
+		writeln!(script, "-- Version {}: {}", migration.version, migration.description)
+			.expect("writing to a `String` should not fail");
+		migration.sql(adapter).into_iter().for_each(|s| {
+			writeln!(script, "{s};").expect("writing to a `String` should not fail");
+		});
+
+		script
+	})
+}
+
+/// [Apply](apply) the [`Migration`]s which are [pending] for the store which the `connection` is to,
+/// using some `adapter`.
+///
+/// # Errors
+///
+/// * [`Error::Newer`], if the store is newer than this version of Winvoice.
+/// * If the version could not be retrieved, or the [`Migration`]s could not be applied.
+pub async fn upgrade<Db>(connection: &mut Db::Connection, adapter: Adapter) -> DynResult<()>
+where
+	Db: Database,
+	i64: Type<Db> + for<'row> Decode<'row, Db>,
+	usize: ColumnIndex<Db::Row>,
+	for<'connection> &'connection mut Db::Connection: Executor<'connection, Database = Db>,
+{
+	let pending = pending(version::<Db>(connection, adapter).await?)?;
+	apply::<Db>(connection, adapter, pending).await
+}
+
+/// The version of the store which the `connection` is to, using some `adapter`.
+///
+/// # Errors
+///
+/// If the version could not be retrieved.
+pub async fn version<Db>(connection: &mut Db::Connection, adapter: Adapter) -> DynResult<i64>
+where
+	Db: Database,
+	i64: Type<Db> + for<'row> Decode<'row, Db>,
+	usize: ColumnIndex<Db::Row>,
+	for<'connection> &'connection mut Db::Connection: Executor<'connection, Database = Db>,
+{
+	let exists = match adapter
+	{
+		Adapter::Postgres => format!("SELECT COUNT(*) FROM information_schema.tables WHERE table_name = '{TABLE}'"),
+		Adapter::Sqlite => format!("SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = '{TABLE}'"),
+	};
+
+	if (&mut *connection).fetch_one(exists.as_str()).await?.try_get::<i64, _>(0)? == 0
+	{
+		return Ok(0);
+	}
+
+	let row = (&mut *connection).fetch_one(format!("SELECT MAX(version) FROM {TABLE}").as_str()).await?;
+	Ok(row.try_get::<Option<i64>, _>(0)?.unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests
+{
+	use pretty_assertions::assert_eq;
+
+	use super::{Error, MIGRATIONS, VERSION};
+	use crate::config::Adapter;
+
+	#[test]
+	fn pending()
+	{
+		assert_eq!(super::pending(0), Ok(&MIGRATIONS[..]));
+		assert_eq!(super::pending(VERSION), Ok(&[][..]));
+		assert_eq!(super::pending(VERSION + 1), Err(Error::Newer(VERSION + 1)));
+		assert!(MIGRATIONS.windows(2).all(|w| w[0].version < w[1].version));
+	}
+
+	#[test]
+	fn script()
+	{
+		let script = super::script(Adapter::Sqlite, &MIGRATIONS[..1]);
+		assert!(script.starts_with("-- Version 1: Record the version of the schema\nCREATE TABLE winvoice_version ("));
+		assert!(script.ends_with(");\nINSERT INTO winvoice_version (version) VALUES (1);\n"));
+		assert_eq!(super::script(Adapter::Postgres, &[]), String::new());
+	}
+}
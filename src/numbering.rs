@@ -0,0 +1,624 @@
+//! Human-facing numbers for invoices (e.g. "INV-2022-0041"), which are assigned in sequence the
+//! first time the invoice of a `Job` is issued.
+//!
+//! Invoice numbers are kept in the store alongside the `Job`s they were assigned to (see
+//! [`Register`]), as are the dates that each invoice was due by when it was issued.
+
+use core::{
+	fmt::{Display, Formatter, Result as FmtResult},
+	result::Result as StdResult,
+	str::FromStr,
+};
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use sqlx::{Database, Result};
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+use sqlx::{Executor, Row};
+use thiserror::Error;
+use winvoice_schema::{
+	chrono::{DateTime, Datelike, Local, Utc},
+	Id,
+	Job,
+};
+
+use crate::{config::Invoices, fmt, migration::Migration};
+
+/// The [`Migration`]s which create the tables that a [`Register`] keeps invoice numbers and due
+/// dates in.
+pub(crate) const MIGRATIONS: [Migration; 2] = [
+	Migration {
+		description: "Keep the numbers assigned to invoices",
+		postgres: &[
+			"CREATE TABLE winvoice_invoice_numbers (
+				job_id BIGINT NOT NULL PRIMARY KEY REFERENCES jobs(id) ON DELETE CASCADE,
+				number TEXT NOT NULL UNIQUE
+			)",
+			"CREATE TABLE winvoice_invoice_series (
+				series TEXT NOT NULL PRIMARY KEY,
+				seq BIGINT NOT NULL
+			)",
+		],
+		sqlite: &[
+			"CREATE TABLE winvoice_invoice_numbers (
+				job_id INTEGER NOT NULL PRIMARY KEY REFERENCES jobs(id) ON DELETE CASCADE,
+				number TEXT NOT NULL UNIQUE
+			)",
+			"CREATE TABLE winvoice_invoice_series (
+				series TEXT NOT NULL PRIMARY KEY,
+				seq INTEGER NOT NULL
+			)",
+		],
+		version: 3,
+	},
+	Migration {
+		description: "Keep the dates that invoices were due by when they were issued",
+		postgres: &["CREATE TABLE winvoice_invoice_due_dates (
+			job_id BIGINT NOT NULL PRIMARY KEY REFERENCES jobs(id) ON DELETE CASCADE,
+			due TIMESTAMPTZ
+		)"],
+		sqlite: &["CREATE TABLE winvoice_invoice_due_dates (
+			job_id INTEGER NOT NULL PRIMARY KEY REFERENCES jobs(id) ON DELETE CASCADE,
+			due TEXT
+		)"],
+		version: 4,
+	},
+];
+
+/// An [`Error`](std::error::Error) for parsing a [`Pattern`].
+#[derive(Clone, Debug, Eq, Error, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Error
+{
+	#[allow(missing_docs)]
+	#[error("The invoice number pattern must contain {{seq}}")]
+	NoSequence,
+
+	#[allow(missing_docs)]
+	#[error("The invoice number pattern has an unclosed {{")]
+	Unclosed,
+
+	#[allow(missing_docs)]
+	#[error("{{{0}}} is not a placeholder that invoice number patterns can use")]
+	UnknownPlaceholder(String),
+}
+
+/// A part of a [`Pattern`].
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+enum Segment
+{
+	/// `{day}`: the day of the month that the invoice was issued, as two digits.
+	Day,
+
+	/// Text which appears as-is.
+	Literal(String),
+
+	/// `{month}`: the month that the invoice was issued, as two digits.
+	Month,
+
+	/// `{seq}` or `{seq:N}`: the place of the invoice in its sequence, padded with zeros to `N`
+	/// digits.
+	Sequence(usize),
+
+	/// `{year}`: the year that the invoice was issued.
+	Year,
+}
+
+/// A pattern for invoice numbers, such as `INV-{year}-{seq:04}`.
+///
+/// The placeholders are `{year}`, `{month}`, and `{day}` (of when the invoice was issued, in the
+/// local timezone), and `{seq}` (which is required). Each distinct rendering of the other
+/// placeholders starts its own sequence, so `INV-{year}-{seq}` starts from 1 every year.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(into = "String", try_from = "String")]
+pub struct Pattern(Vec<Segment>);
+
+impl Pattern
+{
+	/// The invoice number for an invoice which was `issued` at some date, and is number `seq` of
+	/// its [`series`](Pattern::series).
+	pub fn render(&self, issued: DateTime<Utc>, seq: i64) -> String
+	{
+		self.render_with(issued, |width| format!("{seq:0width$}"))
+	}
+
+	/// Render the segments of this [`Pattern`] for an invoice `issued` at some date, using
+	/// `sequence` to render [`Segment::Sequence`]s of some width.
+	fn render_with(&self, issued: DateTime<Utc>, sequence: impl Fn(usize) -> String) -> String
+	{
+		let local = issued.with_timezone(&Local);
+		self.0
+			.iter()
+			.map(|s| match s
+			{
+				Segment::Day => format!("{:02}", local.day()),
+				Segment::Literal(l) => l.clone(),
+				Segment::Month => format!("{:02}", local.month()),
+				Segment::Sequence(width) => sequence(*width),
+				Segment::Year => local.year().to_string(),
+			})
+			.collect()
+	}
+
+	/// The sequence which an invoice `issued` at some date is a part of.
+	pub fn series(&self, issued: DateTime<Utc>) -> String
+	{
+		self.render_with(issued, |_| "{seq}".into())
+	}
+}
+
+impl Display for Pattern
+{
+	fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult
+	{
+		self.0.iter().try_for_each(|s| match s
+		{
+			Segment::Day => write!(f, "{{day}}"),
+			Segment::Literal(l) => write!(f, "{l}"),
+			Segment::Month => write!(f, "{{month}}"),
+			Segment::Sequence(0) => write!(f, "{{seq}}"),
+			Segment::Sequence(width) => write!(f, "{{seq:{width:02}}}"),
+			Segment::Year => write!(f, "{{year}}"),
+		})
+	}
+}
+
+impl From<Pattern> for String
+{
+	fn from(pattern: Pattern) -> Self
+	{
+		pattern.to_string()
+	}
+}
+
+impl FromStr for Pattern
+{
+	type Err = Error;
+
+	fn from_str(s: &str) -> StdResult<Self, Self::Err>
+	{
+		let mut segments = Vec::new();
+		let mut rest = s;
+		while let Some(start) = rest.find('{')
+		{
+			if start > 0
+			{
+				segments.push(Segment::Literal(rest[..start].into()));
+			}
+
+			let end = rest[start..].find('}').ok_or(Error::Unclosed)? + start;
+			let placeholder = &rest[start + 1..end];
+			segments.push(match placeholder
+			{
+				"day" => Segment::Day,
+				"month" => Segment::Month,
+				"seq" => Segment::Sequence(0),
+				"year" => Segment::Year,
+				p => match p.strip_prefix("seq:").map(str::parse)
+				{
+					Some(Ok(width)) => Segment::Sequence(width),
+					_ => return Err(Error::UnknownPlaceholder(p.into())),
+				},
+			});
+
+			rest = &rest[end + 1..];
+		}
+
+		if !rest.is_empty()
+		{
+			segments.push(Segment::Literal(rest.into()));
+		}
+
+		match segments.iter().any(|s| matches!(s, Segment::Sequence(_)))
+		{
+			false => Err(Error::NoSequence),
+			true => Ok(Self(segments)),
+		}
+	}
+}
+
+impl TryFrom<String> for Pattern
+{
+	type Error = Error;
+
+	fn try_from(s: String) -> StdResult<Self, Self::Error>
+	{
+		s.parse()
+	}
+}
+
+/// A [`Database`] which keeps invoice numbers in the `winvoice_invoice_numbers` table, the last
+/// number of each [`series`](Pattern::series) in the `winvoice_invoice_series` table, and the date
+/// that each invoice was due by in the `winvoice_invoice_due_dates` table (see
+/// [`migration`](crate::migration)).
+///
+/// Each method takes a connection rather than a [`Pool`](sqlx::Pool), so that it can be part of
+/// the same [`Transaction`](sqlx::Transaction) which saves the date that an invoice was issued.
+#[async_trait::async_trait(?Send)]
+pub trait Register: Database
+{
+	/// Assign the invoice of the `job` the next number according to the `pattern`, if it has been
+	/// issued and does not have a number yet.
+	///
+	/// Returns the number which was assigned, if any.
+	async fn assign_number(connection: &mut Self::Connection, pattern: &Pattern, job: &Job) -> Result<Option<String>>;
+
+	/// Give the invoice of the `Job` with `job_id` some `number` which was assigned elsewhere (e.g.
+	/// in another store).
+	async fn create_number(connection: &mut Self::Connection, job_id: Id, number: &str) -> Result<()>;
+
+	/// Record that the invoice of the `Job` with `job_id` is `due` by some date (or that it has no
+	/// due date), replacing what was recorded before.
+	async fn create_due_date(connection: &mut Self::Connection, job_id: Id, due: Option<DateTime<Utc>>) -> Result<()>;
+
+	/// Forget the due dates of the invoices of the `Job`s with `job_ids`.
+	async fn delete_due_dates(connection: &mut Self::Connection, job_ids: &[Id]) -> Result<()>;
+
+	/// Remove the invoice numbers which were assigned to the `Job`s with `job_ids`. The last number
+	/// of each [`series`](Pattern::series) is kept, so that the numbers are not assigned again.
+	async fn delete_numbers(connection: &mut Self::Connection, job_ids: &[Id]) -> Result<()>;
+
+	/// The due dates which were recorded for the invoices of the `Job`s with `job_ids`, by `id`.
+	async fn retrieve_due_dates(
+		connection: &mut Self::Connection,
+		job_ids: &[Id],
+	) -> Result<BTreeMap<Id, Option<DateTime<Utc>>>>;
+
+	/// The invoice numbers of the `Job`s with `job_ids` which have been assigned one, by `id`.
+	async fn retrieve_numbers(connection: &mut Self::Connection, job_ids: &[Id]) -> Result<BTreeMap<Id, String>>;
+
+	/// The last number of every [`series`](Pattern::series) which has been assigned.
+	async fn retrieve_series(connection: &mut Self::Connection) -> Result<BTreeMap<String, i64>>;
+
+	/// Make sure that the last number of each of the `series` is at least as high as the one given,
+	/// so that numbers which were assigned elsewhere are not assigned again.
+	async fn update_series(connection: &mut Self::Connection, series: &BTreeMap<String, i64>) -> Result<()>;
+}
+
+/// Implement [`Register`] for some [`Database`], which finds the `greatest` of two values with some
+/// function.
+macro_rules! impl_register {
+	($Db:ty, $greatest:literal) => {
+		#[async_trait::async_trait(?Send)]
+		impl Register for $Db
+		{
+			async fn assign_number(
+				connection: &mut Self::Connection,
+				pattern: &Pattern,
+				job: &Job,
+			) -> Result<Option<String>>
+			{
+				let issued = match job.invoice.date
+				{
+					Some(d) => d.issued,
+					None => return Ok(None),
+				};
+
+				if !Self::retrieve_numbers(connection, &[job.id]).await?.is_empty()
+				{
+					return Ok(None);
+				}
+
+				let series = pattern.series(issued);
+				let seq: i64 = sqlx::query(
+					"INSERT INTO winvoice_invoice_series (series, seq) VALUES ($1, 1) ON CONFLICT (series) DO UPDATE \
+					 SET seq = winvoice_invoice_series.seq + 1 RETURNING seq",
+				)
+				.bind(&series)
+				.fetch_one(&mut *connection)
+				.await?
+				.try_get("seq")?;
+
+				let number = pattern.render(issued, seq);
+				Self::create_number(connection, job.id, &number).await?;
+				Ok(Some(number))
+			}
+
+			async fn create_number(connection: &mut Self::Connection, job_id: Id, number: &str) -> Result<()>
+			{
+				sqlx::query("INSERT INTO winvoice_invoice_numbers (job_id, number) VALUES ($1, $2)")
+					.bind(job_id)
+					.bind(number)
+					.execute(connection)
+					.await
+					.map(|_| ())
+			}
+
+			async fn create_due_date(
+				connection: &mut Self::Connection,
+				job_id: Id,
+				due: Option<DateTime<Utc>>,
+			) -> Result<()>
+			{
+				sqlx::query(
+					"INSERT INTO winvoice_invoice_due_dates (job_id, due) VALUES ($1, $2) ON CONFLICT (job_id) DO \
+					 UPDATE SET due = excluded.due",
+				)
+				.bind(job_id)
+				.bind(due)
+				.execute(connection)
+				.await
+				.map(|_| ())
+			}
+
+			async fn delete_due_dates(connection: &mut Self::Connection, job_ids: &[Id]) -> Result<()>
+			{
+				if !job_ids.is_empty()
+				{
+					let ids = job_ids.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(", ");
+					connection
+						.execute(format!("DELETE FROM winvoice_invoice_due_dates WHERE job_id IN ({ids})").as_str())
+						.await?;
+				}
+
+				Ok(())
+			}
+
+			async fn delete_numbers(connection: &mut Self::Connection, job_ids: &[Id]) -> Result<()>
+			{
+				if !job_ids.is_empty()
+				{
+					let ids = job_ids.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(", ");
+					connection
+						.execute(format!("DELETE FROM winvoice_invoice_numbers WHERE job_id IN ({ids})").as_str())
+						.await?;
+				}
+
+				Ok(())
+			}
+
+			async fn retrieve_due_dates(
+				connection: &mut Self::Connection,
+				job_ids: &[Id],
+			) -> Result<BTreeMap<Id, Option<DateTime<Utc>>>>
+			{
+				if job_ids.is_empty()
+				{
+					return Ok(BTreeMap::new());
+				}
+
+				let ids = job_ids.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(", ");
+				let query = format!("SELECT job_id, due FROM winvoice_invoice_due_dates WHERE job_id IN ({ids})");
+
+				connection
+					.fetch_all(query.as_str())
+					.await?
+					.into_iter()
+					.map(|row| Ok((row.try_get("job_id")?, row.try_get("due")?)))
+					.collect()
+			}
+
+			async fn retrieve_numbers(connection: &mut Self::Connection, job_ids: &[Id])
+				-> Result<BTreeMap<Id, String>>
+			{
+				if job_ids.is_empty()
+				{
+					return Ok(BTreeMap::new());
+				}
+
+				let ids = job_ids.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(", ");
+				let query = format!("SELECT job_id, number FROM winvoice_invoice_numbers WHERE job_id IN ({ids})");
+
+				connection
+					.fetch_all(query.as_str())
+					.await?
+					.into_iter()
+					.map(|row| Ok((row.try_get("job_id")?, row.try_get("number")?)))
+					.collect()
+			}
+
+			async fn retrieve_series(connection: &mut Self::Connection) -> Result<BTreeMap<String, i64>>
+			{
+				connection
+					.fetch_all("SELECT series, seq FROM winvoice_invoice_series")
+					.await?
+					.into_iter()
+					.map(|row| Ok((row.try_get("series")?, row.try_get("seq")?)))
+					.collect()
+			}
+
+			async fn update_series(connection: &mut Self::Connection, series: &BTreeMap<String, i64>) -> Result<()>
+			{
+				for (s, seq) in series
+				{
+					sqlx::query(concat!(
+						"INSERT INTO winvoice_invoice_series (series, seq) VALUES ($1, $2) ON CONFLICT (series) DO ",
+						"UPDATE SET seq = ",
+						$greatest,
+						"(winvoice_invoice_series.seq, excluded.seq)",
+					))
+					.bind(s)
+					.bind(seq)
+					.execute(&mut *connection)
+					.await?;
+				}
+
+				Ok(())
+			}
+		}
+	};
+}
+
+#[cfg(feature = "postgres")]
+impl_register!(sqlx::Postgres, "GREATEST");
+
+#[cfg(feature = "sqlite")]
+impl_register!(sqlx::Sqlite, "MAX");
+
+/// [Assign](Register::assign_number) numbers to the invoices of the `jobs` which have been issued,
+/// if there is a `pattern` to number them with, reporting each new number with [`println!`].
+///
+/// # Errors
+///
+/// If a number could not be assigned.
+pub async fn assign<'job, Db, Jobs>(
+	connection: &mut Db::Connection,
+	pattern: Option<&Pattern>,
+	jobs: Jobs,
+) -> Result<()>
+where
+	Db: Register,
+	Jobs: IntoIterator<Item = &'job Job>,
+{
+	if let Some(p) = pattern
+	{
+		for j in jobs
+		{
+			if let Some(number) = Db::assign_number(connection, p, j).await?
+			{
+				println!("Job {} has been assigned invoice number {number}", fmt::id_num(j.id));
+			}
+		}
+	}
+
+	Ok(())
+}
+
+/// Record when the invoices of the `jobs` in the `store` are [due](Invoices::due) according to the
+/// current terms of the `invoices`, for those which have been issued; and forget the due dates of
+/// those which have not, so that later changes to the terms do not change when invoices which were
+/// already issued are due.
+///
+/// # Errors
+///
+/// If the due dates could not be recorded.
+pub async fn schedule<'job, Db, Jobs>(
+	connection: &mut Db::Connection,
+	invoices: &Invoices,
+	store: &str,
+	jobs: Jobs,
+) -> Result<()>
+where
+	Db: Register,
+	Jobs: IntoIterator<Item = &'job Job>,
+{
+	for j in jobs
+	{
+		match j.invoice.date.is_some()
+		{
+			false => Db::delete_due_dates(connection, &[j.id]).await?,
+			true => Db::create_due_date(connection, j.id, invoices.due(store, j)).await?,
+		}
+	}
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests
+{
+	use pretty_assertions::assert_eq;
+	use winvoice_schema::chrono::{Local, TimeZone, Utc};
+
+	use super::{Error, Pattern};
+
+	#[test]
+	fn pattern()
+	{
+		let issued = Local.ymd(2022, 3, 4).and_hms(12, 0, 0).with_timezone(&Utc);
+		let pattern: Pattern = "INV-{year}-{seq:04}".parse().unwrap();
+
+		assert_eq!(pattern.render(issued, 41), "INV-2022-0041");
+		assert_eq!(pattern.series(issued), "INV-2022-{seq}");
+		assert_eq!(pattern.to_string(), "INV-{year}-{seq:04}");
+		assert_eq!("{year}{month}{day}/{seq}".parse::<Pattern>().unwrap().render(issued, 7), "20220304/7");
+
+		assert_eq!("INV-{year}".parse::<Pattern>(), Err(Error::NoSequence));
+		assert_eq!("INV-{seq".parse::<Pattern>(), Err(Error::Unclosed));
+		assert_eq!("{client}-{seq}".parse::<Pattern>(), Err(Error::UnknownPlaceholder("client".into())));
+	}
+
+	#[cfg(feature = "sqlite")]
+	#[tokio::test]
+	async fn register()
+	{
+		use core::time::Duration;
+		use std::collections::BTreeMap;
+
+		use sqlx::{Connection, Executor, Sqlite, SqliteConnection};
+		use winvoice_schema::{Currency, Invoice, InvoiceDate, Job, Location, Money, Organization};
+
+		use super::Register;
+		use crate::{config::Adapter, migration, sqlite};
+
+		let options = sqlite::connect_options("sqlite::memory:").unwrap();
+		let mut connection = SqliteConnection::connect_with(&options).await.unwrap();
+		connection.execute("CREATE TABLE jobs (id INTEGER NOT NULL PRIMARY KEY)").await.unwrap();
+		connection.execute("INSERT INTO jobs (id) VALUES (3), (5), (6), (9)").await.unwrap();
+		migration::upgrade::<Sqlite>(&mut connection, Adapter::Sqlite).await.unwrap();
+
+		let pattern: Pattern = "INV-{year}-{seq:03}".parse().unwrap();
+		let issued = |year| Some(InvoiceDate { issued: Local.ymd(year, 6, 1).and_hms(0, 0, 0).into(), paid: None });
+
+		let mut job = Job {
+			client: Organization {
+				id: 0,
+				location: Location { id: 0, name: "Earth".into(), outer: None },
+				name: "Foo".into(),
+			},
+			date_close: None,
+			date_open: Utc.ymd(2022, 1, 1).and_hms(0, 0, 0),
+			id: 3,
+			increment: Duration::from_secs(15 * 60),
+			invoice: Invoice { date: None, hourly_rate: Money::new(20_00, 2, Currency::Usd) },
+			notes: String::new(),
+			objectives: "Write code".into(),
+		};
+
+		assert_eq!(Sqlite::assign_number(&mut connection, &pattern, &job).await.unwrap(), None);
+
+		job.invoice.date = issued(2022);
+		assert_eq!(
+			Sqlite::assign_number(&mut connection, &pattern, &job).await.unwrap().as_deref(),
+			Some("INV-2022-001")
+		);
+		assert_eq!(Sqlite::assign_number(&mut connection, &pattern, &job).await.unwrap(), None);
+
+		job.id = 5;
+		assert_eq!(
+			Sqlite::assign_number(&mut connection, &pattern, &job).await.unwrap().as_deref(),
+			Some("INV-2022-002")
+		);
+
+		job.id = 6;
+		job.invoice.date = issued(2023);
+		assert_eq!(
+			Sqlite::assign_number(&mut connection, &pattern, &job).await.unwrap().as_deref(),
+			Some("INV-2023-001")
+		);
+
+		let numbers = Sqlite::retrieve_numbers(&mut connection, &[3, 4, 6]).await.unwrap();
+		assert_eq!(numbers, BTreeMap::from([(3, "INV-2022-001".into()), (6, "INV-2023-001".into())]));
+
+		Sqlite::create_number(&mut connection, 9, "INV-2024-007").await.unwrap();
+		assert!(Sqlite::create_number(&mut connection, 9, "INV-2024-008").await.is_err());
+		assert_eq!(Sqlite::retrieve_numbers(&mut connection, &[9]).await.unwrap()[&9], "INV-2024-007");
+
+		Sqlite::delete_numbers(&mut connection, &[5, 9]).await.unwrap();
+		assert_eq!(
+			Sqlite::retrieve_numbers(&mut connection, &[3, 5, 6, 9]).await.unwrap(),
+			BTreeMap::from([(3, "INV-2022-001".into()), (6, "INV-2023-001".into())])
+		);
+		Sqlite::create_number(&mut connection, 9, "INV-2024-007").await.unwrap();
+
+		let series = BTreeMap::from([("INV-2022-{seq}".into(), 1), ("INV-2024-{seq}".into(), 7)]);
+		Sqlite::update_series(&mut connection, &series).await.unwrap();
+		assert_eq!(
+			Sqlite::retrieve_series(&mut connection).await.unwrap(),
+			BTreeMap::from([("INV-2022-{seq}".into(), 2), ("INV-2023-{seq}".into(), 1), ("INV-2024-{seq}".into(), 7)])
+		);
+		let due = Utc.ymd(2023, 7, 1).and_hms(0, 0, 0);
+		Sqlite::create_due_date(&mut connection, 5, None).await.unwrap();
+		Sqlite::create_due_date(&mut connection, 6, Some(Utc.ymd(2023, 6, 15).and_hms(0, 0, 0))).await.unwrap();
+		Sqlite::create_due_date(&mut connection, 6, Some(due)).await.unwrap();
+		assert_eq!(
+			Sqlite::retrieve_due_dates(&mut connection, &[3, 5, 6]).await.unwrap(),
+			BTreeMap::from([(5, None), (6, Some(due))])
+		);
+
+		Sqlite::delete_due_dates(&mut connection, &[5]).await.unwrap();
+		assert_eq!(
+			Sqlite::retrieve_due_dates(&mut connection, &[5, 6]).await.unwrap(),
+			BTreeMap::from([(6, Some(due))])
+		);
+	}
+}
@@ -0,0 +1,437 @@
+//! Payments which have been made towards the invoices of `Job`s, so that they can be paid in
+//! installments.
+//!
+//! Payments are kept in the store alongside the `Job`s they were made towards (see [`Ledger`]).
+
+use core::fmt::{Display, Formatter, Result as FmtResult};
+use std::collections::BTreeMap;
+
+use money2::{Currency, Decimal, Exchange, ExchangeRates};
+use serde::{Deserialize, Serialize};
+use sqlx::{Database, Pool, Result};
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+use sqlx::{Executor, Row};
+use thiserror::Error;
+use winvoice_adapter::{schema::TimesheetAdapter, Deletable};
+use winvoice_match::MatchTimesheet;
+use winvoice_schema::{
+	chrono::{DateTime, Local, Utc},
+	Id,
+	Job,
+	Money,
+};
+
+use crate::{config::Invoices, exchange, export::Discount, fmt, migration::Migration, utils::Billable, DynResult};
+
+/// The [`Migration`] which creates the table that a [`Ledger`] keeps [`Payment`]s in.
+pub(crate) const MIGRATION: Migration = Migration {
+	description: "Keep payments towards the invoices of jobs",
+	postgres: &["CREATE TABLE winvoice_payments (
+		id BIGSERIAL NOT NULL PRIMARY KEY,
+		job_id BIGINT NOT NULL REFERENCES jobs(id) ON DELETE CASCADE,
+		amount TEXT NOT NULL,
+		date TIMESTAMPTZ NOT NULL,
+		notes TEXT NOT NULL
+	)"],
+	sqlite: &["CREATE TABLE winvoice_payments (
+		id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+		job_id INTEGER NOT NULL REFERENCES jobs(id) ON DELETE CASCADE,
+		amount TEXT NOT NULL,
+		date TEXT NOT NULL,
+		notes TEXT NOT NULL
+	)"],
+	version: 2,
+};
+
+/// An [`Error`](std::error::Error) indicating that an amount (e.g. a [`Payment`]) was not in the
+/// currency of the `Job` it applies to.
+#[derive(Clone, Copy, Debug, Eq, Error, Hash, Ord, PartialEq, PartialOrd)]
+#[error("The amount was in {found}, but the invoice of the Job is in {expected}")]
+pub struct CurrencyMismatch
+{
+	/// The currency of the `hourly_rate` of the `Job`.
+	pub expected: Currency,
+
+	/// The currency of the amount.
+	pub found: Currency,
+}
+
+/// A payment which was made towards the invoice of a `Job`.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Payment
+{
+	/// How much was paid, which is in the same currency as the `hourly_rate` of the `Job`.
+	pub amount: Money,
+
+	/// When the payment was made.
+	pub date: DateTime<Utc>,
+
+	/// The unique identifier of the payment within its store.
+	pub id: Id,
+
+	/// The `id` of the `Job` which the payment was made towards.
+	pub job_id: Id,
+
+	/// Notes about the payment (e.g. the check number).
+	#[serde(default, skip_serializing_if = "String::is_empty")]
+	pub notes: String,
+}
+
+impl Display for Payment
+{
+	fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult
+	{
+		write!(
+			f,
+			"Payment {} of {} towards Job {} on {}",
+			fmt::id_num(self.id),
+			self.amount,
+			fmt::id_num(self.job_id),
+			self.date.with_timezone(&Local).format("%Y-%m-%d %H:%M"),
+		)?;
+
+		match self.notes.is_empty()
+		{
+			false => write!(f, ": {}", self.notes),
+			true => Ok(()),
+		}
+	}
+}
+
+/// A [`Database`] which keeps [`Payment`]s in the `winvoice_payments` table (see
+/// [`migration`](crate::migration)).
+///
+/// Each method takes a connection rather than a [`Pool`], so that it can be part of the same
+/// [`Transaction`](sqlx::Transaction) as the changes to the `Job`s which the [`Payment`]s were made
+/// towards.
+#[async_trait::async_trait(?Send)]
+pub trait Ledger: Database
+{
+	/// Record a [`Payment`] of some `amount` on a `date` towards the `Job` with `job_id`, returning
+	/// it.
+	async fn create_payment(
+		connection: &mut Self::Connection,
+		job_id: Id,
+		amount: Money,
+		date: DateTime<Utc>,
+		notes: String,
+	) -> Result<Payment>;
+
+	/// Remove the `payments`.
+	async fn delete_payments(connection: &mut Self::Connection, payments: &[Payment]) -> Result<()>;
+
+	/// Every [`Payment`] towards any of the `Job`s with `job_ids`, in the order they were recorded.
+	async fn retrieve_payments(connection: &mut Self::Connection, job_ids: &[Id]) -> Result<Vec<Payment>>;
+}
+
+/// Implement [`Ledger`] for some [`Database`].
+macro_rules! impl_ledger {
+	($Db:ty) => {
+		#[async_trait::async_trait(?Send)]
+		impl Ledger for $Db
+		{
+			async fn create_payment(
+				connection: &mut Self::Connection,
+				job_id: Id,
+				amount: Money,
+				date: DateTime<Utc>,
+				notes: String,
+			) -> Result<Payment>
+			{
+				let row = sqlx::query(
+					"INSERT INTO winvoice_payments (job_id, amount, date, notes) VALUES ($1, $2, $3, $4) RETURNING id",
+				)
+				.bind(job_id)
+				.bind(amount.to_string())
+				.bind(date)
+				.bind(&notes)
+				.fetch_one(connection)
+				.await?;
+
+				Ok(Payment { amount, date, id: row.try_get("id")?, job_id, notes })
+			}
+
+			async fn delete_payments(connection: &mut Self::Connection, payments: &[Payment]) -> Result<()>
+			{
+				if !payments.is_empty()
+				{
+					let ids = join_ids(payments.iter().map(|p| p.id));
+					connection.execute(format!("DELETE FROM winvoice_payments WHERE id IN ({ids})").as_str()).await?;
+				}
+
+				Ok(())
+			}
+
+			async fn retrieve_payments(connection: &mut Self::Connection, job_ids: &[Id]) -> Result<Vec<Payment>>
+			{
+				if job_ids.is_empty()
+				{
+					return Ok(Vec::new());
+				}
+
+				let ids = join_ids(job_ids.iter().copied());
+				let query = format!(
+					"SELECT id, job_id, amount, date, notes FROM winvoice_payments WHERE job_id IN ({ids}) ORDER BY id"
+				);
+
+				connection
+					.fetch_all(query.as_str())
+					.await?
+					.into_iter()
+					.map(|row| {
+						Ok(Payment {
+							amount: row
+								.try_get::<String, _>("amount")?
+								.parse()
+								.map_err(|e| sqlx::Error::Decode(Box::new(e)))?,
+							date: row.try_get("date")?,
+							id: row.try_get("id")?,
+							job_id: row.try_get("job_id")?,
+							notes: row.try_get("notes")?,
+						})
+					})
+					.collect()
+			}
+		}
+	};
+}
+
+#[cfg(feature = "postgres")]
+impl_ledger!(sqlx::Postgres);
+
+#[cfg(feature = "sqlite")]
+impl_ledger!(sqlx::Sqlite);
+
+/// Join some `ids` with commas, so that they can be used in a SQL `IN` clause.
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+fn join_ids(ids: impl Iterator<Item = Id>) -> String
+{
+	ids.map(|id| id.to_string()).collect::<Vec<_>>().join(", ")
+}
+
+/// The sum of the `payments` towards the `job`, in the currency of its `hourly_rate`.
+pub fn paid(payments: &[Payment], job: &Job) -> Money
+{
+	let amount = payments.iter().filter(|p| p.job_id == job.id).map(|p| p.amount.amount).sum();
+	Money { amount, ..job.invoice.hourly_rate }
+}
+
+/// The amount left to pay for the invoice of the `job` in the `store`, which is the total of its
+/// `Timesheet`s (in the currency of its `hourly_rate`) after any of the `discounts` and tax, minus
+/// the `payments` towards it.
+///
+/// Amounts in other currencies are converted using the `exchange_rates`, or else rates are only
+/// [obtained](exchange::rates) (e.g. from the `rates` of the `invoices`) when they are required.
+///
+/// # Errors
+///
+/// * If the `Timesheet`s could not be retrieved.
+/// * If exchange rates are required, but could not be [obtained](exchange::rates).
+pub async fn balance<TAdapter, Db>(
+	connection: &Pool<Db>,
+	payments: &[Payment],
+	discounts: &BTreeMap<Id, Discount>,
+	invoices: &Invoices,
+	store: &str,
+	job: &Job,
+	exchange_rates: Option<&ExchangeRates>,
+) -> DynResult<Money>
+where
+	Db: Database,
+	TAdapter: Deletable<Db = Db> + TimesheetAdapter,
+{
+	let currency = job.invoice.hourly_rate.currency;
+	let retrieved = TAdapter::retrieve(connection, MatchTimesheet { job: job.id.into(), ..Default::default() }).await?;
+	let terms = invoices.terms(store, job, discounts.get(&job.id).copied(), None, None);
+	let (terms, timesheets) = match exchange_rates
+	{
+		Some(r) => (terms.exchange(currency, r), retrieved.into_iter().map(|t| t.exchange(currency, r)).collect()),
+		None => futures::try_join!(
+			exchange::terms(terms, currency, None, &invoices.rates),
+			exchange::timesheets(retrieved, currency, None, &invoices.rates),
+		)?,
+	};
+
+	let subtotal = Money { amount: timesheets.iter().map(|t| t.total().amount).sum(), currency };
+	let charges = terms.charges(subtotal);
+	Ok(Money { amount: charges.total.amount - paid(payments, job).amount, currency })
+}
+
+/// Mark the invoice of the `job` as paid on `date` if it has been issued and the `balance` is
+/// settled, or as unpaid if it was paid and the `balance` is not settled.
+///
+/// Returns whether the `job` was changed.
+pub fn reconcile(job: &mut Job, balance: Money, date: DateTime<Utc>) -> bool
+{
+	let settled = balance.amount <= Decimal::ZERO;
+	match job.invoice.date.as_mut()
+	{
+		Some(d) if settled && d.paid.is_none() => d.paid = Some(date),
+		Some(d) if !settled && d.paid.is_some() => d.paid = None,
+		_ => return false,
+	}
+
+	true
+}
+
+#[cfg(test)]
+mod tests
+{
+	use core::time::Duration;
+
+	use pretty_assertions::assert_eq;
+	use winvoice_schema::{
+		chrono::{TimeZone, Utc},
+		Currency,
+		Invoice,
+		InvoiceDate,
+		Job,
+		Location,
+		Money,
+		Organization,
+	};
+
+	use super::Payment;
+
+	fn job() -> Job
+	{
+		Job {
+			client: Organization {
+				id: 0,
+				location: Location { id: 0, name: "Earth".into(), outer: None },
+				name: "Foo".into(),
+			},
+			date_close: None,
+			date_open: Utc.ymd(2022, 1, 1).and_hms(0, 0, 0),
+			id: 3,
+			increment: Duration::from_secs(15 * 60),
+			invoice: Invoice { date: None, hourly_rate: Money::new(20_00, 2, Currency::Usd) },
+			notes: String::new(),
+			objectives: "Write code".into(),
+		}
+	}
+
+	/// Record, retrieve, and delete [`Payment`]s towards the `Job`s with `job_id` and `other_id`
+	/// through the `connection`, deleting the `Job` with `other_id` along the way.
+	#[cfg(any(feature = "postgres", feature = "sqlite"))]
+	async fn ledger<Db>(connection: &mut Db::Connection, job_id: winvoice_schema::Id, other_id: winvoice_schema::Id)
+	where
+		Db: super::Ledger,
+		for<'connection> &'connection mut Db::Connection: sqlx::Executor<'connection, Database = Db>,
+	{
+		use sqlx::Executor;
+
+		let date = Utc.ymd(2022, 2, 1).and_hms(0, 0, 0);
+		let usd = |amount| Money::new(amount, 2, Currency::Usd);
+
+		let first = Db::create_payment(connection, job_id, usd(10_00), date, String::new()).await.unwrap();
+		let second = Db::create_payment(connection, job_id, usd(5_50), date, "Check #4".into()).await.unwrap();
+		let other = Db::create_payment(connection, other_id, usd(1_00), date, String::new()).await.unwrap();
+
+		assert_eq!(Db::retrieve_payments(connection, &[job_id]).await.unwrap(), [first.clone(), second.clone()]);
+		assert_eq!(Db::retrieve_payments(connection, &[job_id, other_id]).await.unwrap().len(), 3);
+		assert_eq!(Db::retrieve_payments(connection, &[]).await.unwrap(), []);
+
+		Db::delete_payments(connection, &[first]).await.unwrap();
+		assert_eq!(Db::retrieve_payments(connection, &[job_id, other_id]).await.unwrap(), [second, other]);
+
+		connection.execute(format!("DELETE FROM jobs WHERE id = {other_id}").as_str()).await.unwrap();
+		assert_eq!(Db::retrieve_payments(connection, &[other_id]).await.unwrap(), []);
+	}
+
+	#[cfg(feature = "postgres")]
+	#[tokio::test]
+	async fn postgres()
+	{
+		use sqlx::{Connection, PgConnection, Postgres};
+		use winvoice_adapter::schema::{JobAdapter, LocationAdapter, OrganizationAdapter};
+		use winvoice_adapter_postgres::schema::{PgJob, PgLocation, PgOrganization};
+
+		use crate::utils;
+
+		let mut connection = PgConnection::connect(&utils::postgres_database().await).await.unwrap();
+		let mut transaction = connection.begin().await.unwrap();
+
+		let location = PgLocation::create(&mut *transaction, "Earth".into(), None).await.unwrap();
+		let client = PgOrganization::create(&mut *transaction, location, "Foo".into()).await.unwrap();
+		let job = job();
+		let mut job_ids = Vec::new();
+		for _ in 0..2
+		{
+			let created = PgJob::create(
+				&mut *transaction,
+				client.clone(),
+				None,
+				job.date_open,
+				job.increment,
+				job.invoice,
+				String::new(),
+				job.objectives.clone(),
+			)
+			.await
+			.unwrap();
+
+			job_ids.push(created.id);
+		}
+
+		ledger::<Postgres>(&mut transaction, job_ids[0], job_ids[1]).await;
+
+		// NOTE: the transaction is not committed, so that nothing is left in the database.
+		transaction.rollback().await.unwrap();
+	}
+
+	#[cfg(feature = "sqlite")]
+	#[tokio::test]
+	async fn sqlite()
+	{
+		use sqlx::{Connection, Executor, Sqlite, SqliteConnection};
+
+		use crate::{config::Adapter, migration, sqlite};
+
+		let options = sqlite::connect_options("sqlite::memory:").unwrap();
+		let mut connection = SqliteConnection::connect_with(&options).await.unwrap();
+		connection.execute("CREATE TABLE jobs (id INTEGER NOT NULL PRIMARY KEY)").await.unwrap();
+		connection.execute("INSERT INTO jobs (id) VALUES (3), (4)").await.unwrap();
+		migration::upgrade::<Sqlite>(&mut connection, Adapter::Sqlite).await.unwrap();
+
+		ledger::<Sqlite>(&mut connection, 3, 4).await;
+	}
+
+	#[test]
+	fn paid()
+	{
+		let date = Utc.ymd(2022, 2, 1).and_hms(0, 0, 0);
+		let job = job();
+		let payment = |id, job_id, amount| Payment {
+			amount: Money::new(amount, 2, Currency::Usd),
+			date,
+			id,
+			job_id,
+			notes: String::new(),
+		};
+
+		assert_eq!(super::paid(&[], &job), Money::new(0, 2, Currency::Usd));
+		assert_eq!(
+			super::paid(&[payment(0, job.id, 10_00), payment(1, 4, 1_00), payment(2, job.id, 5_50)], &job),
+			Money::new(15_50, 2, Currency::Usd)
+		);
+	}
+
+	#[test]
+	fn reconcile()
+	{
+		let date = Utc.ymd(2022, 2, 1).and_hms(0, 0, 0);
+		let mut job = job();
+
+		assert!(!super::reconcile(&mut job, Money::new(0, 2, Currency::Usd), date));
+
+		job.invoice.date = Some(InvoiceDate { issued: date, paid: None });
+		assert!(!super::reconcile(&mut job, Money::new(1_00, 2, Currency::Usd), date));
+		assert!(super::reconcile(&mut job, Money::new(-1_00, 2, Currency::Usd), date));
+		assert_eq!(job.invoice.date.and_then(|d| d.paid), Some(date));
+
+		assert!(!super::reconcile(&mut job, Money::new(0, 2, Currency::Usd), date));
+		assert!(super::reconcile(&mut job, Money::new(1_00, 2, Currency::Usd), date));
+		assert_eq!(job.invoice.date.and_then(|d| d.paid), None);
+	}
+}
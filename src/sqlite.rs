@@ -0,0 +1,104 @@
+//! Adapters which keep each entity of the Winvoice schema in a SQLite database, for stores which
+//! are configured with `adapter = "sqlite"`.
+//!
+//! SQLite stores are local files which belong to a single user, so rather than translating each
+//! match condition into SQL, the adapters select every row of a table and keep the ones which
+//! [match](matches::Matches) in this process.
+
+mod matches;
+pub mod schema;
+
+use core::str::FromStr;
+
+use sqlx::{sqlite::SqliteConnectOptions, Acquire, Executor, Result, Sqlite};
+use winvoice_adapter::Initializable;
+
+/// The statements which create the schema of a SQLite store, as of version `0` (see
+/// [`migration`](crate::migration)).
+const TABLES: [&str; 7] = [
+	"CREATE TABLE IF NOT EXISTS locations (
+		id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+		outer_id INTEGER REFERENCES locations(id),
+		name TEXT NOT NULL
+	)",
+	"CREATE TABLE IF NOT EXISTS contact_information (
+		label TEXT NOT NULL PRIMARY KEY,
+		address_id INTEGER REFERENCES locations(id),
+		email TEXT,
+		other TEXT,
+		phone TEXT,
+		CHECK ((address_id IS NOT NULL) + (email IS NOT NULL) + (other IS NOT NULL) + (phone IS NOT NULL) = 1)
+	)",
+	"CREATE TABLE IF NOT EXISTS employees (
+		id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+		name TEXT NOT NULL,
+		status TEXT NOT NULL,
+		title TEXT NOT NULL
+	)",
+	"CREATE TABLE IF NOT EXISTS organizations (
+		id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+		location_id INTEGER NOT NULL REFERENCES locations(id),
+		name TEXT NOT NULL
+	)",
+	"CREATE TABLE IF NOT EXISTS jobs (
+		id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+		client_id INTEGER NOT NULL REFERENCES organizations(id),
+		date_close TEXT,
+		date_open TEXT NOT NULL,
+		increment TEXT NOT NULL,
+		invoice_date_issued TEXT,
+		invoice_date_paid TEXT,
+		invoice_hourly_rate TEXT NOT NULL,
+		notes TEXT NOT NULL,
+		objectives TEXT NOT NULL,
+		CHECK (invoice_date_paid IS NULL OR invoice_date_issued IS NOT NULL)
+	)",
+	"CREATE TABLE IF NOT EXISTS timesheets (
+		id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+		employee_id INTEGER NOT NULL REFERENCES employees(id),
+		job_id INTEGER NOT NULL REFERENCES jobs(id),
+		time_begin TEXT NOT NULL,
+		time_end TEXT,
+		work_notes TEXT NOT NULL
+	)",
+	"CREATE TABLE IF NOT EXISTS expenses (
+		id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+		timesheet_id INTEGER NOT NULL REFERENCES timesheets(id) ON DELETE CASCADE,
+		category TEXT NOT NULL,
+		cost TEXT NOT NULL,
+		description TEXT NOT NULL
+	)",
+];
+
+/// The options for connecting to the SQLite database at `url`, which enforce its `FOREIGN KEY`
+/// constraints (e.g. so that the payments towards a `Job` are deleted along with it).
+///
+/// # Errors
+///
+/// If the `url` is not a valid SQLite URL.
+pub fn connect_options(url: &str) -> Result<SqliteConnectOptions>
+{
+	SqliteConnectOptions::from_str(url).map(|o| o.foreign_keys(true))
+}
+
+/// Creates the tables which the adapters in the [`schema`] use.
+pub struct SqliteSchema;
+
+#[async_trait::async_trait]
+impl Initializable for SqliteSchema
+{
+	type Db = Sqlite;
+
+	async fn init<'connection, Conn>(connection: Conn) -> Result<()>
+	where
+		Conn: Acquire<'connection, Database = Sqlite> + Send,
+	{
+		let mut transaction = connection.begin().await?;
+		for table in TABLES
+		{
+			(&mut transaction).execute(table).await?;
+		}
+
+		transaction.commit().await
+	}
+}
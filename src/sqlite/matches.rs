@@ -0,0 +1,249 @@
+use sqlx::{Error, Result};
+use winvoice_match::{
+	Match,
+	MatchContact,
+	MatchEmployee,
+	MatchExpense,
+	MatchInvoice,
+	MatchJob,
+	MatchLocation,
+	MatchOption,
+	MatchOrganization,
+	MatchStr,
+	MatchTimesheet,
+};
+use winvoice_schema::{
+	chrono::{DateTime, Local, NaiveDateTime, Utc},
+	Contact,
+	Employee,
+	Expense,
+	Invoice,
+	Job,
+	Location,
+	Organization,
+	Timesheet,
+};
+
+/// A match condition which can be checked against some value of type `T` in this process.
+pub(super) trait Matches<T>
+{
+	/// Whether the `value` matches this condition.
+	///
+	/// # Errors
+	///
+	/// If the condition cannot be checked by the SQLite adapters (e.g. [`MatchStr::Regex`]).
+	fn matches(&self, value: &T) -> Result<bool>;
+}
+
+/// Whether `value` matches every one of the `conditions`.
+fn all<M, T>(conditions: &[M], value: &T) -> Result<bool>
+where
+	M: Matches<T>,
+{
+	conditions.iter().try_fold(true, |acc, m| Ok(acc && m.matches(value)?))
+}
+
+/// Whether `value` matches any of the `conditions`.
+fn any<M, T>(conditions: &[M], value: &T) -> Result<bool>
+where
+	M: Matches<T>,
+{
+	conditions.iter().try_fold(false, |acc, m| Ok(acc || m.matches(value)?))
+}
+
+impl<M, T> Matches<Box<T>> for Box<M>
+where
+	M: Matches<T>,
+{
+	fn matches(&self, value: &Box<T>) -> Result<bool>
+	{
+		(**self).matches(&**value)
+	}
+}
+
+impl<T> Matches<T> for Match<T>
+where
+	T: PartialOrd,
+{
+	fn matches(&self, value: &T) -> Result<bool>
+	{
+		Ok(match self
+		{
+			Self::And(conditions) => all(conditions, value)?,
+			Self::Any => true,
+			Self::EqualTo(t) => value == t,
+			Self::GreaterThan(t) => value > t,
+			Self::InRange(low, high) => low <= value && value < high,
+			Self::LessThan(t) => value < t,
+			Self::Not(condition) => !(**condition).matches(value)?,
+			Self::Or(conditions) => any(conditions, value)?,
+		})
+	}
+}
+
+/// Dates in match conditions are in the user's local time, the same as the dates which are entered
+/// on the command line.
+impl Matches<DateTime<Utc>> for Match<NaiveDateTime>
+{
+	fn matches(&self, value: &DateTime<Utc>) -> Result<bool>
+	{
+		self.matches(&value.with_timezone(&Local).naive_local())
+	}
+}
+
+impl Matches<String> for MatchStr<String>
+{
+	fn matches(&self, value: &String) -> Result<bool>
+	{
+		Ok(match self
+		{
+			Self::And(conditions) => all(conditions, value)?,
+			Self::Any => true,
+			Self::Contains(s) => value.contains(s.as_str()),
+			Self::EqualTo(s) => value == s,
+			Self::Not(condition) => !(**condition).matches(value)?,
+			Self::Or(conditions) => any(conditions, value)?,
+			Self::Regex(_) =>
+			{
+				return Err(Error::Configuration(
+					"The SQLite adapter does not support `regex` conditions; use `contains` or `equal_to` instead"
+						.into(),
+				))
+			},
+		})
+	}
+}
+
+impl<M, T> Matches<Option<T>> for MatchOption<M>
+where
+	M: Matches<T>,
+{
+	fn matches(&self, value: &Option<T>) -> Result<bool>
+	{
+		match (self, value)
+		{
+			(Self::Any, _) | (Self::None, None) => Ok(true),
+			(Self::None, Some(_)) | (Self::Some(_), None) => Ok(false),
+			(Self::Some(condition), Some(v)) => condition.matches(v),
+		}
+	}
+}
+
+impl Matches<Contact> for MatchContact
+{
+	fn matches(&self, value: &Contact) -> Result<bool>
+	{
+		self.label.matches(&value.label)
+	}
+}
+
+impl Matches<Employee> for MatchEmployee
+{
+	fn matches(&self, value: &Employee) -> Result<bool>
+	{
+		Ok(self.id.matches(&value.id)? &&
+			self.name.matches(&value.name)? &&
+			self.status.matches(&value.status)? &&
+			self.title.matches(&value.title)?)
+	}
+}
+
+impl Matches<Expense> for MatchExpense
+{
+	fn matches(&self, value: &Expense) -> Result<bool>
+	{
+		Ok(self.id.matches(&value.id)? &&
+			self.category.matches(&value.category)? &&
+			self.cost.matches(&value.cost)? &&
+			self.description.matches(&value.description)? &&
+			self.timesheet_id.matches(&value.timesheet_id)?)
+	}
+}
+
+impl Matches<Invoice> for MatchInvoice
+{
+	fn matches(&self, value: &Invoice) -> Result<bool>
+	{
+		self.hourly_rate.matches(&value.hourly_rate)
+	}
+}
+
+impl Matches<Job> for MatchJob
+{
+	fn matches(&self, value: &Job) -> Result<bool>
+	{
+		Ok(self.id.matches(&value.id)? &&
+			self.client.matches(&value.client)? &&
+			self.date_close.matches(&value.date_close)? &&
+			self.date_open.matches(&value.date_open)? &&
+			self.invoice.matches(&value.invoice)? &&
+			self.notes.matches(&value.notes)? &&
+			self.objectives.matches(&value.objectives)?)
+	}
+}
+
+impl Matches<Location> for MatchLocation
+{
+	fn matches(&self, value: &Location) -> Result<bool>
+	{
+		Ok(self.id.matches(&value.id)? && self.name.matches(&value.name)? && self.outer.matches(&value.outer)?)
+	}
+}
+
+impl Matches<Organization> for MatchOrganization
+{
+	fn matches(&self, value: &Organization) -> Result<bool>
+	{
+		Ok(self.id.matches(&value.id)? && self.location.matches(&value.location)? && self.name.matches(&value.name)?)
+	}
+}
+
+impl Matches<Timesheet> for MatchTimesheet
+{
+	fn matches(&self, value: &Timesheet) -> Result<bool>
+	{
+		Ok(self.id.matches(&value.id)? &&
+			self.employee.matches(&value.employee)? &&
+			self.job.matches(&value.job)? &&
+			self.time_begin.matches(&value.time_begin)? &&
+			self.time_end.matches(&value.time_end)? &&
+			self.work_notes.matches(&value.work_notes)?)
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use winvoice_match::{Match, MatchLocation, MatchOption, MatchStr};
+	use winvoice_schema::Location;
+
+	use super::Matches;
+
+	#[test]
+	fn matches()
+	{
+		let outer = Location { id: 1, name: "Earth".into(), outer: None };
+		let inner = Location { id: 2, name: "Arizona".into(), outer: Some(outer.clone().into()) };
+
+		let condition = MatchLocation {
+			outer: MatchOption::Some(MatchLocation { name: "Earth".to_owned().into(), ..Default::default() }.into()),
+			..Default::default()
+		};
+		assert!(condition.matches(&inner).unwrap());
+		assert!(!condition.matches(&outer).unwrap());
+
+		let condition = MatchLocation {
+			id: Match::Not(Match::InRange(0, 2).into()),
+			name: MatchStr::Contains("zon".into()),
+			..Default::default()
+		};
+		assert!(condition.matches(&inner).unwrap());
+		assert!(!condition.matches(&outer).unwrap());
+
+		let condition = MatchLocation { outer: MatchOption::None, ..Default::default() };
+		assert!(condition.matches(&outer).unwrap());
+		assert!(!condition.matches(&inner).unwrap());
+
+		assert!(MatchStr::Regex("^A".to_owned()).matches(&inner.name).is_err());
+	}
+}
@@ -0,0 +1,56 @@
+//! The SQLite adapters for each entity of the Winvoice schema.
+
+mod contact;
+mod employee;
+mod expenses;
+mod job;
+mod location;
+mod organization;
+mod timesheet;
+
+use core::str::FromStr;
+use std::error::Error;
+
+pub use contact::SqliteContact;
+pub use employee::SqliteEmployee;
+pub use expenses::SqliteExpenses;
+pub use job::SqliteJob;
+pub use location::SqliteLocation;
+pub use organization::SqliteOrganization;
+use sqlx::{query::Query, sqlite::SqliteRow, Encode, Executor, Result, Row, Sqlite, Type};
+pub use timesheet::SqliteTimesheet;
+
+use super::matches::Matches;
+
+/// Delete the rows of a `table` whose `column` is any of the `keys`.
+async fn delete<'connection, Conn, T>(connection: Conn, table: &str, column: &str, keys: Vec<T>) -> Result<()>
+where
+	Conn: Executor<'connection, Database = Sqlite>,
+	T: for<'query> Encode<'query, Sqlite> + Send + Type<Sqlite>,
+{
+	if keys.is_empty()
+	{
+		return Ok(());
+	}
+
+	let statement = format!("DELETE FROM {table} WHERE {column} IN ({})", vec!["?"; keys.len()].join(", "));
+	keys.into_iter().fold(sqlx::query(&statement), Query::bind).execute(connection).await?;
+	Ok(())
+}
+
+/// Keep the `entities` which match the `condition`.
+fn filter<M, T>(entities: impl IntoIterator<Item = T>, condition: &M) -> Result<Vec<T>>
+where
+	M: Matches<T>,
+{
+	entities.into_iter().filter_map(|e| condition.matches(&e).map(|m| m.then_some(e)).transpose()).collect()
+}
+
+/// Get some `column` of a `row` which is stored as text, and parse it.
+fn parse<T>(row: &SqliteRow, column: &str) -> Result<T>
+where
+	T: FromStr,
+	T::Err: Error + Send + Sync + 'static,
+{
+	row.try_get::<String, _>(column)?.parse().map_err(|e| sqlx::Error::Decode(Box::new(e)))
+}
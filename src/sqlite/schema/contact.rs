@@ -0,0 +1,121 @@
+use sqlx::{Error, Executor, Pool, Result, Row, Sqlite, Transaction};
+use winvoice_adapter::{schema::ContactAdapter, Deletable, Retrievable, Updatable};
+use winvoice_match::MatchContact;
+use winvoice_schema::{Contact, ContactKind, Id};
+
+use super::SqliteLocation;
+
+/// The SQLite implementation of [`ContactAdapter`].
+pub struct SqliteContact;
+
+impl SqliteContact
+{
+	/// The `address_id`, `email`, `other`, and `phone` columns of a [`Contact`] with some `kind`,
+	/// exactly one of which is not `NULL`.
+	fn columns(kind: &ContactKind) -> (Option<Id>, Option<&str>, Option<&str>, Option<&str>)
+	{
+		(kind.address().map(|l| l.id), kind.email(), kind.other(), kind.phone())
+	}
+}
+
+#[async_trait::async_trait]
+impl Deletable for SqliteContact
+{
+	type Db = Sqlite;
+	type Entity = Contact;
+
+	async fn delete<'connection, 'entity, Conn, Iter>(connection: Conn, entities: Iter) -> Result<()>
+	where
+		Conn: Executor<'connection, Database = Sqlite>,
+		Iter: Iterator<Item = &'entity Contact> + Send,
+		Contact: 'entity,
+	{
+		super::delete(connection, "contact_information", "label", entities.map(|c| c.label.clone()).collect()).await
+	}
+}
+
+#[async_trait::async_trait]
+impl ContactAdapter for SqliteContact
+{
+	async fn create<'connection, Conn>(connection: Conn, kind: ContactKind, label: String) -> Result<Contact>
+	where
+		Conn: Executor<'connection, Database = Sqlite>,
+	{
+		let (address_id, email, other, phone) = Self::columns(&kind);
+		sqlx::query("INSERT INTO contact_information (label, address_id, email, other, phone) VALUES (?, ?, ?, ?, ?)")
+			.bind(&label)
+			.bind(address_id)
+			.bind(email)
+			.bind(other)
+			.bind(phone)
+			.execute(connection)
+			.await?;
+
+		Ok(Contact { kind, label })
+	}
+}
+
+#[async_trait::async_trait]
+impl Retrievable for SqliteContact
+{
+	type Db = Sqlite;
+	type Entity = Contact;
+	type Match = MatchContact;
+
+	async fn retrieve(connection: &Pool<Sqlite>, where_clause: MatchContact) -> Result<Vec<Contact>>
+	{
+		let (locations, rows) = futures::try_join!(
+			SqliteLocation::select(connection),
+			connection
+				.fetch_all("SELECT label, address_id, email, other, phone FROM contact_information ORDER BY label"),
+		)?;
+
+		let contacts = rows.into_iter().map(|row| {
+			let kind = match row.try_get::<Option<Id>, _>("address_id")?
+			{
+				Some(id) => ContactKind::Address(locations.get(&id).cloned().ok_or(Error::RowNotFound)?),
+				None => match (row.try_get("email")?, row.try_get("other")?, row.try_get("phone")?)
+				{
+					(Some(email), ..) => ContactKind::Email(email),
+					(_, Some(other), _) => ContactKind::Other(other),
+					(.., Some(phone)) => ContactKind::Phone(phone),
+					(None, None, None) => return Err(Error::ColumnNotFound("address_id".into())),
+				},
+			};
+
+			Ok(Contact { kind, label: row.try_get("label")? })
+		});
+
+		super::filter(contacts.collect::<Result<Vec<_>>>()?, &where_clause)
+	}
+}
+
+#[async_trait::async_trait]
+impl Updatable for SqliteContact
+{
+	type Db = Sqlite;
+	type Entity = Contact;
+
+	async fn update<'entity, Iter>(connection: &mut Transaction<Sqlite>, entities: Iter) -> Result<()>
+	where
+		Iter: Clone + Iterator<Item = &'entity Contact> + Send,
+		Contact: 'entity,
+	{
+		for contact in entities
+		{
+			let (address_id, email, other, phone) = Self::columns(&contact.kind);
+			sqlx::query(
+				"UPDATE contact_information SET address_id = ?, email = ?, other = ?, phone = ? WHERE label = ?",
+			)
+			.bind(address_id)
+			.bind(email)
+			.bind(other)
+			.bind(phone)
+			.bind(&contact.label)
+			.execute(&mut *connection)
+			.await?;
+		}
+
+		Ok(())
+	}
+}
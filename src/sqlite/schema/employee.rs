@@ -0,0 +1,109 @@
+use std::collections::BTreeMap;
+
+use sqlx::{Executor, Pool, Result, Row, Sqlite, Transaction};
+use winvoice_adapter::{schema::EmployeeAdapter, Deletable, Retrievable, Updatable};
+use winvoice_match::MatchEmployee;
+use winvoice_schema::{Employee, Id};
+
+/// The SQLite implementation of [`EmployeeAdapter`].
+pub struct SqliteEmployee;
+
+impl SqliteEmployee
+{
+	/// Every [`Employee`] in the store, by `id`.
+	pub(super) async fn select(connection: &Pool<Sqlite>) -> Result<BTreeMap<Id, Employee>>
+	{
+		connection
+			.fetch_all("SELECT id, name, status, title FROM employees")
+			.await?
+			.into_iter()
+			.map(|row| {
+				let id = row.try_get("id")?;
+				Ok((id, Employee {
+					id,
+					name: row.try_get("name")?,
+					status: row.try_get("status")?,
+					title: row.try_get("title")?,
+				}))
+			})
+			.collect()
+	}
+}
+
+#[async_trait::async_trait]
+impl Deletable for SqliteEmployee
+{
+	type Db = Sqlite;
+	type Entity = Employee;
+
+	async fn delete<'connection, 'entity, Conn, Iter>(connection: Conn, entities: Iter) -> Result<()>
+	where
+		Conn: Executor<'connection, Database = Sqlite>,
+		Iter: Iterator<Item = &'entity Employee> + Send,
+		Employee: 'entity,
+	{
+		super::delete(connection, "employees", "id", entities.map(|e| e.id).collect()).await
+	}
+}
+
+#[async_trait::async_trait]
+impl EmployeeAdapter for SqliteEmployee
+{
+	async fn create<'connection, Conn>(
+		connection: Conn,
+		name: String,
+		status: String,
+		title: String,
+	) -> Result<Employee>
+	where
+		Conn: Executor<'connection, Database = Sqlite>,
+	{
+		let result = sqlx::query("INSERT INTO employees (name, status, title) VALUES (?, ?, ?)")
+			.bind(&name)
+			.bind(&status)
+			.bind(&title)
+			.execute(connection)
+			.await?;
+
+		Ok(Employee { id: result.last_insert_rowid(), name, status, title })
+	}
+}
+
+#[async_trait::async_trait]
+impl Retrievable for SqliteEmployee
+{
+	type Db = Sqlite;
+	type Entity = Employee;
+	type Match = MatchEmployee;
+
+	async fn retrieve(connection: &Pool<Sqlite>, where_clause: MatchEmployee) -> Result<Vec<Employee>>
+	{
+		Self::select(connection).await.and_then(|employees| super::filter(employees.into_values(), &where_clause))
+	}
+}
+
+#[async_trait::async_trait]
+impl Updatable for SqliteEmployee
+{
+	type Db = Sqlite;
+	type Entity = Employee;
+
+	async fn update<'entity, Iter>(connection: &mut Transaction<Sqlite>, entities: Iter) -> Result<()>
+	where
+		Iter: Clone + Iterator<Item = &'entity Employee> + Send,
+		Employee: 'entity,
+	{
+		for employee in entities
+		{
+			sqlx::query("UPDATE employees SET name = ?, status = ?, title = ? WHERE id = ?")
+				.bind(&employee.name)
+				.bind(&employee.status)
+				.bind(&employee.title)
+				.bind(employee.id)
+				.execute(&mut *connection)
+				.await?;
+		}
+
+		Ok(())
+	}
+}
@@ -0,0 +1,133 @@
+use std::collections::BTreeMap;
+
+use money2::Money;
+use sqlx::{sqlite::SqliteRow, Executor, Pool, Result, Row, Sqlite, Transaction};
+use winvoice_adapter::{schema::ExpensesAdapter, Deletable, Retrievable, Updatable};
+use winvoice_match::MatchExpense;
+use winvoice_schema::{Expense, Id};
+
+/// The SQLite implementation of [`ExpensesAdapter`].
+pub struct SqliteExpenses;
+
+impl SqliteExpenses
+{
+	/// Make an [`Expense`] out of a `row` of the `expenses` table.
+	fn from_row(row: &SqliteRow) -> Result<Expense>
+	{
+		Ok(Expense {
+			id: row.try_get("id")?,
+			category: row.try_get("category")?,
+			cost: super::parse(row, "cost")?,
+			description: row.try_get("description")?,
+			timesheet_id: row.try_get("timesheet_id")?,
+		})
+	}
+
+	/// Every [`Expense`] in the store, by the `id` of the `Timesheet` it was incurred during.
+	pub(super) async fn select(connection: &Pool<Sqlite>) -> Result<BTreeMap<Id, Vec<Expense>>>
+	{
+		connection
+			.fetch_all("SELECT id, timesheet_id, category, cost, description FROM expenses ORDER BY id")
+			.await?
+			.iter()
+			.try_fold(BTreeMap::<_, Vec<_>>::new(), |mut expenses, row| {
+				let expense = Self::from_row(row)?;
+				expenses.entry(expense.timesheet_id).or_default().push(expense);
+				Ok(expenses)
+			})
+	}
+}
+
+#[async_trait::async_trait]
+impl Deletable for SqliteExpenses
+{
+	type Db = Sqlite;
+	type Entity = Expense;
+
+	async fn delete<'connection, 'entity, Conn, Iter>(connection: Conn, entities: Iter) -> Result<()>
+	where
+		Conn: Executor<'connection, Database = Sqlite>,
+		Iter: Iterator<Item = &'entity Expense> + Send,
+		Expense: 'entity,
+	{
+		super::delete(connection, "expenses", "id", entities.map(|x| x.id).collect()).await
+	}
+}
+
+#[async_trait::async_trait]
+impl ExpensesAdapter for SqliteExpenses
+{
+	async fn create<'connection, Conn>(
+		connection: Conn,
+		expenses: Vec<(String, Money, String)>,
+		timesheet_id: Id,
+	) -> Result<Vec<Expense>>
+	where
+		Conn: Executor<'connection, Database = Sqlite>,
+	{
+		if expenses.is_empty()
+		{
+			return Ok(Vec::new());
+		}
+
+		let statement = format!(
+			"INSERT INTO expenses (timesheet_id, category, cost, description) VALUES {} RETURNING id, timesheet_id, \
+			 category, cost, description",
+			vec!["(?, ?, ?, ?)"; expenses.len()].join(", "),
+		);
+
+		let rows = expenses
+			.into_iter()
+			.fold(sqlx::query(&statement), |query, (category, cost, description)| {
+				query.bind(timesheet_id).bind(category).bind(cost.to_string()).bind(description)
+			})
+			.fetch_all(connection)
+			.await?;
+
+		let mut created = rows.iter().map(Self::from_row).collect::<Result<Vec<_>>>()?;
+		created.sort_by_key(|x| x.id);
+		Ok(created)
+	}
+}
+
+#[async_trait::async_trait]
+impl Retrievable for SqliteExpenses
+{
+	type Db = Sqlite;
+	type Entity = Expense;
+	type Match = MatchExpense;
+
+	async fn retrieve(connection: &Pool<Sqlite>, where_clause: MatchExpense) -> Result<Vec<Expense>>
+	{
+		Self::select(connection)
+			.await
+			.and_then(|expenses| super::filter(expenses.into_values().flatten(), &where_clause))
+	}
+}
+
+#[async_trait::async_trait]
+impl Updatable for SqliteExpenses
+{
+	type Db = Sqlite;
+	type Entity = Expense;
+
+	async fn update<'entity, Iter>(connection: &mut Transaction<Sqlite>, entities: Iter) -> Result<()>
+	where
+		Iter: Clone + Iterator<Item = &'entity Expense> + Send,
+		Expense: 'entity,
+	{
+		for expense in entities
+		{
+			sqlx::query("UPDATE expenses SET timesheet_id = ?, category = ?, cost = ?, description = ? WHERE id = ?")
+				.bind(expense.timesheet_id)
+				.bind(&expense.category)
+				.bind(expense.cost.to_string())
+				.bind(&expense.description)
+				.bind(expense.id)
+				.execute(&mut *connection)
+				.await?;
+		}
+
+		Ok(())
+	}
+}
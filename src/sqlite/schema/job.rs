@@ -0,0 +1,157 @@
+use core::time::Duration;
+use std::collections::BTreeMap;
+
+use sqlx::{Error, Executor, Pool, Result, Row, Sqlite, Transaction};
+use winvoice_adapter::{schema::JobAdapter, Deletable, Retrievable, Updatable};
+use winvoice_match::MatchJob;
+use winvoice_schema::{
+	chrono::{DateTime, Utc},
+	Id,
+	Invoice,
+	InvoiceDate,
+	Job,
+	Organization,
+};
+
+use super::SqliteOrganization;
+
+/// The SQLite implementation of [`JobAdapter`].
+pub struct SqliteJob;
+
+impl SqliteJob
+{
+	/// Every [`Job`] in the store, by `id`.
+	pub(super) async fn select(connection: &Pool<Sqlite>) -> Result<BTreeMap<Id, Job>>
+	{
+		let (organizations, rows) = futures::try_join!(
+			SqliteOrganization::select(connection),
+			connection.fetch_all(
+				"SELECT id, client_id, date_close, date_open, increment, invoice_date_issued, invoice_date_paid, \
+				 invoice_hourly_rate, notes, objectives FROM jobs"
+			),
+		)?;
+
+		rows.into_iter()
+			.map(|row| {
+				let id = row.try_get("id")?;
+				let client_id = row.try_get::<Id, _>("client_id")?;
+				let issued = row.try_get::<Option<DateTime<Utc>>, _>("invoice_date_issued")?;
+				let paid = row.try_get("invoice_date_paid")?;
+
+				Ok((id, Job {
+					client: organizations.get(&client_id).cloned().ok_or(Error::RowNotFound)?,
+					date_close: row.try_get("date_close")?,
+					date_open: row.try_get("date_open")?,
+					id,
+					increment: super::parse::<humantime::Duration>(&row, "increment")?.into(),
+					invoice: Invoice {
+						date: issued.map(|issued| InvoiceDate { issued, paid }),
+						hourly_rate: super::parse(&row, "invoice_hourly_rate")?,
+					},
+					notes: row.try_get("notes")?,
+					objectives: row.try_get("objectives")?,
+				}))
+			})
+			.collect()
+	}
+}
+
+#[async_trait::async_trait]
+impl Deletable for SqliteJob
+{
+	type Db = Sqlite;
+	type Entity = Job;
+
+	async fn delete<'connection, 'entity, Conn, Iter>(connection: Conn, entities: Iter) -> Result<()>
+	where
+		Conn: Executor<'connection, Database = Sqlite>,
+		Iter: Iterator<Item = &'entity Job> + Send,
+		Job: 'entity,
+	{
+		super::delete(connection, "jobs", "id", entities.map(|j| j.id).collect()).await
+	}
+}
+
+#[async_trait::async_trait]
+impl JobAdapter for SqliteJob
+{
+	async fn create<'connection, Conn>(
+		connection: Conn,
+		client: Organization,
+		date_close: Option<DateTime<Utc>>,
+		date_open: DateTime<Utc>,
+		increment: Duration,
+		invoice: Invoice,
+		notes: String,
+		objectives: String,
+	) -> Result<Job>
+	where
+		Conn: Executor<'connection, Database = Sqlite>,
+	{
+		let result = sqlx::query(
+			"INSERT INTO jobs (client_id, date_close, date_open, increment, invoice_date_issued, invoice_date_paid, \
+			 invoice_hourly_rate, notes, objectives) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+		)
+		.bind(client.id)
+		.bind(date_close)
+		.bind(date_open)
+		.bind(humantime::format_duration(increment).to_string())
+		.bind(invoice.date.map(|d| d.issued))
+		.bind(invoice.date.and_then(|d| d.paid))
+		.bind(invoice.hourly_rate.to_string())
+		.bind(&notes)
+		.bind(&objectives)
+		.execute(connection)
+		.await?;
+
+		Ok(Job { client, date_close, date_open, id: result.last_insert_rowid(), increment, invoice, notes, objectives })
+	}
+}
+
+#[async_trait::async_trait]
+impl Retrievable for SqliteJob
+{
+	type Db = Sqlite;
+	type Entity = Job;
+	type Match = MatchJob;
+
+	async fn retrieve(connection: &Pool<Sqlite>, where_clause: MatchJob) -> Result<Vec<Job>>
+	{
+		Self::select(connection).await.and_then(|jobs| super::filter(jobs.into_values(), &where_clause))
+	}
+}
+
+#[async_trait::async_trait]
+impl Updatable for SqliteJob
+{
+	type Db = Sqlite;
+	type Entity = Job;
+
+	async fn update<'entity, Iter>(connection: &mut Transaction<Sqlite>, entities: Iter) -> Result<()>
+	where
+		Iter: Clone + Iterator<Item = &'entity Job> + Send,
+		Job: 'entity,
+	{
+		for job in entities
+		{
+			sqlx::query(
+				"UPDATE jobs SET client_id = ?, date_close = ?, date_open = ?, increment = ?, invoice_date_issued = \
+				 ?, invoice_date_paid = ?, invoice_hourly_rate = ?, notes = ?, objectives = ? WHERE id = ?",
+			)
+			.bind(job.client.id)
+			.bind(job.date_close)
+			.bind(job.date_open)
+			.bind(humantime::format_duration(job.increment).to_string())
+			.bind(job.invoice.date.map(|d| d.issued))
+			.bind(job.invoice.date.and_then(|d| d.paid))
+			.bind(job.invoice.hourly_rate.to_string())
+			.bind(&job.notes)
+			.bind(&job.objectives)
+			.bind(job.id)
+			.execute(&mut *connection)
+			.await?;
+		}
+
+		Ok(())
+	}
+}
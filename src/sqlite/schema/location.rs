@@ -0,0 +1,107 @@
+use std::collections::BTreeMap;
+
+use sqlx::{Error, Executor, Pool, Result, Row, Sqlite, Transaction};
+use winvoice_adapter::{schema::LocationAdapter, Deletable, Retrievable, Updatable};
+use winvoice_match::MatchLocation;
+use winvoice_schema::{Id, Location};
+
+/// The SQLite implementation of [`LocationAdapter`].
+pub struct SqliteLocation;
+
+impl SqliteLocation
+{
+	/// Every [`Location`] in the store, by `id`.
+	pub(super) async fn select(connection: &Pool<Sqlite>) -> Result<BTreeMap<Id, Location>>
+	{
+		/// Build the [`Location`] with `id` out of the `rows`, along with its outer [`Location`]s.
+		fn build(id: Id, rows: &BTreeMap<Id, (Option<Id>, String)>) -> Result<Location>
+		{
+			let (outer_id, name) = rows.get(&id).ok_or(Error::RowNotFound)?;
+			Ok(Location {
+				id,
+				name: name.clone(),
+				outer: outer_id.map(|outer| build(outer, rows).map(Box::new)).transpose()?,
+			})
+		}
+
+		let rows = connection
+			.fetch_all("SELECT id, outer_id, name FROM locations")
+			.await?
+			.into_iter()
+			.map(|row| Ok((row.try_get("id")?, (row.try_get("outer_id")?, row.try_get("name")?))))
+			.collect::<Result<BTreeMap<_, _>>>()?;
+
+		rows.keys().map(|id| build(*id, &rows).map(|location| (*id, location))).collect()
+	}
+}
+
+#[async_trait::async_trait]
+impl Deletable for SqliteLocation
+{
+	type Db = Sqlite;
+	type Entity = Location;
+
+	async fn delete<'connection, 'entity, Conn, Iter>(connection: Conn, entities: Iter) -> Result<()>
+	where
+		Conn: Executor<'connection, Database = Sqlite>,
+		Iter: Iterator<Item = &'entity Location> + Send,
+		Location: 'entity,
+	{
+		super::delete(connection, "locations", "id", entities.map(|l| l.id).collect()).await
+	}
+}
+
+#[async_trait::async_trait]
+impl LocationAdapter for SqliteLocation
+{
+	async fn create<'connection, Conn>(connection: Conn, name: String, outer: Option<Location>) -> Result<Location>
+	where
+		Conn: Executor<'connection, Database = Sqlite>,
+	{
+		let result = sqlx::query("INSERT INTO locations (outer_id, name) VALUES (?, ?)")
+			.bind(outer.as_ref().map(|o| o.id))
+			.bind(&name)
+			.execute(connection)
+			.await?;
+
+		Ok(Location { id: result.last_insert_rowid(), name, outer: outer.map(Box::new) })
+	}
+}
+
+#[async_trait::async_trait]
+impl Retrievable for SqliteLocation
+{
+	type Db = Sqlite;
+	type Entity = Location;
+	type Match = MatchLocation;
+
+	async fn retrieve(connection: &Pool<Sqlite>, where_clause: MatchLocation) -> Result<Vec<Location>>
+	{
+		Self::select(connection).await.and_then(|locations| super::filter(locations.into_values(), &where_clause))
+	}
+}
+
+#[async_trait::async_trait]
+impl Updatable for SqliteLocation
+{
+	type Db = Sqlite;
+	type Entity = Location;
+
+	async fn update<'entity, Iter>(connection: &mut Transaction<Sqlite>, entities: Iter) -> Result<()>
+	where
+		Iter: Clone + Iterator<Item = &'entity Location> + Send,
+		Location: 'entity,
+	{
+		for location in entities
+		{
+			sqlx::query("UPDATE locations SET outer_id = ?, name = ? WHERE id = ?")
+				.bind(location.outer.as_ref().map(|o| o.id))
+				.bind(&location.name)
+				.bind(location.id)
+				.execute(&mut *connection)
+				.await?;
+		}
+
+		Ok(())
+	}
+}
@@ -0,0 +1,108 @@
+use std::collections::BTreeMap;
+
+use sqlx::{Error, Executor, Pool, Result, Row, Sqlite, Transaction};
+use winvoice_adapter::{schema::OrganizationAdapter, Deletable, Retrievable, Updatable};
+use winvoice_match::MatchOrganization;
+use winvoice_schema::{Id, Location, Organization};
+
+use super::SqliteLocation;
+
+/// The SQLite implementation of [`OrganizationAdapter`].
+pub struct SqliteOrganization;
+
+impl SqliteOrganization
+{
+	/// Every [`Organization`] in the store, by `id`.
+	pub(super) async fn select(connection: &Pool<Sqlite>) -> Result<BTreeMap<Id, Organization>>
+	{
+		let (locations, rows) = futures::try_join!(
+			SqliteLocation::select(connection),
+			connection.fetch_all("SELECT id, location_id, name FROM organizations"),
+		)?;
+
+		rows.into_iter()
+			.map(|row| {
+				let id = row.try_get("id")?;
+				let location_id = row.try_get::<Id, _>("location_id")?;
+				Ok((id, Organization {
+					id,
+					location: locations.get(&location_id).cloned().ok_or(Error::RowNotFound)?,
+					name: row.try_get("name")?,
+				}))
+			})
+			.collect()
+	}
+}
+
+#[async_trait::async_trait]
+impl Deletable for SqliteOrganization
+{
+	type Db = Sqlite;
+	type Entity = Organization;
+
+	async fn delete<'connection, 'entity, Conn, Iter>(connection: Conn, entities: Iter) -> Result<()>
+	where
+		Conn: Executor<'connection, Database = Sqlite>,
+		Iter: Iterator<Item = &'entity Organization> + Send,
+		Organization: 'entity,
+	{
+		super::delete(connection, "organizations", "id", entities.map(|o| o.id).collect()).await
+	}
+}
+
+#[async_trait::async_trait]
+impl OrganizationAdapter for SqliteOrganization
+{
+	async fn create<'connection, Conn>(connection: Conn, location: Location, name: String) -> Result<Organization>
+	where
+		Conn: Executor<'connection, Database = Sqlite>,
+	{
+		let result = sqlx::query("INSERT INTO organizations (location_id, name) VALUES (?, ?)")
+			.bind(location.id)
+			.bind(&name)
+			.execute(connection)
+			.await?;
+
+		Ok(Organization { id: result.last_insert_rowid(), location, name })
+	}
+}
+
+#[async_trait::async_trait]
+impl Retrievable for SqliteOrganization
+{
+	type Db = Sqlite;
+	type Entity = Organization;
+	type Match = MatchOrganization;
+
+	async fn retrieve(connection: &Pool<Sqlite>, where_clause: MatchOrganization) -> Result<Vec<Organization>>
+	{
+		Self::select(connection)
+			.await
+			.and_then(|organizations| super::filter(organizations.into_values(), &where_clause))
+	}
+}
+
+#[async_trait::async_trait]
+impl Updatable for SqliteOrganization
+{
+	type Db = Sqlite;
+	type Entity = Organization;
+
+	async fn update<'entity, Iter>(connection: &mut Transaction<Sqlite>, entities: Iter) -> Result<()>
+	where
+		Iter: Clone + Iterator<Item = &'entity Organization> + Send,
+		Organization: 'entity,
+	{
+		for organization in entities
+		{
+			sqlx::query("UPDATE organizations SET location_id = ?, name = ? WHERE id = ?")
+				.bind(organization.location.id)
+				.bind(&organization.name)
+				.bind(organization.id)
+				.execute(&mut *connection)
+				.await?;
+		}
+
+		Ok(())
+	}
+}
@@ -0,0 +1,139 @@
+use money2::Money;
+use sqlx::{Error, Executor, Pool, Result, Row, Sqlite, Transaction};
+use winvoice_adapter::{
+	schema::{ExpensesAdapter, TimesheetAdapter},
+	Deletable,
+	Retrievable,
+	Updatable,
+};
+use winvoice_match::MatchTimesheet;
+use winvoice_schema::{
+	chrono::{DateTime, Utc},
+	Employee,
+	Id,
+	Job,
+	Timesheet,
+};
+
+use super::{SqliteEmployee, SqliteExpenses, SqliteJob};
+
+/// The SQLite implementation of [`TimesheetAdapter`].
+pub struct SqliteTimesheet;
+
+#[async_trait::async_trait]
+impl Deletable for SqliteTimesheet
+{
+	type Db = Sqlite;
+	type Entity = Timesheet;
+
+	async fn delete<'connection, 'entity, Conn, Iter>(connection: Conn, entities: Iter) -> Result<()>
+	where
+		Conn: Executor<'connection, Database = Sqlite>,
+		Iter: Iterator<Item = &'entity Timesheet> + Send,
+		Timesheet: 'entity,
+	{
+		super::delete(connection, "timesheets", "id", entities.map(|t| t.id).collect()).await
+	}
+}
+
+#[async_trait::async_trait]
+impl Retrievable for SqliteTimesheet
+{
+	type Db = Sqlite;
+	type Entity = Timesheet;
+	type Match = MatchTimesheet;
+
+	async fn retrieve(connection: &Pool<Sqlite>, where_clause: MatchTimesheet) -> Result<Vec<Timesheet>>
+	{
+		let (employees, mut expenses, jobs, rows) = futures::try_join!(
+			SqliteEmployee::select(connection),
+			SqliteExpenses::select(connection),
+			SqliteJob::select(connection),
+			connection.fetch_all(
+				"SELECT id, employee_id, job_id, time_begin, time_end, work_notes FROM timesheets ORDER BY id"
+			),
+		)?;
+
+		let timesheets = rows.into_iter().map(|row| {
+			let id = row.try_get("id")?;
+			let employee_id = row.try_get::<Id, _>("employee_id")?;
+			let job_id = row.try_get::<Id, _>("job_id")?;
+
+			Ok(Timesheet {
+				id,
+				employee: employees.get(&employee_id).cloned().ok_or(Error::RowNotFound)?,
+				expenses: expenses.remove(&id).unwrap_or_default(),
+				job: jobs.get(&job_id).cloned().ok_or(Error::RowNotFound)?,
+				time_begin: row.try_get("time_begin")?,
+				time_end: row.try_get("time_end")?,
+				work_notes: row.try_get("work_notes")?,
+			})
+		});
+
+		super::filter(timesheets.collect::<Result<Vec<_>>>()?, &where_clause)
+	}
+}
+
+#[async_trait::async_trait]
+impl TimesheetAdapter for SqliteTimesheet
+{
+	async fn create(
+		connection: &mut Transaction<Sqlite>,
+		employee: Employee,
+		expenses: Vec<(String, Money, String)>,
+		job: Job,
+		time_begin: DateTime<Utc>,
+		time_end: Option<DateTime<Utc>>,
+		work_notes: String,
+	) -> Result<Timesheet>
+	{
+		let result = sqlx::query(
+			"INSERT INTO timesheets (employee_id, job_id, time_begin, time_end, work_notes) VALUES (?, ?, ?, ?, ?) ",
+		)
+		.bind(employee.id)
+		.bind(job.id)
+		.bind(time_begin)
+		.bind(time_end)
+		.bind(&work_notes)
+		.execute(&mut *connection)
+		.await?;
+
+		let id = result.last_insert_rowid();
+		let expenses = SqliteExpenses::create(&mut *connection, expenses, id).await?;
+
+		Ok(Timesheet { id, employee, expenses, job, time_begin, time_end, work_notes })
+	}
+}
+
+#[async_trait::async_trait]
+impl Updatable for SqliteTimesheet
+{
+	type Db = Sqlite;
+	type Entity = Timesheet;
+
+	async fn update<'entity, Iter>(connection: &mut Transaction<Sqlite>, entities: Iter) -> Result<()>
+	where
+		Iter: Clone + Iterator<Item = &'entity Timesheet> + Send,
+		Timesheet: 'entity,
+	{
+		for timesheet in entities
+		{
+			sqlx::query(
+				"UPDATE timesheets SET employee_id = ?, job_id = ?, time_begin = ?, time_end = ?, work_notes = ? \
+				 WHERE id = ?",
+			)
+			.bind(timesheet.employee.id)
+			.bind(timesheet.job.id)
+			.bind(timesheet.time_begin)
+			.bind(timesheet.time_end)
+			.bind(&timesheet.work_notes)
+			.bind(timesheet.id)
+			.execute(&mut *connection)
+			.await?;
+
+			SqliteExpenses::update(connection, timesheet.expenses.iter()).await?;
+		}
+
+		Ok(())
+	}
+}
@@ -1,17 +1,48 @@
 //! Misc utilities for Winvoice.
 
+mod billable;
 mod identifiable;
 
-pub use identifiable::Identifiable;
-use winvoice_schema::chrono::{DateTime, Datelike, Local, NaiveDateTime, TimeZone, Timelike, Utc};
 #[cfg(test)]
-use {
-	serde::Serialize,
-	serde_yaml as yaml,
-	std::{env, fs, path::Path},
+use std::env;
+use std::{
+	fs,
+	io,
+	path::{Path, PathBuf},
 };
 
-use crate::fmt;
+pub use billable::Billable;
+pub use identifiable::Identifiable;
+use serde::de::DeserializeOwned;
+#[cfg(test)]
+use serde::Serialize;
+use serde_yaml as yaml;
+use winvoice_schema::chrono::{DateTime, Datelike, Local, NaiveDateTime, TimeZone, Timelike, Utc};
+
+use crate::{fmt, input::Result};
+
+/// The path to the file with some `name` in the directory where Winvoice keeps data which is not
+/// part of any store.
+///
+/// # Errors
+///
+/// * When the platform does not have a data directory (e.g. `$HOME` is not set).
+pub(crate) fn data_path(name: &str) -> io::Result<PathBuf>
+{
+	let mut path = dirs::data_dir().ok_or_else(|| {
+		io::Error::new(
+			io::ErrorKind::NotFound,
+			format!(
+				"There is no data directory on this platform in which to keep {}; specify the file to use instead",
+				fmt::quoted(name),
+			),
+		)
+	})?;
+
+	path.push("winvoice");
+	path.push(name);
+	Ok(path)
+}
 
 /// Load the `$DATABASE_URL` from a `.env` file, or an environment variable.
 #[cfg(test)]
@@ -20,6 +51,26 @@ pub(crate) fn database_url() -> dotenvy::Result<String>
 	dotenvy::var("DATABASE_URL")
 }
 
+/// The Postgres database at the [`database_url`], [migrated](crate::migration) for use with
+/// Winvoice.
+///
+/// # Panics
+///
+/// If there is no [`database_url`], or the database cannot be migrated.
+#[cfg(all(feature = "postgres", test))]
+pub(crate) async fn postgres_database() -> String
+{
+	use sqlx::{Connection, PgConnection, Postgres};
+
+	use crate::{config::Adapter, migration};
+
+	let database_url = database_url().unwrap();
+	let mut connection = PgConnection::connect(&database_url).await.unwrap();
+	migration::upgrade::<Postgres>(&mut connection, Adapter::Postgres).await.unwrap();
+
+	database_url
+}
+
 /// A temporary YAML file which can be used to store data regarding a specific `test`.
 #[cfg(test)]
 pub(crate) fn temp_file<T>(test: &str) -> std::path::PathBuf
@@ -35,12 +86,68 @@ pub(crate) fn temp_file<T>(test: &str) -> std::path::PathBuf
 	parent
 }
 
+/// A temporary SQLite database, [initialized](winvoice_adapter::Initializable) and
+/// [migrated](crate::migration) for use with Winvoice, which can be used to store data regarding a
+/// specific `test`.
+///
+/// # Panics
+///
+/// If the database cannot be created or initialized.
+#[cfg(all(feature = "sqlite", test))]
+pub(crate) async fn temp_sqlite_database<T>(test: &str) -> String
+{
+	use sqlx::{Connection, Sqlite, SqliteConnection};
+	use winvoice_adapter::Initializable;
+
+	use crate::{
+		config::Adapter,
+		migration,
+		sqlite::{self, SqliteSchema},
+	};
+
+	let mut filepath = temp_file::<T>(test);
+
+	// the journals of a previous run must go too, or SQLite will apply them to the new database
+	["db", "db-shm", "db-wal"].into_iter().map(|extension| filepath.with_extension(extension)).for_each(|f| {
+		if f.is_file()
+		{
+			fs::remove_file(f).unwrap();
+		}
+	});
+
+	filepath.set_extension("db");
+
+	let database_url = format!("sqlite://{}?mode=rwc", filepath.display());
+	let mut connection =
+		SqliteConnection::connect_with(&sqlite::connect_options(&database_url).unwrap()).await.unwrap();
+	SqliteSchema::init(&mut connection).await.unwrap();
+	migration::upgrade::<Sqlite>(&mut connection, Adapter::Sqlite).await.unwrap();
+
+	database_url
+}
+
 /// Create a [`DateTime<Utc>`] out of some [`Local`] [`NaiveDateTime`].
 pub(crate) fn naive_local_datetime_to_utc(d: NaiveDateTime) -> DateTime<Utc>
 {
 	Local.ymd(d.year(), d.month(), d.day()).and_hms(d.hour(), d.minute(), d.second()).into()
 }
 
+/// Read some `T` from the [YAML][yaml] file at `filepath`, or its [`Default`] if there is no
+/// file.
+///
+/// [yaml]: serde_yaml::from_str
+pub(crate) fn read_data<T>(filepath: &Path) -> Result<T>
+where
+	T: Default + DeserializeOwned,
+{
+	match fs::read_to_string(filepath)
+	{
+		Ok(contents) => yaml::from_str(&contents).map_err(Into::into),
+		Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(T::default()),
+		Err(e) => Err(e.into()),
+	}
+}
+
 /// Indicate with [`println!`] that a value of type `Actioned` — identified by `id` — has been
 /// `action`ed.
 pub(super) fn report_action<Actioned>(action: &str, actioned: &Actioned)
@@ -0,0 +1,45 @@
+use money2::Decimal;
+use winvoice_schema::{Money, Timesheet};
+
+/// Work which is billed to a client by the hour.
+pub trait Billable
+{
+	/// The number of hours worked, rounded up to the nearest increment.
+	fn hours(&self) -> Decimal;
+
+	/// The cost of the [`hours`](Billable::hours) worked.
+	fn labor(&self) -> Money;
+
+	/// The cost of the [`labor`](Billable::labor), plus any expenses.
+	///
+	/// The expenses are assumed to be in the same currency as the hourly rate (e.g. after an
+	/// [exchange](money2::Exchange)).
+	fn total(&self) -> Money;
+}
+
+impl Billable for Timesheet
+{
+	fn hours(&self) -> Decimal
+	{
+		let seconds = self.time_end.map_or(0, |end| (end - self.time_begin).num_seconds().max(0).unsigned_abs());
+		let rounded = match self.job.increment.as_secs()
+		{
+			0 => seconds,
+			increment => seconds.div_ceil(increment) * increment,
+		};
+
+		Decimal::from(rounded) / Decimal::from(3600)
+	}
+
+	fn labor(&self) -> Money
+	{
+		let rate = self.job.invoice.hourly_rate;
+		Money { amount: (rate.amount * self.hours()).round_dp(2), ..rate }
+	}
+
+	fn total(&self) -> Money
+	{
+		let labor = self.labor();
+		Money { amount: self.expenses.iter().fold(labor.amount, |sum, x| sum + x.cost.amount), ..labor }
+	}
+}
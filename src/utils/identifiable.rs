@@ -1,6 +1,6 @@
 use winvoice_schema::{Contact, Employee, Expense, Job, Location, Organization, Timesheet};
 
-use crate::fmt;
+use crate::{fmt, payments::Payment};
 
 pub trait Identifiable
 {
@@ -41,6 +41,7 @@ macro_rules! impl_using_id_and_name {
 
 impl_using_id!(Expense);
 impl_using_id!(Job);
+impl_using_id!(Payment);
 impl_using_id!(Timesheet);
 impl_using_id_and_name!(Employee);
 impl_using_id_and_name!(Location);